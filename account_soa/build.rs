@@ -1,3 +1,4 @@
-
 fn main() {
+    // Migrations are embedded by sqlx::migrate!() macro.
+    println!("cargo:rerun-if-changed=migrations");
 }
@@ -41,6 +41,40 @@ create domain ACCOUNT_ID as UUID;
 
 create domain AMOUNT as NUMERIC(15,6);
 
+-- The same as AccountStatus in gRPC proto (mvv.client.search.proto)
+create domain ACCOUNT_STATUS as VARCHAR(16)
+    check (value in ('PENDING', 'ACTIVE', 'SUSPENDED', 'CLOSED'));
+
+-- EXPIRED status is not stored, it is calculated for ACTIVE holds by EXPIRES_AT.
+create domain HOLD_STATUS as VARCHAR(16)
+    check (value in ('ACTIVE', 'CAPTURED', 'RELEASED'));
+
+create domain PENDING_TRANSFER_STATUS as VARCHAR(16)
+    check (value in ('PENDING', 'APPROVED', 'REJECTED'));
+
+create domain STANDING_ORDER_PERIOD as VARCHAR(16)
+    check (value in ('DAILY', 'WEEKLY', 'MONTHLY'));
+
+create domain WEBHOOK_EVENT_TYPE as VARCHAR(32)
+    check (value in ('TRANSFER_INCOMING', 'TRANSFER_OUTGOING'));
+
+-- DEAD (dead letter) delivery is not retried anymore.
+create domain WEBHOOK_DELIVERY_STATUS as VARCHAR(16)
+    check (value in ('PENDING', 'DELIVERED', 'DEAD'));
+
+-- The same as ClientFeature (Standard/Business/SuperBusiness) in account_web.
+create domain CLIENT_TIER as VARCHAR(16)
+    check (value in ('STANDARD', 'BUSINESS', 'SUPER_BUSINESS'));
+
+create domain DAY_COUNT_CONVENTION as VARCHAR(16)
+    check (value in ('ACT_365', 'THIRTY_360'));
+
+-- Accrued (not rounded) interest.
+create domain ACCRUED_INTEREST as NUMERIC(25,10);
+
+create domain EXCHANGE_RATE as NUMERIC(20,10)
+    check (value > 0);
+
 -- T O D O: specify all allowed/possible currencies
 create domain CURRENCY as CHAR(3)
     constraint check_valid_currency check (value is not null and value ~ '^[A-Z]{3}$');
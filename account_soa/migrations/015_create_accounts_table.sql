@@ -12,13 +12,22 @@ create table ACCOUNTS
     -- type modifier is not allowed for type "citext"
     -- NAME       CITEXT(256)  not null,
     NAME       VARCHAR(256) not null,
-    AMOUNT     AMOUNT       not null default 0 check (AMOUNT >= 0),
+    AMOUNT     AMOUNT       not null default 0,
+    -- Balance can go down to -OVERDRAFT_LIMIT (in account currency).
+    OVERDRAFT_LIMIT AMOUNT  not null default 0 check (OVERDRAFT_LIMIT >= 0),
     CUR        CURRENCY     not null,
+    STATUS     ACCOUNT_STATUS not null default 'ACTIVE',
+    -- Annual interest rate (in percents), interest is accrued daily and credited monthly.
+    INTEREST_RATE        NUMERIC(7,4) not null default 0 check (INTEREST_RATE >= 0 and INTEREST_RATE < 100),
+    DAY_COUNT_CONVENTION DAY_COUNT_CONVENTION not null default 'ACT_365',
+    -- Accrued, but not yet credited interest (less than minor unit of currency after crediting).
+    ACCRUED_INTEREST     ACCRUED_INTEREST not null default 0 check (ACCRUED_INTEREST >= 0),
     CREATED_AT TIMESTAMPTZ  not null default CURRENT_TIMESTAMP,
     UPDATED_AT TIMESTAMPTZ  not null default CURRENT_TIMESTAMP,
 
     constraint FK_CLIENT_ID foreign key(CLIENT_ID) references CLIENTS(CLIENT_ID),
-    constraint C_ACCOUNT_NAME unique (CLIENT_ID, NAME)
+    constraint C_ACCOUNT_NAME unique (CLIENT_ID, NAME),
+    constraint C_ACCOUNT_AMOUNT check (AMOUNT >= -OVERDRAFT_LIMIT)
     -- check (length(name) >= 1 and length(name) <= 300),
 );
 
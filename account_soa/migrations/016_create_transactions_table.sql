@@ -0,0 +1,34 @@
+
+create table TRANSACTIONS
+(
+    -- It is also used as (keyset) pagination cursor.
+    ID            BIGSERIAL    not null primary key,
+    -- Debit and credit entries of the same transfer share this ID.
+    TRANSFER_ID   UUID         not null,
+    ACCOUNT_ID    ACCOUNT_ID   not null,
+    -- Account on the other side of transfer.
+    COUNTERPARTY_ACCOUNT_ID ACCOUNT_ID,
+    -- Signed value: negative for debit, positive for credit.
+    AMOUNT        AMOUNT       not null,
+    CUR           CURRENCY     not null,
+    BALANCE_AFTER AMOUNT       not null,
+    -- Only for cross-currency transfers: rate (source currency => target currency)
+    -- used for conversion and time when this rate was fixed.
+    EXCHANGE_RATE    EXCHANGE_RATE,
+    EXCHANGE_RATE_AT TIMESTAMPTZ,
+    -- Only for compensating (reversal) entries: ID of reversed (original) transfer.
+    REVERSED_TRANSFER_ID UUID,
+    -- Only for fee entries: ID of transfer for which fee is charged
+    -- (fee is a separate transfer to bank fee account).
+    FEE_FOR_TRANSFER_ID UUID,
+    CREATED_AT    TIMESTAMPTZ  not null default CURRENT_TIMESTAMP,
+
+    constraint FK_ACCOUNT_ID foreign key(ACCOUNT_ID) references ACCOUNTS(ID),
+    constraint FK_COUNTERPARTY_ACCOUNT_ID foreign key(COUNTERPARTY_ACCOUNT_ID) references ACCOUNTS(ID)
+);
+
+create index TRANSACTIONS_ACCOUNT_IDX on TRANSACTIONS(ACCOUNT_ID, CREATED_AT);
+create index TRANSACTIONS_TRANSFER_IDX on TRANSACTIONS(TRANSFER_ID);
+-- Transfer can be reversed only once.
+create unique index TRANSACTIONS_REVERSED_TRANSFER_IDX on TRANSACTIONS(REVERSED_TRANSFER_ID, ACCOUNT_ID)
+    where REVERSED_TRANSFER_ID is not null;
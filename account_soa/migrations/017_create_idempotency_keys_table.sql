@@ -0,0 +1,17 @@
+
+-- Idempotency keys of 'transferAmount' REST operation.
+-- Row is inserted in the same DB transaction as transfer itself,
+-- so key is 'used' only if transfer is really committed.
+create table TRANSFER_IDEMPOTENCY_KEYS
+(
+    CLIENT_ID       CLIENT_ID    not null,
+    IDEMPOTENCY_KEY VARCHAR(255) not null,
+    -- SHA-256 (hex) of normalized request
+    REQUEST_HASH    CHAR(64)     not null,
+    -- Outcome
+    TRANSFER_ID     UUID         not null,
+    CREATED_AT      TIMESTAMPTZ  not null default CURRENT_TIMESTAMP,
+
+    primary key (CLIENT_ID, IDEMPOTENCY_KEY),
+    constraint FK_CLIENT_ID foreign key(CLIENT_ID) references CLIENTS(CLIENT_ID)
+);
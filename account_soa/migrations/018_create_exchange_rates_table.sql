@@ -0,0 +1,13 @@
+
+-- Current exchange rates (they are filled/updated by external process).
+-- 1 FROM_CUR = RATE TO_CUR
+create table EXCHANGE_RATES
+(
+    FROM_CUR   CURRENCY      not null,
+    TO_CUR     CURRENCY      not null,
+    RATE       EXCHANGE_RATE not null,
+    UPDATED_AT TIMESTAMPTZ   not null default CURRENT_TIMESTAMP,
+
+    primary key (FROM_CUR, TO_CUR),
+    check (FROM_CUR <> TO_CUR)
+);
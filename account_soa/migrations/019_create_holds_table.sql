@@ -0,0 +1,30 @@
+
+-- Reserved (not settled yet) amounts of accounts.
+-- Sum of active (and not expired) holds reduces available balance, but not ledger one (ACCOUNTS.AMOUNT).
+create table HOLDS
+(
+    ID              UUID         not null primary key,
+    ACCOUNT_ID      ACCOUNT_ID   not null,
+    -- Authorized amount (in account currency)
+    AMOUNT          AMOUNT       not null check (AMOUNT > 0),
+    CUR             CURRENCY     not null,
+    STATUS          HOLD_STATUS  not null default 'ACTIVE',
+    -- Only for captured hold
+    CAPTURED_AMOUNT AMOUNT       check (CAPTURED_AMOUNT > 0 and CAPTURED_AMOUNT <= AMOUNT),
+    TRANSFER_ID     UUID,
+    EXPIRES_AT      TIMESTAMPTZ  not null,
+    CREATED_AT      TIMESTAMPTZ  not null default CURRENT_TIMESTAMP,
+    UPDATED_AT      TIMESTAMPTZ  not null default CURRENT_TIMESTAMP,
+
+    constraint FK_ACCOUNT_ID foreign key(ACCOUNT_ID) references ACCOUNTS(ID)
+);
+
+create index HOLDS_ACTIVE_ACCOUNT_IDX on HOLDS(ACCOUNT_ID) where STATUS = 'ACTIVE';
+
+-- Sum of active (and not expired) holds of account.
+create function ACCOUNT_HELD_AMOUNT(P_ACCOUNT_ID ACCOUNT_ID) returns NUMERIC
+    language sql stable
+    as $$
+        select coalesce(sum(AMOUNT), 0) from HOLDS
+            where ACCOUNT_ID = P_ACCOUNT_ID and STATUS = 'ACTIVE' and EXPIRES_AT > CURRENT_TIMESTAMP
+    $$;
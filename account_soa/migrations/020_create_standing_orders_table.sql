@@ -0,0 +1,38 @@
+
+-- Recurring (scheduled) transfers of clients.
+-- They are executed by scheduler (only one replica at a time, see pg_try_advisory_lock).
+create table STANDING_ORDERS
+(
+    ID                UUID         not null primary key,
+    CLIENT_ID         CLIENT_ID    not null,
+    FROM_ACCOUNT_ID   ACCOUNT_ID   not null,
+    -- Account of this or another client.
+    TO_IBAN           IBAN         collate ENGLISH_CI not null,
+    -- It is required if 'to' account belongs to another client.
+    BENEFICIARY_NAME  VARCHAR(201),
+    -- In 'from' account currency.
+    AMOUNT            AMOUNT       not null check (AMOUNT > 0),
+    CUR               CURRENCY     not null,
+    PERIOD            STANDING_ORDER_PERIOD not null,
+    -- Time of the first execution, N-th execution time is START_AT + N * PERIOD
+    -- (to avoid shifting of day of month after short months).
+    START_AT          TIMESTAMPTZ  not null,
+    -- Number (N) of the next execution and its time.
+    NEXT_EXECUTION_NO INTEGER      not null default 0 check (NEXT_EXECUTION_NO >= 0),
+    NEXT_EXECUTION_AT TIMESTAMPTZ  not null,
+    -- Retry state of the next execution (only for 'not enough balance' failures).
+    FAILED_ATTEMPTS   INTEGER      not null default 0 check (FAILED_ATTEMPTS >= 0),
+    RETRY_AT          TIMESTAMPTZ,
+    -- Result of the last execution.
+    LAST_EXECUTED_AT  TIMESTAMPTZ,
+    LAST_TRANSFER_ID  UUID,
+    LAST_ERROR        VARCHAR(1000),
+    CREATED_AT        TIMESTAMPTZ  not null default CURRENT_TIMESTAMP,
+    UPDATED_AT        TIMESTAMPTZ  not null default CURRENT_TIMESTAMP,
+
+    constraint FK_CLIENT_ID foreign key(CLIENT_ID) references CLIENTS(CLIENT_ID),
+    constraint FK_FROM_ACCOUNT_ID foreign key(FROM_ACCOUNT_ID) references ACCOUNTS(ID)
+);
+
+create index STANDING_ORDERS_CLIENT_IDX on STANDING_ORDERS(CLIENT_ID);
+create index STANDING_ORDERS_DUE_IDX on STANDING_ORDERS(coalesce(RETRY_AT, NEXT_EXECUTION_AT));
@@ -0,0 +1,15 @@
+
+-- Transfer limits of client tiers (per currency).
+-- If there is no row for tier/currency (or limit is null), transfers are not limited.
+create table TRANSFER_LIMITS
+(
+    CLIENT_TIER         CLIENT_TIER  not null,
+    CUR                 CURRENCY     not null,
+    -- Max amount of one transfer
+    MAX_TRANSFER_AMOUNT AMOUNT       check (MAX_TRANSFER_AMOUNT > 0),
+    -- Max total amount of outgoing transfers for the last 24 hours
+    MAX_DAILY_AMOUNT    AMOUNT       check (MAX_DAILY_AMOUNT > 0),
+    UPDATED_AT          TIMESTAMPTZ  not null default CURRENT_TIMESTAMP,
+
+    primary key (CLIENT_TIER, CUR)
+);
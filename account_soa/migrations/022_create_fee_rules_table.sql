@@ -0,0 +1,29 @@
+
+-- Transfer fee rules (per source account currency and transfer type).
+-- fee = FLAT_FEE + amount * FEE_PERCENT / 100, limited by [MIN_FEE, MAX_FEE].
+-- If there is no rule for currency/type, transfer has no fee.
+create table FEE_RULES
+(
+    CUR            CURRENCY      not null,
+    -- Transfer to account of another client.
+    INTER_CLIENT   BOOL          not null,
+    -- Transfer with currency conversion.
+    CROSS_CURRENCY BOOL          not null,
+    FLAT_FEE       AMOUNT        not null default 0 check (FLAT_FEE >= 0),
+    FEE_PERCENT    NUMERIC(7,4)  not null default 0 check (FEE_PERCENT >= 0 and FEE_PERCENT < 100),
+    MIN_FEE        AMOUNT        check (MIN_FEE >= 0),
+    MAX_FEE        AMOUNT        check (MAX_FEE >= 0),
+    UPDATED_AT     TIMESTAMPTZ   not null default CURRENT_TIMESTAMP,
+
+    primary key (CUR, INTER_CLIENT, CROSS_CURRENCY),
+    check (MIN_FEE is null or MAX_FEE is null or MIN_FEE <= MAX_FEE)
+);
+
+-- Bank accounts which collect fees (one per currency).
+create table BANK_FEE_ACCOUNTS
+(
+    CUR        CURRENCY    not null primary key,
+    ACCOUNT_ID ACCOUNT_ID  not null unique,
+
+    constraint FK_ACCOUNT_ID foreign key(ACCOUNT_ID) references ACCOUNTS(ID)
+);
@@ -0,0 +1,45 @@
+
+-- Transfers with amount above threshold (of amount currency) need approval by another user (maker-checker).
+-- If there is no threshold for currency, transfers are executed immediately.
+create table TRANSFER_APPROVAL_THRESHOLDS
+(
+    CUR        CURRENCY     not null primary key,
+    THRESHOLD  AMOUNT       not null check (THRESHOLD > 0),
+    UPDATED_AT TIMESTAMPTZ  not null default CURRENT_TIMESTAMP
+);
+
+create table PENDING_TRANSFERS
+(
+    ID               UUID         not null primary key,
+    CLIENT_ID        CLIENT_ID    not null,
+    FROM_ACCOUNT_ID  ACCOUNT_ID   not null,
+    TO_IBAN          IBAN         collate ENGLISH_CI not null,
+    BENEFICIARY_NAME VARCHAR(201),
+    -- In 'from' account currency
+    AMOUNT           AMOUNT       not null check (AMOUNT > 0),
+    CUR              CURRENCY     not null,
+    STATUS           PENDING_TRANSFER_STATUS not null default 'PENDING',
+    -- Maker (user who requested transfer)
+    CREATED_BY       VARCHAR(256) not null,
+    -- Checker (user who approved/rejected transfer), it cannot be the same as maker.
+    DECIDED_BY       VARCHAR(256),
+    DECIDED_AT       TIMESTAMPTZ,
+    -- Only for approved (executed) transfer
+    TRANSFER_ID      UUID,
+    REJECT_REASON    VARCHAR(1000),
+    -- Optional 'Idempotency-Key' of transfer request
+    IDEMPOTENCY_KEY  VARCHAR(255),
+    -- SHA-256 (hex) of normalized request
+    REQUEST_HASH     CHAR(64),
+    CREATED_AT       TIMESTAMPTZ  not null default CURRENT_TIMESTAMP,
+    UPDATED_AT       TIMESTAMPTZ  not null default CURRENT_TIMESTAMP,
+
+    constraint FK_CLIENT_ID foreign key(CLIENT_ID) references CLIENTS(CLIENT_ID),
+    constraint FK_FROM_ACCOUNT_ID foreign key(FROM_ACCOUNT_ID) references ACCOUNTS(ID),
+    check (DECIDED_BY is null or lower(DECIDED_BY) <> lower(CREATED_BY)),
+    check ((STATUS = 'APPROVED') = (TRANSFER_ID is not null))
+);
+
+create index PENDING_TRANSFERS_CLIENT_IDX on PENDING_TRANSFERS(CLIENT_ID, STATUS);
+create unique index PENDING_TRANSFERS_IDEMPOTENCY_KEY_IDX on PENDING_TRANSFERS(CLIENT_ID, IDEMPOTENCY_KEY)
+    where IDEMPOTENCY_KEY is not null;
@@ -0,0 +1,20 @@
+-- Daily interest accruals (one row per account and day), re-run for the same day is skipped.
+create table INTEREST_ACCRUALS
+(
+    ACCOUNT_ID           ACCOUNT_ID   not null,
+    ACCRUAL_DATE         DATE         not null,
+    -- End of day balance.
+    BALANCE              AMOUNT       not null,
+    INTEREST_RATE        NUMERIC(7,4) not null,
+    DAY_COUNT_CONVENTION DAY_COUNT_CONVENTION not null,
+    AMOUNT               ACCRUED_INTEREST not null check (AMOUNT >= 0),
+    CUR                  CURRENCY     not null,
+    -- Only for the last day of month (if accrued interest is credited).
+    CREDIT_TRANSFER_ID   UUID,
+    CREDITED_AMOUNT      AMOUNT       check (CREDITED_AMOUNT > 0),
+    CREATED_AT           TIMESTAMPTZ  not null default CURRENT_TIMESTAMP,
+
+    primary key (ACCOUNT_ID, ACCRUAL_DATE),
+    constraint FK_ACCOUNT_ID foreign key(ACCOUNT_ID) references ACCOUNTS(ID),
+    check ((CREDIT_TRANSFER_ID is null) = (CREDITED_AMOUNT is null))
+);
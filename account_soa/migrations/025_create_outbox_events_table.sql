@@ -0,0 +1,19 @@
+
+-- Transactional outbox: domain events are inserted in the same DB transaction as account changes,
+-- and then they are published by dispatcher (at-least-once, in ID order).
+create table OUTBOX_EVENTS
+(
+    ID              BIGSERIAL    not null primary key,
+    -- Consumers should use it to skip duplicates.
+    EVENT_ID        UUID         not null unique,
+    EVENT_TYPE      VARCHAR(100) not null,
+    PAYLOAD         JSONB        not null,
+    CREATED_AT      TIMESTAMPTZ  not null default CURRENT_TIMESTAMP,
+    PUBLISHED_AT    TIMESTAMPTZ,
+    -- Retry state of failed publishing.
+    ATTEMPTS        INTEGER      not null default 0 check (ATTEMPTS >= 0),
+    NEXT_ATTEMPT_AT TIMESTAMPTZ  not null default CURRENT_TIMESTAMP,
+    LAST_ERROR      VARCHAR(1000)
+);
+
+create index OUTBOX_EVENTS_UNPUBLISHED_IDX on OUTBOX_EVENTS(ID) where PUBLISHED_AT is null;
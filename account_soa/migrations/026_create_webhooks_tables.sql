@@ -0,0 +1,60 @@
+
+-- Webhook subscriptions of clients (push notifications about incoming/outgoing transfers).
+create table WEBHOOK_SUBSCRIPTIONS
+(
+    ID          UUID          not null primary key,
+    CLIENT_ID   CLIENT_ID     not null,
+    URL         VARCHAR(2000) not null,
+    -- Key of HMAC-SHA256 signature of deliveries (it is never returned by REST API).
+    SECRET      VARCHAR(256)  not null,
+    EVENT_TYPES WEBHOOK_EVENT_TYPE[] not null check (cardinality(EVENT_TYPES) > 0),
+    ACTIVE      BOOLEAN       not null default true,
+    CREATED_AT  TIMESTAMPTZ   not null default CURRENT_TIMESTAMP,
+    UPDATED_AT  TIMESTAMPTZ   not null default CURRENT_TIMESTAMP,
+
+    constraint FK_CLIENT_ID foreign key(CLIENT_ID) references CLIENTS(CLIENT_ID)
+);
+
+create index WEBHOOK_SUBSCRIPTIONS_CLIENT_IDX on WEBHOOK_SUBSCRIPTIONS(CLIENT_ID);
+
+
+-- Notification for one subscription. It is inserted in the same DB transaction as transfer
+-- and it is sent by delivery worker (with retries, see NEXT_ATTEMPT_AT).
+create table WEBHOOK_DELIVERIES
+(
+    ID              UUID          not null primary key,
+    SUBSCRIPTION_ID UUID          not null,
+    -- The same for all subscriptions, receivers should use it to skip duplicates.
+    EVENT_ID        UUID          not null,
+    EVENT_TYPE      WEBHOOK_EVENT_TYPE not null,
+    PAYLOAD         JSONB         not null,
+    STATUS          WEBHOOK_DELIVERY_STATUS not null default 'PENDING',
+    ATTEMPTS        INTEGER       not null default 0 check (ATTEMPTS >= 0),
+    NEXT_ATTEMPT_AT TIMESTAMPTZ   not null default CURRENT_TIMESTAMP,
+    LAST_ERROR      VARCHAR(1000),
+    DELIVERED_AT    TIMESTAMPTZ,
+    CREATED_AT      TIMESTAMPTZ   not null default CURRENT_TIMESTAMP,
+    UPDATED_AT      TIMESTAMPTZ   not null default CURRENT_TIMESTAMP,
+
+    constraint FK_SUBSCRIPTION_ID foreign key(SUBSCRIPTION_ID) references WEBHOOK_SUBSCRIPTIONS(ID) on delete cascade,
+    unique (SUBSCRIPTION_ID, EVENT_ID)
+);
+
+create index WEBHOOK_DELIVERIES_SUBSCRIPTION_IDX on WEBHOOK_DELIVERIES(SUBSCRIPTION_ID, CREATED_AT);
+create index WEBHOOK_DELIVERIES_PENDING_IDX on WEBHOOK_DELIVERIES(NEXT_ATTEMPT_AT) where STATUS = 'PENDING';
+
+
+-- History of delivery attempts.
+create table WEBHOOK_DELIVERY_ATTEMPTS
+(
+    DELIVERY_ID     UUID          not null,
+    ATTEMPT_NO      INTEGER       not null check (ATTEMPT_NO > 0),
+    ATTEMPTED_AT    TIMESTAMPTZ   not null,
+    -- It is null if there is no response (connection error, timeout).
+    RESPONSE_STATUS INTEGER,
+    ERROR           VARCHAR(1000),
+    DURATION_MS     INTEGER       not null check (DURATION_MS >= 0),
+
+    primary key (DELIVERY_ID, ATTEMPT_NO),
+    constraint FK_DELIVERY_ID foreign key(DELIVERY_ID) references WEBHOOK_DELIVERIES(ID) on delete cascade
+);
@@ -0,0 +1,3 @@
+
+-- Version of client record for optimistic locking (it is incremented by every change).
+alter table CLIENTS add column VERSION BIGINT not null default 0;
@@ -0,0 +1,16 @@
+-- Idempotency keys of 'transferBatch' REST operation.
+-- Row is inserted in the same DB transaction as batch itself,
+-- so key is 'used' only if batch is really committed (rejected batch can be retried with the same key).
+create table TRANSFER_BATCH_IDEMPOTENCY_KEYS
+(
+    CLIENT_ID       CLIENT_ID    not null,
+    IDEMPOTENCY_KEY VARCHAR(255) not null,
+    -- SHA-256 (hex) of normalized request
+    REQUEST_HASH    CHAR(64)     not null,
+    -- Outcome (transfer ID of every leg, in the same order as legs of request)
+    TRANSFER_IDS    UUID[]       not null,
+    CREATED_AT      TIMESTAMPTZ  not null default CURRENT_TIMESTAMP,
+
+    primary key (CLIENT_ID, IDEMPOTENCY_KEY),
+    constraint FK_CLIENT_ID foreign key(CLIENT_ID) references CLIENTS(CLIENT_ID)
+);
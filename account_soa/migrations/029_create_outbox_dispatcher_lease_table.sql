@@ -0,0 +1,11 @@
+-- Only one outbox dispatcher (replica) publishes events at a time (to keep order of events).
+-- Lease is taken/prolonged by short DB statements, events are published without open DB transaction.
+create table OUTBOX_DISPATCHER_LEASE
+(
+    ID           SMALLINT     not null primary key default 1 check (ID = 1),
+    -- Dispatcher (replica) which holds the lease.
+    OWNER_ID     UUID,
+    LEASED_UNTIL TIMESTAMPTZ  not null default '-infinity'
+);
+
+insert into OUTBOX_DISPATCHER_LEASE (ID) values (1);
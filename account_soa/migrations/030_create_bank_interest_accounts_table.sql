@@ -0,0 +1,10 @@
+
+-- Bank accounts which pay credited interest (one per currency).
+-- They are interest expense accounts, so their balance is negative (they need overdraft limit).
+create table BANK_INTEREST_ACCOUNTS
+(
+    CUR        CURRENCY    not null primary key,
+    ACCOUNT_ID ACCOUNT_ID  not null unique,
+
+    constraint FK_ACCOUNT_ID foreign key(ACCOUNT_ID) references ACCOUNTS(ID)
+);
@@ -1,7 +1,16 @@
+use mvv_account_soa::cli::{ CliCommand, run_cli_command };
+
 
 #[tokio::main]
 async fn main() {
-    mvv_account_soa::rest::web_app::web_app_main().await
-        .map_err(|err|{ eprintln!("Error: {err:?}"); err})
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let res = match CliCommand::parse(&args) {
+        Ok(Some(command)) => run_cli_command(command).await,
+        Ok(None) => mvv_account_soa::rest::web_app::web_app_main().await,
+        Err(err) => Err(err),
+    };
+
+    res.map_err(|err|{ eprintln!("Error: {err:?}"); err})
         .expect("Oops! Failed")
 }
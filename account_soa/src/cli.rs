@@ -0,0 +1,213 @@
+use std::sync::Arc;
+use chrono::NaiveDate;
+use crate::database::migration::{ migrate_dry_run, migrate_up, migration_status };
+use crate::rest::web_app::{ create_prod_account_service, create_prod_db_connection, init_app_env };
+use crate::service::interest_accrual::InterestAccrualJob;
+use crate::service::reconciliation::ReconciliationJob;
+//--------------------------------------------------------------------------------------------------
+
+
+
+/// Sub-commands of 'mvv_account_soa' (without sub-command REST server is started).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliCommand {
+    /// accrue-interest --from 2024-01-01 [--to 2024-01-31]
+    AccrueInterest { from: NaiveDate, to: NaiveDate },
+    /// migrate up|status|dry-run
+    Migrate(MigrateCommand),
+    /// reconcile (prints JSON report, fails if any drift is found)
+    Reconcile,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrateCommand {
+    /// Applies pending migrations.
+    Up,
+    /// Prints applied/pending migrations.
+    Status,
+    /// Prints SQL of pending migrations (without executing them).
+    DryRun,
+}
+
+impl CliCommand {
+    /// Returns None if there is no sub-command (args are passed without program name).
+    pub fn parse(args: &[String]) -> Result<Option<CliCommand>, anyhow::Error> {
+        let Some(command) = args.first() else { return Ok(None) };
+
+        match command.as_str() {
+            "accrue-interest" => {
+                let options = parse_options(&args[1..]) ?;
+                let from = option_date(&options, "--from") ?
+                    .ok_or_else(|| anyhow::anyhow!("Option --from is required for accrue-interest."))?;
+                let to = option_date(&options, "--to") ?.unwrap_or(from);
+                if from > to {
+                    anyhow::bail!("Option --from [{from}] is after --to [{to}].");
+                }
+                Ok(Some(CliCommand::AccrueInterest { from, to }))
+            }
+            "migrate" => {
+                let migrate_command = match args.get(1..) {
+                    Some([action]) => match action.as_str() {
+                        "up" => MigrateCommand::Up,
+                        "status" => MigrateCommand::Status,
+                        "dry-run" => MigrateCommand::DryRun,
+                        _ => anyhow::bail!("Unknown migrate action [{action}].\n{}", usage()),
+                    },
+                    _ => anyhow::bail!("Migrate action (up|status|dry-run) is required.\n{}", usage()),
+                };
+                Ok(Some(CliCommand::Migrate(migrate_command)))
+            }
+            "reconcile" => {
+                if let Some(arg) = args.get(1) {
+                    anyhow::bail!("Unexpected argument [{arg}].\n{}", usage());
+                }
+                Ok(Some(CliCommand::Reconcile))
+            }
+            // Server options (for example, --generate-open-api).
+            _ if command.starts_with("--") => Ok(None),
+            _ => Err(anyhow::anyhow!("Unknown command [{command}].\n{}", usage())),
+        }
+    }
+}
+
+
+pub fn usage() -> &'static str {
+    "Usage: \n \
+       mvv_account_soa \n \
+       mvv_account_soa accrue-interest --from YYYY-MM-DD [--to YYYY-MM-DD] \n \
+       mvv_account_soa migrate up|status|dry-run \n \
+       mvv_account_soa reconcile \n"
+}
+
+
+pub async fn run_cli_command(command: CliCommand) -> Result<(), anyhow::Error> {
+    init_app_env() ?;
+
+    let db = create_prod_db_connection() ?;
+
+    match command {
+        CliCommand::AccrueInterest { from, to } => {
+            let account_service = create_prod_account_service(&db);
+            let job = InterestAccrualJob { account_service: Arc::clone(&account_service) };
+            let summary = job.run(from, to).await ?;
+            println!("Interest accrual from {from} to {to}: {} accounts, {} days accrued, {} days skipped, {} credits",
+                summary.accounts, summary.accrued_days, summary.skipped_days, summary.credits);
+        }
+        CliCommand::Migrate(MigrateCommand::Up) => {
+            let applied = migrate_up(&db).await ?;
+            if applied.is_empty() {
+                println!("DB is up to date");
+            }
+            for migration in applied {
+                println!("Applied {:03} {}", migration.version, migration.description);
+            }
+        }
+        CliCommand::Migrate(MigrateCommand::Status) => {
+            for migration in migration_status(&db).await ? {
+                println!("{:03} {:<40} {}", migration.version, migration.description, migration.state);
+            }
+        }
+        CliCommand::Migrate(MigrateCommand::DryRun) => {
+            let sql = migrate_dry_run(&db).await ?;
+            if sql.is_empty() {
+                println!("-- DB is up to date");
+            }
+            print!("{sql}");
+        }
+        CliCommand::Reconcile => {
+            let job = ReconciliationJob { database_connection: Arc::clone(&db) };
+            let report = job.run().await ?;
+            println!("{}", report.to_json() ?);
+            if !report.ok {
+                anyhow::bail!("Balance reconciliation found drift ({} mismatched accounts).", report.mismatched_accounts.len());
+            }
+        }
+    }
+    Ok(())
+}
+
+
+/// Options in format '--name value'.
+fn parse_options(args: &[String]) -> Result<Vec<(&str, &str)>, anyhow::Error> {
+    let mut options = Vec::new();
+    let mut args = args.iter();
+    while let Some(name) = args.next() {
+        if !name.starts_with("--") {
+            anyhow::bail!("Unexpected argument [{name}].\n{}", usage());
+        }
+        let value = args.next()
+            .ok_or_else(|| anyhow::anyhow!("Option {name} has no value."))?;
+        options.push((name.as_str(), value.as_str()));
+    }
+    Ok(options)
+}
+
+fn option_date(options: &[(&str, &str)], name: &str) -> Result<Option<NaiveDate>, anyhow::Error> {
+    options.iter()
+        .find(|(option_name, _)| *option_name == name)
+        .map(|(_, value)| NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|err| anyhow::anyhow!("Option {name} has incorrect date [{value}] ({err}).")))
+        .transpose()
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+    use chrono::NaiveDate;
+    use mvv_auth::util::test_unwrap::TestResultUnwrap;
+    use super::{ CliCommand, MigrateCommand };
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::from_str(s).test_unwrap()
+    }
+
+    #[test]
+    fn no_command() {
+        assert_eq!(CliCommand::parse(&args(&[])).test_unwrap(), None);
+        assert_eq!(CliCommand::parse(&args(&["--generate-open-api"])).test_unwrap(), None);
+    }
+
+    #[test]
+    fn accrue_interest_command() {
+        assert_eq!(
+            CliCommand::parse(&args(&["accrue-interest", "--from", "2024-01-01", "--to", "2024-01-31"])).test_unwrap(),
+            Some(CliCommand::AccrueInterest { from: date("2024-01-01"), to: date("2024-01-31") }),
+        );
+        assert_eq!(
+            CliCommand::parse(&args(&["accrue-interest", "--from", "2024-02-29"])).test_unwrap(),
+            Some(CliCommand::AccrueInterest { from: date("2024-02-29"), to: date("2024-02-29") }),
+        );
+    }
+
+    #[test]
+    fn accrue_interest_command_with_wrong_args() {
+        assert!(CliCommand::parse(&args(&["accrue-interest"])).is_err());
+        assert!(CliCommand::parse(&args(&["accrue-interest", "--from"])).is_err());
+        assert!(CliCommand::parse(&args(&["accrue-interest", "--from", "2024-02-30"])).is_err());
+        assert!(CliCommand::parse(&args(&["accrue-interest", "--from", "2024-02-01", "--to", "2024-01-01"])).is_err());
+        assert!(CliCommand::parse(&args(&["accrue-interest", "2024-02-01"])).is_err());
+        assert!(CliCommand::parse(&args(&["unknown-command"])).is_err());
+    }
+
+    #[test]
+    fn migrate_command() {
+        assert_eq!(CliCommand::parse(&args(&["migrate", "up"])).test_unwrap(), Some(CliCommand::Migrate(MigrateCommand::Up)));
+        assert_eq!(CliCommand::parse(&args(&["migrate", "status"])).test_unwrap(), Some(CliCommand::Migrate(MigrateCommand::Status)));
+        assert_eq!(CliCommand::parse(&args(&["migrate", "dry-run"])).test_unwrap(), Some(CliCommand::Migrate(MigrateCommand::DryRun)));
+
+        assert!(CliCommand::parse(&args(&["migrate"])).is_err());
+        assert!(CliCommand::parse(&args(&["migrate", "down"])).is_err());
+        assert!(CliCommand::parse(&args(&["migrate", "up", "status"])).is_err());
+    }
+
+    #[test]
+    fn reconcile_command() {
+        assert_eq!(CliCommand::parse(&args(&["reconcile"])).test_unwrap(), Some(CliCommand::Reconcile));
+        assert!(CliCommand::parse(&args(&["reconcile", "--fix"])).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt::Write;
+use log::info;
+use sqlx::migrate::{ Migration, Migrator };
+//--------------------------------------------------------------------------------------------------
+
+
+
+/// DB schema migrations ('migrations' dir) embedded into binary.
+/// Applied migrations are registered in _sqlx_migrations table.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum MigrationState {
+    #[display("applied")]
+    Applied,
+    #[display("pending")]
+    Pending,
+    /// Migration file was changed after it had been applied.
+    #[display("applied, CHECKSUM MISMATCH")]
+    ChecksumMismatch,
+    /// Previous run of migration failed (DB should be fixed manually).
+    #[display("FAILED")]
+    Failed,
+    /// Migration is applied by newer version of application (it is absent in this binary).
+    #[display("applied, UNKNOWN")]
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+}
+
+/// Row of _sqlx_migrations table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub description: String,
+    pub checksum: Vec<u8>,
+    pub success: bool,
+}
+
+
+/// Ordered by version.
+pub async fn migration_status(database_connection: &sqlx_postgres::PgPool)
+    -> Result<Vec<MigrationStatus>, sqlx::Error> {
+    let applied = applied_migrations(database_connection).await ?;
+    Ok(migration_states(MIGRATOR.iter(), &applied))
+}
+
+
+/// Returns applied migrations.
+pub async fn migrate_up(database_connection: &sqlx_postgres::PgPool)
+    -> Result<Vec<MigrationStatus>, anyhow::Error> {
+
+    let pending = pending_migrations(database_connection).await ?;
+    MIGRATOR.run(database_connection).await ?;
+
+    for migration in &pending {
+        info!("DB migration {} ({}) is applied", migration.version, migration.description);
+    }
+    Ok(pending)
+}
+
+
+/// SQL of pending migrations, nothing is executed.
+pub async fn migrate_dry_run(database_connection: &sqlx_postgres::PgPool)
+    -> Result<String, sqlx::Error> {
+
+    let applied = applied_migrations(database_connection).await ?;
+    let pending_versions = migration_states(MIGRATOR.iter(), &applied)
+        .into_iter()
+        .filter(|status| status.state == MigrationState::Pending)
+        .map(|status| status.version)
+        .collect::<Vec<_>>();
+
+    let mut sql = String::new();
+    for migration in MIGRATOR.iter().filter(|migration| pending_versions.contains(&migration.version)) {
+        let _ = writeln!(sql, "-- Migration {} ({})", migration.version, migration.description);
+        let _ = writeln!(sql, "{}", migration.sql.trim_end());
+        let _ = writeln!(sql);
+    }
+    Ok(sql)
+}
+
+
+async fn pending_migrations(database_connection: &sqlx_postgres::PgPool)
+    -> Result<Vec<MigrationStatus>, sqlx::Error> {
+    Ok(migration_status(database_connection).await ?
+        .into_iter()
+        .filter(|status| status.state == MigrationState::Pending)
+        .collect())
+}
+
+
+/// It does not create _sqlx_migrations table (if DB is not migrated yet, there are no applied migrations).
+async fn applied_migrations(database_connection: &sqlx_postgres::PgPool)
+    -> Result<Vec<AppliedMigration>, sqlx::Error> {
+
+    use sqlx::Row;
+
+    let table_exists: bool = sqlx::query_scalar("select to_regclass('_sqlx_migrations') is not null ")
+        .fetch_one(database_connection)
+        .await ?;
+    if !table_exists {
+        return Ok(Vec::new());
+    }
+
+    sqlx::query("select version, description, checksum, success from _sqlx_migrations order by version ")
+        .fetch_all(database_connection)
+        .await ?
+        .iter()
+        .map(|row| Ok(AppliedMigration {
+            version: row.try_get("version") ?,
+            description: row.try_get("description") ?,
+            checksum: row.try_get("checksum") ?,
+            success: row.try_get("success") ?,
+        }))
+        .collect()
+}
+
+
+fn migration_states<'a>(embedded: impl Iterator<Item = &'a Migration>, applied: &[AppliedMigration])
+    -> Vec<MigrationStatus> {
+
+    let mut statuses = embedded
+        .map(|migration| {
+            let state = match applied.iter().find(|applied| applied.version == migration.version) {
+                None => MigrationState::Pending,
+                Some(applied) if !applied.success => MigrationState::Failed,
+                Some(applied) if applied.checksum.as_slice() != &*migration.checksum => MigrationState::ChecksumMismatch,
+                Some(_) => MigrationState::Applied,
+            };
+            MigrationStatus { version: migration.version, description: migration.description.to_string(), state }
+        })
+        .collect::<Vec<_>>();
+
+    for applied in applied {
+        if !statuses.iter().any(|status| status.version == applied.version) {
+            statuses.push(MigrationStatus {
+                version: applied.version,
+                description: applied.description.clone(),
+                state: MigrationState::Unknown,
+            });
+        }
+    }
+
+    statuses.sort_by_key(|status| status.version);
+    statuses
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use sqlx::migrate::{ Migration, MigrationType };
+    use super::{ migration_states, AppliedMigration, MigrationState, MIGRATOR };
+
+    fn migration(version: i64, sql: &'static str) -> Migration {
+        Migration::new(version, Cow::Owned(format!("migration {version}")), MigrationType::Simple, Cow::Borrowed(sql))
+    }
+    fn applied(migration: &Migration, success: bool) -> AppliedMigration {
+        AppliedMigration {
+            version: migration.version,
+            description: migration.description.to_string(),
+            checksum: migration.checksum.to_vec(),
+            success,
+        }
+    }
+
+    #[test]
+    fn embedded_migrations() {
+        let versions = MIGRATOR.iter().map(|migration| migration.version).collect::<Vec<_>>();
+        assert_eq!(versions.first(), Some(&1));
+
+        let mut sorted_versions = versions.clone();
+        sorted_versions.sort();
+        sorted_versions.dedup();
+        assert_eq!(versions, sorted_versions);
+    }
+
+    #[test]
+    fn states_of_migrations() {
+        let migrations = [
+            migration(1, "create table T1 (ID INT);"),
+            migration(2, "create table T2 (ID INT);"),
+            migration(3, "create table T3 (ID INT);"),
+            migration(4, "create table T4 (ID INT);"),
+        ];
+        let mut changed_migration_2 = applied(&migrations[1], true);
+        changed_migration_2.checksum = migration(2, "create table T2 (ID BIGINT);").checksum.to_vec();
+
+        let applied = [
+            applied(&migrations[0], true),
+            changed_migration_2,
+            applied(&migrations[2], false),
+            applied(&migration(7, "create table T7 (ID INT);"), true),
+        ];
+
+        let states = migration_states(migrations.iter(), &applied)
+            .into_iter()
+            .map(|status| (status.version, status.state))
+            .collect::<Vec<_>>();
+
+        assert_eq!(states, vec![
+            (1, MigrationState::Applied),
+            (2, MigrationState::ChecksumMismatch),
+            (3, MigrationState::Failed),
+            (4, MigrationState::Pending),
+            (7, MigrationState::Unknown),
+        ]);
+    }
+
+    #[test]
+    fn states_of_not_migrated_db() {
+        let migrations = [migration(1, "create table T1 (ID INT);")];
+        let states = migration_states(migrations.iter(), &[]);
+        assert_eq!(states.len(), 1);
+        assert_eq!(states[0].state, MigrationState::Pending);
+        assert_eq!(states[0].description, "migration 1");
+    }
+}
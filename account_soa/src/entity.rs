@@ -5,6 +5,16 @@
 
 pub mod account;
 pub mod user;
+pub mod transaction;
+pub mod idempotency;
+pub mod hold;
+pub mod standing_order;
+pub mod transfer_limit;
+pub mod fee;
+pub mod pending_transfer;
+pub mod interest;
+pub mod account_event;
+pub mod webhook;
 mod investigation;
 mod iban;
 mod id;
@@ -27,7 +37,14 @@ pub mod prelude {
 //     pub use super::account::Account;
 // }
 
-pub use account::{ AccountId, Account, };
+pub use account::{ AccountId, Account, AccountStatus, };
+pub use transaction::{ TransferId, AccountTransaction, };
+pub use hold::{ HoldId, Hold, HoldStatus, };
+pub use standing_order::{ StandingOrderId, StandingOrder, StandingOrderPeriod, };
+pub use transfer_limit::{ ClientTier, TransferLimitKind, };
+pub use pending_transfer::{ PendingTransferId, PendingTransfer, PendingTransferStatus, };
+pub use interest::{ DayCountConvention, InterestAccrual, };
 pub use id::ClientId;
 pub use iban::IbanWrapper;
 pub use iban::IbanRefWrapper;
+pub use iban::make_iban;
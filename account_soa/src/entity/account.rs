@@ -21,7 +21,7 @@ use mvv_common::{
     backtrace::{backtrace, BacktraceCell},
     unchecked::UncheckedResultUnwrap,
 };
-use mvv_common_bank_entities::amount::Amount;
+use mvv_common_bank_entities::amount::{ Amount, ops::AmountOpsError };
 use crate::entity::ClientId;
 // -------------------------------------------------------------------------------------------------
 
@@ -42,6 +42,12 @@ generate_pg_delegate_type_info! { AccountId, uuid::Uuid }
 generate_pg_delegate_encode!    { AccountId, uuid::Uuid }
 generate_pg_delegate_decode!    { AccountId, uuid::Uuid }
 
+impl AccountId {
+    pub fn generate() -> Self {
+        AccountId(uuid::Uuid::new_v4())
+    }
+}
+
 
 
 
@@ -80,7 +86,7 @@ pub type AccountNameInner = fixedstr::str256;
     validate(with = validate_account_name, error = AccountNameError),
     derive(Debug, Display, AsRef, PartialEq, Clone, Serialize, Deserialize),
 )]
-struct AccountName(AccountNameInner);
+pub struct AccountName(AccountNameInner);
 impl AccountName {
     #[inline]
     pub fn as_str(&self) -> &str {
@@ -110,6 +116,63 @@ generate_pg_encode_from_as_str! { AccountName }
 generate_pg_decode_from_str! { AccountName }
 
 
+#[derive(thiserror::Error, Debug)]
+pub enum AccountStatusError {
+    #[error("Unknown account status")]
+    UnknownStatus(BacktraceCell),
+}
+
+/// The same as AccountStatus in gRPC proto (mvv.client.search.proto).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum AccountStatus {
+    /// Account is opened but cannot be used yet.
+    Pending,
+    Active,
+    /// Temporary blocked (all operations are rejected).
+    Suspended,
+    /// Terminal status.
+    Closed,
+}
+
+impl AccountStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountStatus::Pending   => "PENDING",
+            AccountStatus::Active    => "ACTIVE",
+            AccountStatus::Suspended => "SUSPENDED",
+            AccountStatus::Closed    => "CLOSED",
+        }
+    }
+
+    /// Allowed transitions: PENDING => ACTIVE/CLOSED, ACTIVE <=> SUSPENDED, any not closed => CLOSED.
+    pub fn can_be_changed_to(&self, new_status: AccountStatus) -> bool {
+        use AccountStatus::*;
+        match (*self, new_status) {
+            (Pending, Active) | (Pending, Closed) => true,
+            (Active, Suspended) | (Active, Closed) => true,
+            (Suspended, Active) | (Suspended, Closed) => true,
+            _ => false,
+        }
+    }
+}
+
+impl core::str::FromStr for AccountStatus {
+    type Err = AccountStatusError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "PENDING"   => Ok(AccountStatus::Pending),
+            "ACTIVE"    => Ok(AccountStatus::Active),
+            "SUSPENDED" => Ok(AccountStatus::Suspended),
+            "CLOSED"    => Ok(AccountStatus::Closed),
+            _ => Err(AccountStatusError::UnknownStatus(backtrace())),
+        }
+    }
+}
+generate_pg_delegate_type_info! { AccountStatus, str }
+generate_pg_encode_from_as_str! { AccountStatus }
+generate_pg_decode_from_str! { AccountStatus }
+
+
 #[derive(Debug)]
 #[readonly::make]
 pub struct Account {
@@ -118,6 +181,11 @@ pub struct Account {
     pub client_id: ClientId,
     pub name: AccountName,
     pub amount: Amount,
+    /// Balance can go down to -overdraft_limit (it has account currency).
+    pub overdraft_limit: Amount,
+    /// Sum of active holds (it reduces available funds, but not ledger balance 'amount').
+    pub held_amount: Amount,
+    pub status: AccountStatus,
     pub created_at: chrono::DateTime<Utc>,
     pub updated_at: chrono::DateTime<Utc>,
 }
@@ -128,6 +196,9 @@ pub struct AccountParts {
     pub client_id: ClientId,
     pub name: AccountName,
     pub amount: Amount,
+    pub overdraft_limit: Amount,
+    pub held_amount: Amount,
+    pub status: AccountStatus,
     pub created_at: chrono::DateTime<Utc>,
     pub updated_at: chrono::DateTime<Utc>,
 }
@@ -142,6 +213,9 @@ impl Account {
             client_id: args.client_id,
             name: args.name,
             amount: args.amount,
+            overdraft_limit: args.overdraft_limit,
+            held_amount: args.held_amount,
+            status: args.status,
             created_at: args.created_at,
             updated_at: args.updated_at,
         }
@@ -150,6 +224,12 @@ impl Account {
 
 
 impl Account {
+    /// Funds which can be spent (ledger balance + overdraft limit - active holds).
+    pub fn available_funds(&self) -> Result<Amount, AmountOpsError> {
+        let funds = (&self.amount + &self.overdraft_limit) ?;
+        &funds - &self.held_amount
+    }
+
     pub fn into_parts(self) -> AccountParts {
         AccountParts {
             id: self.id,
@@ -157,6 +237,9 @@ impl Account {
             client_id: self.client_id,
             name: self.name,
             amount: self.amount,
+            overdraft_limit: self.overdraft_limit,
+            held_amount: self.held_amount,
+            status: self.status,
             created_at: self.created_at,
             updated_at: self.updated_at,
         }
@@ -211,6 +294,42 @@ mod tests {
     use mvv_auth::util::test_unwrap::TestResultUnwrap;
     use crate::entity::IbanWrapper;
 
+    #[test]
+    fn account_status_transitions() {
+        use super::AccountStatus::*;
+        assert!(Pending.can_be_changed_to(Active));
+        assert!(Active.can_be_changed_to(Suspended));
+        assert!(Suspended.can_be_changed_to(Active));
+        assert!(Suspended.can_be_changed_to(Closed));
+
+        assert!(!Active.can_be_changed_to(Active));
+        assert!(!Active.can_be_changed_to(Pending));
+        assert!(!Closed.can_be_changed_to(Active));
+    }
+
+    #[test]
+    fn available_funds() {
+        use core::str::FromStr;
+        use mvv_common_bank_entities::amount::Amount;
+        use crate::entity::{ AccountId, ClientId };
+        use super::{ Account, AccountStatus };
+
+        let account = Account::new(super::new::Args {
+            id: AccountId::from_str("00000000-0000-0000-0000-000000000101").test_unwrap(),
+            iban: iban::Iban::from_str("UA713736572172926969841832393").test_unwrap(),
+            client_id: ClientId::from_str("00000000-0000-0000-0000-000000000001").test_unwrap(),
+            name: "account 1".try_into().test_unwrap(),
+            amount: Amount::from_str("100.50 USD").test_unwrap(),
+            overdraft_limit: Amount::from_str("50 USD").test_unwrap(),
+            held_amount: Amount::from_str("20.25 USD").test_unwrap(),
+            status: AccountStatus::Active,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        });
+
+        assert_eq!(account.available_funds().test_unwrap(), Amount::from_str("130.25 USD").test_unwrap());
+    }
+
     #[test]
     fn account_id_from_str() {
         use core::str::FromStr;
@@ -0,0 +1,154 @@
+use bigdecimal::BigDecimal;
+use serde::Serialize;
+use mvv_common_bank_entities::{ amount::Amount, Currency };
+// -------------------------------------------------------------------------------------------------
+
+
+
+/// Amount in event payload ({ "value": 12.5, "currency": "USD" }).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EventAmount {
+    #[serde(with = "mvv_common::json::serde_json_bd::bd_with")]
+    pub value: BigDecimal,
+    pub currency: Currency,
+}
+
+impl From<&Amount> for EventAmount {
+    fn from(amount: &Amount) -> Self {
+        EventAmount { value: amount.value_ref().clone(), currency: amount.currency() }
+    }
+}
+
+
+/// Domain event which is written to outbox (OUTBOX_EVENTS table) in the same DB transaction
+/// as account change, and then it is published to EventSink (see OutboxDispatcher).
+/// It is serialized to JSON with 'type' field (the same as event_type()).
+/// It is only published (serialized) by this service, so there is no Deserialize impl.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum AccountEvent {
+    #[serde(rename_all = "camelCase")]
+    AccountOpened {
+        account_id: uuid::Uuid,
+        client_id: uuid::Uuid,
+        iban: String,
+        currency: Currency,
+    },
+    #[serde(rename_all = "camelCase")]
+    AccountStatusChanged {
+        account_id: uuid::Uuid,
+        client_id: uuid::Uuid,
+        old_status: String,
+        new_status: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    OverdraftLimitChanged {
+        account_id: uuid::Uuid,
+        client_id: uuid::Uuid,
+        overdraft_limit: EventAmount,
+    },
+    #[serde(rename_all = "camelCase")]
+    TransferCompleted {
+        transfer_id: uuid::Uuid,
+        from_account_id: uuid::Uuid,
+        from_client_id: uuid::Uuid,
+        to_account_id: uuid::Uuid,
+        to_client_id: uuid::Uuid,
+        /// In 'from' account currency.
+        amount: EventAmount,
+        /// In 'to' account currency.
+        credit_amount: EventAmount,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        fee: Option<EventAmount>,
+        from_balance_after: EventAmount,
+        to_balance_after: EventAmount,
+    },
+    #[serde(rename_all = "camelCase")]
+    TransferReversed {
+        reversal_transfer_id: uuid::Uuid,
+        reversed_transfer_id: uuid::Uuid,
+        /// Original 'from' account (it gets money back).
+        from_account_id: uuid::Uuid,
+        from_client_id: uuid::Uuid,
+        to_account_id: uuid::Uuid,
+        to_client_id: uuid::Uuid,
+        /// In 'from' account currency.
+        refund_amount: EventAmount,
+        /// In 'to' account currency.
+        chargeback_amount: EventAmount,
+        /// Fee of reversed transfer (it is returned to 'from' account).
+        #[serde(skip_serializing_if = "Option::is_none")]
+        fee_refund: Option<EventAmount>,
+        from_balance_after: EventAmount,
+        to_balance_after: EventAmount,
+    },
+    #[serde(rename_all = "camelCase")]
+    InterestCredited {
+        transfer_id: uuid::Uuid,
+        account_id: uuid::Uuid,
+        amount: EventAmount,
+        balance_after: EventAmount,
+    },
+}
+
+impl AccountEvent {
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            AccountEvent::AccountOpened { .. }         => "AccountOpened",
+            AccountEvent::AccountStatusChanged { .. }  => "AccountStatusChanged",
+            AccountEvent::OverdraftLimitChanged { .. } => "OverdraftLimitChanged",
+            AccountEvent::TransferCompleted { .. }     => "TransferCompleted",
+            AccountEvent::TransferReversed { .. }      => "TransferReversed",
+            AccountEvent::InterestCredited { .. }      => "InterestCredited",
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use mvv_auth::util::test_unwrap::TestResultUnwrap;
+    use mvv_common_bank_entities::make_currency;
+    use super::{ AccountEvent, EventAmount };
+
+    fn uuid(s: &str) -> uuid::Uuid {
+        uuid::Uuid::from_str(s).test_unwrap()
+    }
+    fn usd(s: &str) -> EventAmount {
+        EventAmount { value: BigDecimal::from_str(s).test_unwrap(), currency: make_currency!("USD") }
+    }
+
+    #[test]
+    fn transfer_completed_to_json() {
+        let event = AccountEvent::TransferCompleted {
+            transfer_id: uuid("6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55"),
+            from_account_id: uuid("00000000-0000-0000-0000-000000000101"),
+            from_client_id: uuid("00000000-0000-0000-0000-000000000001"),
+            to_account_id: uuid("00000000-0000-0000-0000-000000000201"),
+            to_client_id: uuid("00000000-0000-0000-0000-000000000002"),
+            amount: usd("10.5"),
+            credit_amount: usd("10.5"),
+            fee: None,
+            from_balance_after: usd("139.5"),
+            to_balance_after: usd("110.5"),
+        };
+        assert_eq!(event.event_type(), "TransferCompleted");
+
+        let json = serde_json::to_string(&event).test_unwrap();
+        assert_eq!(serde_json::Value::from_str(&json).test_unwrap(), serde_json::json!({
+            "type": "TransferCompleted",
+            "transferId": "6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55",
+            "fromAccountId": "00000000-0000-0000-0000-000000000101",
+            "fromClientId": "00000000-0000-0000-0000-000000000001",
+            "toAccountId": "00000000-0000-0000-0000-000000000201",
+            "toClientId": "00000000-0000-0000-0000-000000000002",
+            "amount": { "value": 10.5, "currency": "USD" },
+            "creditAmount": { "value": 10.5, "currency": "USD" },
+            "fromBalanceAfter": { "value": 139.5, "currency": "USD" },
+            "toBalanceAfter": { "value": 110.5, "currency": "USD" },
+        }));
+    }
+}
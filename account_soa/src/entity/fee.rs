@@ -0,0 +1,102 @@
+use bigdecimal::{ BigDecimal, RoundingMode };
+use mvv_common_bank_entities::amount::Amount;
+// -------------------------------------------------------------------------------------------------
+
+
+
+/// Transfer fee rule (row of FEE_RULES table).
+/// Fee (in currency of 'from' account) is 'flat_fee + amount * percent / 100',
+/// but it is not less than 'min_fee' and not greater than 'max_fee'.
+/// Fee is rounded (half-up) to minor units of currency (for example, to cents for USD).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeRule {
+    pub flat_fee: BigDecimal,
+    pub percent: BigDecimal,
+    pub min_fee: Option<BigDecimal>,
+    pub max_fee: Option<BigDecimal>,
+}
+
+impl FeeRule {
+    pub fn calculate(&self, amount: &Amount) -> Amount {
+        let fee = &self.flat_fee + amount.value_ref() * &self.percent / BigDecimal::from(100i32);
+
+        let fee = match self.min_fee {
+            Some(ref min_fee) if &fee < min_fee => min_fee.clone(),
+            _ => fee,
+        };
+        let fee = match self.max_fee {
+            Some(ref max_fee) if &fee > max_fee => max_fee.clone(),
+            _ => fee,
+        };
+        amount.with_value(fee).round_to_minor_units(RoundingMode::HalfUp)
+    }
+}
+
+
+/// Kind of transfer which selects fee rule (together with currency of 'from' account).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeTransferType {
+    /// 'to' account belongs to another client.
+    pub inter_client: bool,
+    /// Accounts have different currencies.
+    pub cross_currency: bool,
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use mvv_auth::util::test_unwrap::TestResultUnwrap;
+    use mvv_common_bank_entities::amount::Amount;
+    use super::FeeRule;
+
+    fn bd(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).test_unwrap()
+    }
+
+    fn usd(s: &str) -> Amount {
+        Amount::from_str(&format!("{s} USD")).test_unwrap()
+    }
+
+    #[test]
+    fn flat_fee() {
+        let rule = FeeRule { flat_fee: bd("5"), percent: bd("0"), min_fee: None, max_fee: None };
+        assert_eq!(rule.calculate(&usd("1000")), usd("5.00"));
+        assert_eq!(rule.calculate(&usd("0.01")), usd("5.00"));
+    }
+
+    #[test]
+    fn percentage_fee_with_min_and_max() {
+        let rule = FeeRule { flat_fee: bd("0.5"), percent: bd("0.5"), min_fee: Some(bd("1")), max_fee: Some(bd("50")) };
+
+        assert_eq!(rule.calculate(&usd("200")), usd("1.50"));
+        // min
+        assert_eq!(rule.calculate(&usd("10")), usd("1.00"));
+        // max
+        assert_eq!(rule.calculate(&usd("100000")), usd("50.00"));
+    }
+
+    #[test]
+    fn fee_is_rounded_to_cents() {
+        let rule = FeeRule { flat_fee: bd("0"), percent: bd("1.5"), min_fee: None, max_fee: None };
+        // 1.5% of 33.33 = 0.49995
+        assert_eq!(rule.calculate(&usd("33.33")), usd("0.50"));
+        // 1.5% of 33.3 = 0.4995
+        assert_eq!(rule.calculate(&usd("33.3")), usd("0.50"));
+        // 1.5% of 33 = 0.495
+        assert_eq!(rule.calculate(&usd("33")), usd("0.50"));
+        // 1.5% of 32.9 = 0.4935
+        assert_eq!(rule.calculate(&usd("32.9")), usd("0.49"));
+    }
+
+    #[test]
+    fn fee_is_rounded_to_currency_minor_units() {
+        let rule = FeeRule { flat_fee: bd("0"), percent: bd("1.5"), min_fee: None, max_fee: None };
+        // 1.5% of 3333 = 49.995
+        assert_eq!(rule.calculate(&Amount::from_str("3333 JPY").test_unwrap()), Amount::from_str("50 JPY").test_unwrap());
+        // 1.5% of 33.333 = 0.499995
+        assert_eq!(rule.calculate(&Amount::from_str("33.333 KWD").test_unwrap()), Amount::from_str("0.500 KWD").test_unwrap());
+    }
+}
@@ -0,0 +1,146 @@
+use chrono::Utc;
+use mvv_common::{
+    generate_from_str_new_type_delegate,
+    generate_into_inner_delegate,
+    generate_pg07_decode_from_str as generate_pg_decode_from_str,
+    generate_pg07_delegate_decode as generate_pg_delegate_decode,
+    generate_pg07_delegate_encode as generate_pg_delegate_encode,
+    generate_pg07_delegate_type_info as generate_pg_delegate_type_info,
+    generate_pg07_encode_from_as_str as generate_pg_encode_from_as_str,
+};
+use mvv_common::backtrace::{ backtrace, BacktraceCell };
+use mvv_common_bank_entities::amount::Amount;
+use crate::entity::{ AccountId, TransferId };
+// -------------------------------------------------------------------------------------------------
+
+
+
+#[derive(Debug, Clone, PartialEq, derive_more::Display)]
+#[display("{}", _0)]
+pub struct HoldId( #[allow(dead_code)] uuid::Uuid);
+
+generate_into_inner_delegate!   { HoldId, uuid::Uuid }
+generate_from_str_new_type_delegate! { HoldId, uuid::Uuid, mvv_common::uuid::UuidFormatError }
+generate_pg_delegate_type_info! { HoldId, uuid::Uuid }
+generate_pg_delegate_encode!    { HoldId, uuid::Uuid }
+generate_pg_delegate_decode!    { HoldId, uuid::Uuid }
+
+impl HoldId {
+    pub fn generate() -> Self {
+        HoldId(uuid::Uuid::new_v4())
+    }
+}
+
+
+#[derive(thiserror::Error, Debug)]
+pub enum HoldStatusError {
+    #[error("Unknown hold status")]
+    UnknownStatus(BacktraceCell),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum HoldStatus {
+    /// Amount is reserved (it reduces available balance).
+    Active,
+    /// Hold is converted to real transfer (in full or in part).
+    Captured,
+    /// Hold is cancelled.
+    Released,
+    /// TTL is over (it is not stored in DB, it is calculated for active holds by 'expires_at').
+    Expired,
+}
+
+impl HoldStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HoldStatus::Active   => "ACTIVE",
+            HoldStatus::Captured => "CAPTURED",
+            HoldStatus::Released => "RELEASED",
+            HoldStatus::Expired  => "EXPIRED",
+        }
+    }
+}
+
+impl core::str::FromStr for HoldStatus {
+    type Err = HoldStatusError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ACTIVE"   => Ok(HoldStatus::Active),
+            "CAPTURED" => Ok(HoldStatus::Captured),
+            "RELEASED" => Ok(HoldStatus::Released),
+            "EXPIRED"  => Ok(HoldStatus::Expired),
+            _ => Err(HoldStatusError::UnknownStatus(backtrace())),
+        }
+    }
+}
+generate_pg_delegate_type_info! { HoldStatus, str }
+generate_pg_encode_from_as_str! { HoldStatus }
+generate_pg_decode_from_str! { HoldStatus }
+
+
+/// Reserved amount of account (row of HOLDS table).
+#[derive(Debug)]
+#[readonly::make]
+pub struct Hold {
+    pub id: HoldId,
+    pub account_id: AccountId,
+    /// Authorized (reserved) amount.
+    pub amount: Amount,
+    pub status: HoldStatus,
+    /// Only for captured hold (it can be less than authorized amount).
+    pub captured_amount: Option<Amount>,
+    pub transfer_id: Option<TransferId>,
+    pub expires_at: chrono::DateTime<Utc>,
+    pub created_at: chrono::DateTime<Utc>,
+    pub updated_at: chrono::DateTime<Utc>,
+}
+
+pub struct HoldParts {
+    pub id: HoldId,
+    pub account_id: AccountId,
+    pub amount: Amount,
+    pub status: HoldStatus,
+    pub captured_amount: Option<Amount>,
+    pub transfer_id: Option<TransferId>,
+    pub expires_at: chrono::DateTime<Utc>,
+    pub created_at: chrono::DateTime<Utc>,
+    pub updated_at: chrono::DateTime<Utc>,
+}
+
+
+impl Hold {
+    #[inline]
+    pub fn new(args: new::Args) -> Self {
+        Hold {
+            id: args.id,
+            account_id: args.account_id,
+            amount: args.amount,
+            status: args.status,
+            captured_amount: args.captured_amount,
+            transfer_id: args.transfer_id,
+            expires_at: args.expires_at,
+            created_at: args.created_at,
+            updated_at: args.updated_at,
+        }
+    }
+
+    pub fn into_parts(self) -> HoldParts {
+        HoldParts {
+            id: self.id,
+            account_id: self.account_id,
+            amount: self.amount,
+            status: self.status,
+            captured_amount: self.captured_amount,
+            transfer_id: self.transfer_id,
+            expires_at: self.expires_at,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+
+// Attempt to emulate named args in rust.
+pub mod new {
+    pub type Args = super::HoldParts;
+}
@@ -35,3 +35,52 @@ pub struct IbanRefWrapper<'a> (
 
 generate_pg_ref_delegate_type_info!   { IbanRefWrapper, str }
 generate_pg_ref_delegate_encode!      { IbanRefWrapper, str }
+
+
+
+/// Creates IBAN with calculated check digits (ISO 13616).
+///
+/// `bban` - basic bank account number (for UA it is 6 digits of bank code + 19 digits of account number).
+pub fn make_iban(country_code: &str, bban: &str) -> Result<Iban, iban::ParseError> {
+    use core::str::FromStr;
+    let check_digits = 98 - iban_mod97(bban, country_code);
+    Iban::from_str(&format!("{country_code}{check_digits:02}{bban}"))
+}
+
+/// BBAN + country code + "00" as number (letters are replaced by 10..35) mod 97.
+fn iban_mod97(bban: &str, country_code: &str) -> u32 {
+    bban.chars()
+        .chain(country_code.chars())
+        .chain("00".chars())
+        .filter_map(|ch| ch.to_digit(36))
+        .fold(0u32, |acc, digit| {
+            let shift = if digit < 10 { 10 } else { 100 };
+            (acc * shift + digit) % 97
+        })
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use mvv_auth::util::test_unwrap::TestResultUnwrap;
+    use super::{ Iban, make_iban };
+
+    #[test]
+    fn make_iban_test() {
+        use core::str::FromStr;
+
+        assert_eq!(
+            make_iban("UA", "3052992990004149123456789").test_unwrap().to_string(),
+            Iban::from_str("UA903052992990004149123456789").test_unwrap().to_string(),
+        );
+        assert_eq!(
+            make_iban("UA", "3736572172926969841832393").test_unwrap().to_string(),
+            Iban::from_str("UA713736572172926969841832393").test_unwrap().to_string(),
+        );
+        assert_eq!(
+            make_iban("UA", "8614766857337364625464668").test_unwrap().to_string(),
+            Iban::from_str("UA948614766857337364625464668").test_unwrap().to_string(),
+        );
+    }
+}
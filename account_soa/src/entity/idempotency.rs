@@ -0,0 +1,72 @@
+use mvv_common::{
+    backtrace::{backtrace, BacktraceCell},
+};
+// -------------------------------------------------------------------------------------------------
+
+
+
+pub const IDEMPOTENCY_KEY_MAX_LEN: usize = 255;
+
+#[derive(thiserror::Error, Debug)]
+pub enum IdempotencyKeyError {
+    #[error("Idempotency key length error")]
+    LenCharViolated(BacktraceCell),
+    #[error("Idempotency key allowed chars error")]
+    CharsViolated(BacktraceCell),
+}
+
+
+/// Client provided key (usually UUID) of 'Idempotency-Key' HTTP header.
+#[derive(Debug, Clone, PartialEq, derive_more::Display)]
+#[display("{}", _0)]
+pub struct IdempotencyKey(String);
+
+impl IdempotencyKey {
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl core::str::FromStr for IdempotencyKey {
+    type Err = IdempotencyKeyError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || s.len() > IDEMPOTENCY_KEY_MAX_LEN {
+            return Err(IdempotencyKeyError::LenCharViolated(backtrace()))
+        }
+        // Only visible ASCII chars are allowed (as in any HTTP header token).
+        if !s.bytes().all(|b| b.is_ascii_graphic()) {
+            return Err(IdempotencyKeyError::CharsViolated(backtrace()))
+        }
+        Ok(IdempotencyKey(s.to_owned()))
+    }
+}
+
+
+/// Idempotency key together with hash of (normalized) request.
+/// The same key with different request hash is treated as conflict.
+#[derive(Debug, Clone)]
+pub struct Idempotency {
+    pub key: IdempotencyKey,
+    /// SHA-256 (hex).
+    pub request_hash: String,
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use mvv_auth::util::test_unwrap::TestResultUnwrap;
+    use super::IdempotencyKey;
+
+    #[test]
+    fn idempotency_key_from_str() {
+        use core::str::FromStr;
+        IdempotencyKey::from_str("6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55").test_unwrap();
+        IdempotencyKey::from_str("a").test_unwrap();
+
+        assert!(IdempotencyKey::from_str("").is_err());
+        assert!(IdempotencyKey::from_str("with space").is_err());
+        assert!(IdempotencyKey::from_str(&"a".repeat(256)).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+use bigdecimal::{ BigDecimal, RoundingMode };
+use chrono::{ Datelike, NaiveDate };
+use mvv_common::{
+    generate_pg07_decode_from_str as generate_pg_decode_from_str,
+    generate_pg07_delegate_type_info as generate_pg_delegate_type_info,
+    generate_pg07_encode_from_as_str as generate_pg_encode_from_as_str,
+};
+use mvv_common::backtrace::{ backtrace, BacktraceCell };
+use mvv_common_bank_entities::amount::Amount;
+use crate::entity::{ AccountId, TransferId };
+// -------------------------------------------------------------------------------------------------
+
+
+
+/// Accrued (not yet credited) interest is kept with this scale,
+/// it is rounded to minor units of currency only when it is credited.
+pub const INTEREST_ACCRUAL_SCALE: i64 = 10;
+
+
+#[derive(thiserror::Error, Debug)]
+pub enum DayCountConventionError {
+    #[error("Unknown day count convention")]
+    UnknownConvention(BacktraceCell),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum DayCountConvention {
+    /// Actual count of days, year has 365 days (ACT/365 Fixed).
+    #[display("ACT/365")]
+    Act365,
+    /// Every month has 30 days, year has 360 days (30/360, bond basis).
+    #[display("30/360")]
+    Thirty360,
+}
+
+impl DayCountConvention {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DayCountConvention::Act365    => "ACT_365",
+            DayCountConvention::Thirty360 => "THIRTY_360",
+        }
+    }
+
+    pub fn days_in_year(&self) -> i64 {
+        match self {
+            DayCountConvention::Act365    => 365,
+            DayCountConvention::Thirty360 => 360,
+        }
+    }
+
+    /// Count of days between dates ('from' inclusive, 'to' exclusive).
+    pub fn day_count(&self, from: NaiveDate, to: NaiveDate) -> i64 {
+        match self {
+            DayCountConvention::Act365 => (to - from).num_days(),
+            DayCountConvention::Thirty360 => {
+                let d1 = from.day().min(30);
+                let d2 = if d1 == 30 { to.day().min(30) } else { to.day() };
+                360 * i64::from(to.year() - from.year())
+                    + 30 * (i64::from(to.month()) - i64::from(from.month()))
+                    + (i64::from(d2) - i64::from(d1))
+            }
+        }
+    }
+
+    /// Interest of one day (from 'date' to the next day) for 'annual_rate' (in percents).
+    /// With 30/360 it is zero for 31st day of month and it is 2 or 3 days for the last day of February.
+    pub fn daily_interest(&self, balance: &BigDecimal, annual_rate: &BigDecimal, date: NaiveDate) -> BigDecimal {
+        let days = date.succ_opt()
+            .map(|next_date| self.day_count(date, next_date))
+            .unwrap_or(0);
+        let interest = balance * annual_rate * BigDecimal::from(days)
+            / BigDecimal::from(100 * self.days_in_year());
+        interest.with_scale_round(INTEREST_ACCRUAL_SCALE, RoundingMode::HalfUp)
+    }
+}
+
+impl core::str::FromStr for DayCountConvention {
+    type Err = DayCountConventionError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ACT_365"    => Ok(DayCountConvention::Act365),
+            "THIRTY_360" => Ok(DayCountConvention::Thirty360),
+            _ => Err(DayCountConventionError::UnknownConvention(backtrace())),
+        }
+    }
+}
+generate_pg_delegate_type_info! { DayCountConvention, str }
+generate_pg_encode_from_as_str! { DayCountConvention }
+generate_pg_decode_from_str! { DayCountConvention }
+
+
+/// Interest accrued for one day (row of INTEREST_ACCRUALS table).
+#[derive(Debug)]
+#[readonly::make]
+pub struct InterestAccrual {
+    pub account_id: AccountId,
+    pub accrual_date: NaiveDate,
+    /// Balance at the end of day (interest is accrued only for positive balance).
+    pub balance: Amount,
+    /// Annual rate (in percents).
+    pub interest_rate: BigDecimal,
+    pub day_count_convention: DayCountConvention,
+    /// Not rounded (see INTEREST_ACCRUAL_SCALE).
+    pub amount: Amount,
+    /// Only for the last day of month, if accrued interest is credited.
+    pub credit_transfer_id: Option<TransferId>,
+    /// Rounded (down) to minor units of currency, the rest stays accrued till the next month.
+    pub credited_amount: Option<Amount>,
+}
+
+pub struct InterestAccrualParts {
+    pub account_id: AccountId,
+    pub accrual_date: NaiveDate,
+    pub balance: Amount,
+    pub interest_rate: BigDecimal,
+    pub day_count_convention: DayCountConvention,
+    pub amount: Amount,
+    pub credit_transfer_id: Option<TransferId>,
+    pub credited_amount: Option<Amount>,
+}
+
+
+impl InterestAccrual {
+    #[inline]
+    pub fn new(args: new::Args) -> Self {
+        InterestAccrual {
+            account_id: args.account_id,
+            accrual_date: args.accrual_date,
+            balance: args.balance,
+            interest_rate: args.interest_rate,
+            day_count_convention: args.day_count_convention,
+            amount: args.amount,
+            credit_transfer_id: args.credit_transfer_id,
+            credited_amount: args.credited_amount,
+        }
+    }
+
+    pub fn into_parts(self) -> InterestAccrualParts {
+        InterestAccrualParts {
+            account_id: self.account_id,
+            accrual_date: self.accrual_date,
+            balance: self.balance,
+            interest_rate: self.interest_rate,
+            day_count_convention: self.day_count_convention,
+            amount: self.amount,
+            credit_transfer_id: self.credit_transfer_id,
+            credited_amount: self.credited_amount,
+        }
+    }
+}
+
+
+// Attempt to emulate named args in rust.
+pub mod new {
+    pub type Args = super::InterestAccrualParts;
+}
+
+
+/// Accrued interest is credited on the last day of month.
+pub fn is_last_day_of_month(date: NaiveDate) -> bool {
+    date.succ_opt().is_some_and(|next_date| next_date.month() != date.month())
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use chrono::NaiveDate;
+    use mvv_auth::util::test_unwrap::TestResultUnwrap;
+    use super::{ DayCountConvention, is_last_day_of_month };
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::from_str(s).test_unwrap()
+    }
+    fn bd(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).test_unwrap()
+    }
+
+    #[test]
+    fn act_365_day_count() {
+        let act365 = DayCountConvention::Act365;
+        assert_eq!(act365.day_count(date("2024-01-31"), date("2024-02-01")), 1);
+        assert_eq!(act365.day_count(date("2024-02-01"), date("2024-03-01")), 29);
+        assert_eq!(act365.day_count(date("2023-02-01"), date("2023-03-01")), 28);
+        assert_eq!(act365.day_count(date("2024-01-01"), date("2025-01-01")), 366);
+    }
+
+    #[test]
+    fn thirty_360_day_count() {
+        let thirty360 = DayCountConvention::Thirty360;
+        assert_eq!(thirty360.day_count(date("2024-01-01"), date("2024-02-01")), 30);
+        assert_eq!(thirty360.day_count(date("2024-02-01"), date("2024-03-01")), 30);
+        assert_eq!(thirty360.day_count(date("2024-01-01"), date("2025-01-01")), 360);
+        // 31st day of month is not counted
+        assert_eq!(thirty360.day_count(date("2024-01-30"), date("2024-01-31")), 0);
+        assert_eq!(thirty360.day_count(date("2024-01-31"), date("2024-02-01")), 1);
+        // the last day of February covers the rest of 30-day month
+        assert_eq!(thirty360.day_count(date("2023-02-28"), date("2023-03-01")), 3);
+        assert_eq!(thirty360.day_count(date("2024-02-29"), date("2024-03-01")), 2);
+    }
+
+    #[test]
+    fn daily_interest() {
+        // 1000 * 3.65% / 365
+        assert_eq!(
+            DayCountConvention::Act365.daily_interest(&bd("1000"), &bd("3.65"), date("2024-05-10")),
+            bd("0.1000000000"),
+        );
+        // 1000 * 3.6% / 360
+        assert_eq!(
+            DayCountConvention::Thirty360.daily_interest(&bd("1000"), &bd("3.6"), date("2024-05-10")),
+            bd("0.1000000000"),
+        );
+        assert_eq!(
+            DayCountConvention::Thirty360.daily_interest(&bd("1000"), &bd("3.6"), date("2024-05-31")),
+            bd("0"),
+        );
+        // 100 * 5% / 365 = 0.01369863013698...
+        assert_eq!(
+            DayCountConvention::Act365.daily_interest(&bd("100"), &bd("5"), date("2024-05-10")),
+            bd("0.0136986301"),
+        );
+    }
+
+    #[test]
+    fn last_day_of_month() {
+        assert!(is_last_day_of_month(date("2024-01-31")));
+        assert!(is_last_day_of_month(date("2024-02-29")));
+        assert!(is_last_day_of_month(date("2023-02-28")));
+        assert!(!is_last_day_of_month(date("2024-02-28")));
+        assert!(!is_last_day_of_month(date("2024-01-01")));
+    }
+}
@@ -0,0 +1,169 @@
+use chrono::Utc;
+use mvv_common::{
+    generate_from_str_new_type_delegate,
+    generate_into_inner_delegate,
+    generate_pg07_decode_from_str as generate_pg_decode_from_str,
+    generate_pg07_delegate_decode as generate_pg_delegate_decode,
+    generate_pg07_delegate_encode as generate_pg_delegate_encode,
+    generate_pg07_delegate_type_info as generate_pg_delegate_type_info,
+    generate_pg07_encode_from_as_str as generate_pg_encode_from_as_str,
+};
+use mvv_common::backtrace::{ backtrace, BacktraceCell };
+use mvv_common_bank_entities::amount::Amount;
+use crate::entity::{ AccountId, ClientId, TransferId };
+// -------------------------------------------------------------------------------------------------
+
+
+
+#[derive(Debug, Clone, PartialEq, derive_more::Display)]
+#[display("{}", _0)]
+pub struct PendingTransferId( #[allow(dead_code)] uuid::Uuid);
+
+generate_into_inner_delegate!   { PendingTransferId, uuid::Uuid }
+generate_from_str_new_type_delegate! { PendingTransferId, uuid::Uuid, mvv_common::uuid::UuidFormatError }
+generate_pg_delegate_type_info! { PendingTransferId, uuid::Uuid }
+generate_pg_delegate_encode!    { PendingTransferId, uuid::Uuid }
+generate_pg_delegate_decode!    { PendingTransferId, uuid::Uuid }
+
+impl PendingTransferId {
+    pub fn generate() -> Self {
+        PendingTransferId(uuid::Uuid::new_v4())
+    }
+}
+
+
+#[derive(thiserror::Error, Debug)]
+pub enum PendingTransferStatusError {
+    #[error("Unknown pending transfer status")]
+    UnknownStatus(BacktraceCell),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum PendingTransferStatus {
+    /// Waiting for approval.
+    Pending,
+    /// Approved and executed.
+    Approved,
+    Rejected,
+}
+
+impl PendingTransferStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PendingTransferStatus::Pending  => "PENDING",
+            PendingTransferStatus::Approved => "APPROVED",
+            PendingTransferStatus::Rejected => "REJECTED",
+        }
+    }
+}
+
+impl core::str::FromStr for PendingTransferStatus {
+    type Err = PendingTransferStatusError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "PENDING"  => Ok(PendingTransferStatus::Pending),
+            "APPROVED" => Ok(PendingTransferStatus::Approved),
+            "REJECTED" => Ok(PendingTransferStatus::Rejected),
+            _ => Err(PendingTransferStatusError::UnknownStatus(backtrace())),
+        }
+    }
+}
+generate_pg_delegate_type_info! { PendingTransferStatus, str }
+generate_pg_encode_from_as_str! { PendingTransferStatus }
+generate_pg_decode_from_str! { PendingTransferStatus }
+
+
+/// Large transfer which waits for approval by another user (row of PENDING_TRANSFERS table).
+#[derive(Debug)]
+#[readonly::make]
+pub struct PendingTransfer {
+    pub id: PendingTransferId,
+    pub client_id: ClientId,
+    pub from_account_id: AccountId,
+    /// Account of this or another client.
+    pub to_iban: iban::Iban,
+    pub beneficiary_name: Option<String>,
+    /// In 'from' account currency.
+    pub amount: Amount,
+    pub status: PendingTransferStatus,
+    /// User (maker) who requested transfer.
+    pub created_by: String,
+    /// User (checker) who approved/rejected transfer.
+    pub decided_by: Option<String>,
+    pub decided_at: Option<chrono::DateTime<Utc>>,
+    /// Only for approved transfer.
+    pub transfer_id: Option<TransferId>,
+    pub reject_reason: Option<String>,
+    pub created_at: chrono::DateTime<Utc>,
+    pub updated_at: chrono::DateTime<Utc>,
+}
+
+pub struct PendingTransferParts {
+    pub id: PendingTransferId,
+    pub client_id: ClientId,
+    pub from_account_id: AccountId,
+    pub to_iban: iban::Iban,
+    pub beneficiary_name: Option<String>,
+    pub amount: Amount,
+    pub status: PendingTransferStatus,
+    pub created_by: String,
+    pub decided_by: Option<String>,
+    pub decided_at: Option<chrono::DateTime<Utc>>,
+    pub transfer_id: Option<TransferId>,
+    pub reject_reason: Option<String>,
+    pub created_at: chrono::DateTime<Utc>,
+    pub updated_at: chrono::DateTime<Utc>,
+}
+
+
+impl PendingTransfer {
+    #[inline]
+    pub fn new(args: new::Args) -> Self {
+        PendingTransfer {
+            id: args.id,
+            client_id: args.client_id,
+            from_account_id: args.from_account_id,
+            to_iban: args.to_iban,
+            beneficiary_name: args.beneficiary_name,
+            amount: args.amount,
+            status: args.status,
+            created_by: args.created_by,
+            decided_by: args.decided_by,
+            decided_at: args.decided_at,
+            transfer_id: args.transfer_id,
+            reject_reason: args.reject_reason,
+            created_at: args.created_at,
+            updated_at: args.updated_at,
+        }
+    }
+
+    pub fn into_parts(self) -> PendingTransferParts {
+        PendingTransferParts {
+            id: self.id,
+            client_id: self.client_id,
+            from_account_id: self.from_account_id,
+            to_iban: self.to_iban,
+            beneficiary_name: self.beneficiary_name,
+            amount: self.amount,
+            status: self.status,
+            created_by: self.created_by,
+            decided_by: self.decided_by,
+            decided_at: self.decided_at,
+            transfer_id: self.transfer_id,
+            reject_reason: self.reject_reason,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+
+    /// Maker cannot be checker of the same transfer (user names are case-insensitive).
+    pub fn can_be_decided_by(&self, user_name: &str) -> bool {
+        !self.created_by.trim().eq_ignore_ascii_case(user_name.trim())
+    }
+}
+
+
+// Attempt to emulate named args in rust.
+pub mod new {
+    pub type Args = super::PendingTransferParts;
+}
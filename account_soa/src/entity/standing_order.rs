@@ -0,0 +1,217 @@
+use chrono::Utc;
+use mvv_common::{
+    generate_from_str_new_type_delegate,
+    generate_into_inner_delegate,
+    generate_pg07_decode_from_str as generate_pg_decode_from_str,
+    generate_pg07_delegate_decode as generate_pg_delegate_decode,
+    generate_pg07_delegate_encode as generate_pg_delegate_encode,
+    generate_pg07_delegate_type_info as generate_pg_delegate_type_info,
+    generate_pg07_encode_from_as_str as generate_pg_encode_from_as_str,
+};
+use mvv_common::backtrace::{ backtrace, BacktraceCell };
+use mvv_common_bank_entities::amount::Amount;
+use crate::entity::{ AccountId, ClientId, TransferId };
+// -------------------------------------------------------------------------------------------------
+
+
+
+#[derive(Debug, Clone, PartialEq, derive_more::Display)]
+#[display("{}", _0)]
+pub struct StandingOrderId( #[allow(dead_code)] uuid::Uuid);
+
+generate_into_inner_delegate!   { StandingOrderId, uuid::Uuid }
+generate_from_str_new_type_delegate! { StandingOrderId, uuid::Uuid, mvv_common::uuid::UuidFormatError }
+generate_pg_delegate_type_info! { StandingOrderId, uuid::Uuid }
+generate_pg_delegate_encode!    { StandingOrderId, uuid::Uuid }
+generate_pg_delegate_decode!    { StandingOrderId, uuid::Uuid }
+
+impl StandingOrderId {
+    pub fn generate() -> Self {
+        StandingOrderId(uuid::Uuid::new_v4())
+    }
+}
+
+
+#[derive(thiserror::Error, Debug)]
+pub enum StandingOrderPeriodError {
+    #[error("Unknown standing order period")]
+    UnknownPeriod(BacktraceCell),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum StandingOrderPeriod {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl StandingOrderPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StandingOrderPeriod::Daily   => "DAILY",
+            StandingOrderPeriod::Weekly  => "WEEKLY",
+            StandingOrderPeriod::Monthly => "MONTHLY",
+        }
+    }
+
+    /// Time of N-th execution (0 is the first one).
+    /// It is calculated from start time (not from previous execution)
+    /// to keep day of month after short months (31 Jan => 28 Feb => 31 Mar).
+    pub fn execution_at(&self, start_at: chrono::DateTime<Utc>, execution_no: u32)
+        -> Option<chrono::DateTime<Utc>> {
+        match self {
+            StandingOrderPeriod::Daily =>
+                start_at.checked_add_signed(chrono::Duration::days(i64::from(execution_no))),
+            StandingOrderPeriod::Weekly =>
+                start_at.checked_add_signed(chrono::Duration::weeks(i64::from(execution_no))),
+            StandingOrderPeriod::Monthly =>
+                start_at.checked_add_months(chrono::Months::new(execution_no)),
+        }
+    }
+}
+
+impl core::str::FromStr for StandingOrderPeriod {
+    type Err = StandingOrderPeriodError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "DAILY"   => Ok(StandingOrderPeriod::Daily),
+            "WEEKLY"  => Ok(StandingOrderPeriod::Weekly),
+            "MONTHLY" => Ok(StandingOrderPeriod::Monthly),
+            _ => Err(StandingOrderPeriodError::UnknownPeriod(backtrace())),
+        }
+    }
+}
+generate_pg_delegate_type_info! { StandingOrderPeriod, str }
+generate_pg_encode_from_as_str! { StandingOrderPeriod }
+generate_pg_decode_from_str! { StandingOrderPeriod }
+
+
+/// Recurring transfer (row of STANDING_ORDERS table).
+#[derive(Debug)]
+#[readonly::make]
+pub struct StandingOrder {
+    pub id: StandingOrderId,
+    pub client_id: ClientId,
+    pub from_account_id: AccountId,
+    /// Account of this or another client.
+    pub to_iban: iban::Iban,
+    /// It is required if 'to' account belongs to another client.
+    pub beneficiary_name: Option<String>,
+    /// In 'from' account currency.
+    pub amount: Amount,
+    pub period: StandingOrderPeriod,
+    pub start_at: chrono::DateTime<Utc>,
+    pub next_execution_no: u32,
+    pub next_execution_at: chrono::DateTime<Utc>,
+    /// Failed attempts of the next execution (due to not enough balance).
+    pub failed_attempts: u32,
+    pub retry_at: Option<chrono::DateTime<Utc>>,
+    pub last_executed_at: Option<chrono::DateTime<Utc>>,
+    pub last_transfer_id: Option<TransferId>,
+    pub last_error: Option<String>,
+    pub created_at: chrono::DateTime<Utc>,
+    pub updated_at: chrono::DateTime<Utc>,
+}
+
+pub struct StandingOrderParts {
+    pub id: StandingOrderId,
+    pub client_id: ClientId,
+    pub from_account_id: AccountId,
+    pub to_iban: iban::Iban,
+    pub beneficiary_name: Option<String>,
+    pub amount: Amount,
+    pub period: StandingOrderPeriod,
+    pub start_at: chrono::DateTime<Utc>,
+    pub next_execution_no: u32,
+    pub next_execution_at: chrono::DateTime<Utc>,
+    pub failed_attempts: u32,
+    pub retry_at: Option<chrono::DateTime<Utc>>,
+    pub last_executed_at: Option<chrono::DateTime<Utc>>,
+    pub last_transfer_id: Option<TransferId>,
+    pub last_error: Option<String>,
+    pub created_at: chrono::DateTime<Utc>,
+    pub updated_at: chrono::DateTime<Utc>,
+}
+
+
+impl StandingOrder {
+    #[inline]
+    pub fn new(args: new::Args) -> Self {
+        StandingOrder {
+            id: args.id,
+            client_id: args.client_id,
+            from_account_id: args.from_account_id,
+            to_iban: args.to_iban,
+            beneficiary_name: args.beneficiary_name,
+            amount: args.amount,
+            period: args.period,
+            start_at: args.start_at,
+            next_execution_no: args.next_execution_no,
+            next_execution_at: args.next_execution_at,
+            failed_attempts: args.failed_attempts,
+            retry_at: args.retry_at,
+            last_executed_at: args.last_executed_at,
+            last_transfer_id: args.last_transfer_id,
+            last_error: args.last_error,
+            created_at: args.created_at,
+            updated_at: args.updated_at,
+        }
+    }
+
+    pub fn into_parts(self) -> StandingOrderParts {
+        StandingOrderParts {
+            id: self.id,
+            client_id: self.client_id,
+            from_account_id: self.from_account_id,
+            to_iban: self.to_iban,
+            beneficiary_name: self.beneficiary_name,
+            amount: self.amount,
+            period: self.period,
+            start_at: self.start_at,
+            next_execution_no: self.next_execution_no,
+            next_execution_at: self.next_execution_at,
+            failed_attempts: self.failed_attempts,
+            retry_at: self.retry_at,
+            last_executed_at: self.last_executed_at,
+            last_transfer_id: self.last_transfer_id,
+            last_error: self.last_error,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+
+// Attempt to emulate named args in rust.
+pub mod new {
+    pub type Args = super::StandingOrderParts;
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+    use mvv_auth::util::test_unwrap::TestResultUnwrap;
+    use super::StandingOrderPeriod;
+
+    #[test]
+    fn monthly_execution_at_keeps_day_of_month() {
+        let start_at = chrono::DateTime::<chrono::Utc>::from_str("2024-01-31T10:00:00Z").test_unwrap();
+        let period = StandingOrderPeriod::Monthly;
+
+        assert_eq!(period.execution_at(start_at, 0).test_unwrap().to_rfc3339(), "2024-01-31T10:00:00+00:00");
+        assert_eq!(period.execution_at(start_at, 1).test_unwrap().to_rfc3339(), "2024-02-29T10:00:00+00:00");
+        assert_eq!(period.execution_at(start_at, 2).test_unwrap().to_rfc3339(), "2024-03-31T10:00:00+00:00");
+    }
+
+    #[test]
+    fn daily_and_weekly_execution_at() {
+        let start_at = chrono::DateTime::<chrono::Utc>::from_str("2024-01-31T10:00:00Z").test_unwrap();
+
+        assert_eq!(StandingOrderPeriod::Daily.execution_at(start_at, 1).test_unwrap().to_rfc3339(),
+                   "2024-02-01T10:00:00+00:00");
+        assert_eq!(StandingOrderPeriod::Weekly.execution_at(start_at, 2).test_unwrap().to_rfc3339(),
+                   "2024-02-14T10:00:00+00:00");
+    }
+}
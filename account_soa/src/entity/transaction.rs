@@ -0,0 +1,109 @@
+use bigdecimal::BigDecimal;
+use chrono::Utc;
+use mvv_common::{
+    generate_from_str_new_type_delegate,
+    generate_into_inner_delegate,
+    generate_pg07_delegate_decode as generate_pg_delegate_decode,
+    generate_pg07_delegate_encode as generate_pg_delegate_encode,
+    generate_pg07_delegate_type_info as generate_pg_delegate_type_info,
+};
+use mvv_common_bank_entities::amount::Amount;
+use crate::entity::AccountId;
+// -------------------------------------------------------------------------------------------------
+
+
+
+/// ID of the whole transfer (both debit and credit ledger entries have the same transfer ID).
+#[derive(Debug, Clone, PartialEq, derive_more::Display)]
+#[display("{}", _0)]
+pub struct TransferId( #[allow(dead_code)] uuid::Uuid);
+
+generate_into_inner_delegate!   { TransferId, uuid::Uuid }
+generate_from_str_new_type_delegate! { TransferId, uuid::Uuid, mvv_common::uuid::UuidFormatError }
+generate_pg_delegate_type_info! { TransferId, uuid::Uuid }
+generate_pg_delegate_encode!    { TransferId, uuid::Uuid }
+generate_pg_delegate_decode!    { TransferId, uuid::Uuid }
+
+impl TransferId {
+    pub fn generate() -> Self {
+        TransferId(uuid::Uuid::new_v4())
+    }
+}
+
+
+/// Ledger entry (row of TRANSACTIONS table).
+/// Amount is signed: negative for debit, positive for credit.
+#[derive(Debug)]
+#[readonly::make]
+pub struct AccountTransaction {
+    pub id: i64,
+    pub transfer_id: TransferId,
+    pub account_id: AccountId,
+    pub counterparty_account_id: Option<AccountId>,
+    pub amount: Amount,
+    pub balance_after: Amount,
+    /// Only for cross-currency transfer (source currency => target currency).
+    pub exchange_rate: Option<BigDecimal>,
+    pub exchange_rate_at: Option<chrono::DateTime<Utc>>,
+    /// Only for compensating (reversal) entry: transfer which is reversed by this entry.
+    pub reversed_transfer_id: Option<TransferId>,
+    /// Only for fee entry: transfer for which fee is charged.
+    pub fee_for_transfer_id: Option<TransferId>,
+    pub created_at: chrono::DateTime<Utc>,
+}
+
+pub struct AccountTransactionParts {
+    pub id: i64,
+    pub transfer_id: TransferId,
+    pub account_id: AccountId,
+    pub counterparty_account_id: Option<AccountId>,
+    pub amount: Amount,
+    pub balance_after: Amount,
+    pub exchange_rate: Option<BigDecimal>,
+    pub exchange_rate_at: Option<chrono::DateTime<Utc>>,
+    pub reversed_transfer_id: Option<TransferId>,
+    pub fee_for_transfer_id: Option<TransferId>,
+    pub created_at: chrono::DateTime<Utc>,
+}
+
+
+impl AccountTransaction {
+    #[inline]
+    pub fn new(args: new::Args) -> Self {
+        AccountTransaction {
+            id: args.id,
+            transfer_id: args.transfer_id,
+            account_id: args.account_id,
+            counterparty_account_id: args.counterparty_account_id,
+            amount: args.amount,
+            balance_after: args.balance_after,
+            exchange_rate: args.exchange_rate,
+            exchange_rate_at: args.exchange_rate_at,
+            reversed_transfer_id: args.reversed_transfer_id,
+            fee_for_transfer_id: args.fee_for_transfer_id,
+            created_at: args.created_at,
+        }
+    }
+
+    pub fn into_parts(self) -> AccountTransactionParts {
+        AccountTransactionParts {
+            id: self.id,
+            transfer_id: self.transfer_id,
+            account_id: self.account_id,
+            counterparty_account_id: self.counterparty_account_id,
+            amount: self.amount,
+            balance_after: self.balance_after,
+            exchange_rate: self.exchange_rate,
+            exchange_rate_at: self.exchange_rate_at,
+            reversed_transfer_id: self.reversed_transfer_id,
+            fee_for_transfer_id: self.fee_for_transfer_id,
+            created_at: self.created_at,
+        }
+    }
+}
+
+
+// Attempt to emulate named args in rust.
+pub mod new {
+    pub type Args = super::AccountTransactionParts;
+}
@@ -0,0 +1,82 @@
+use mvv_common::{
+    generate_pg07_decode_from_str as generate_pg_decode_from_str,
+    generate_pg07_delegate_type_info as generate_pg_delegate_type_info,
+    generate_pg07_encode_from_as_str as generate_pg_encode_from_as_str,
+};
+use mvv_common::backtrace::{ backtrace, BacktraceCell };
+// -------------------------------------------------------------------------------------------------
+
+
+
+#[derive(thiserror::Error, Debug)]
+pub enum ClientTierError {
+    #[error("Unknown client tier")]
+    UnknownTier(BacktraceCell),
+}
+
+/// The same tiers as ClientFeature (Standard/Business/SuperBusiness) in account_web.
+/// Client gets the highest tier of its features (CLIENTS.BUSINESS_USER/SUPER_BUSINESS_USER).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum ClientTier {
+    Standard,
+    Business,
+    SuperBusiness,
+}
+
+impl ClientTier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClientTier::Standard      => "STANDARD",
+            ClientTier::Business      => "BUSINESS",
+            ClientTier::SuperBusiness => "SUPER_BUSINESS",
+        }
+    }
+
+    pub fn from_features(business_user: bool, super_business_user: bool) -> Self {
+        if super_business_user { ClientTier::SuperBusiness }
+        else if business_user { ClientTier::Business }
+        else { ClientTier::Standard }
+    }
+}
+
+impl core::str::FromStr for ClientTier {
+    type Err = ClientTierError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "STANDARD"       => Ok(ClientTier::Standard),
+            "BUSINESS"       => Ok(ClientTier::Business),
+            "SUPER_BUSINESS" => Ok(ClientTier::SuperBusiness),
+            _ => Err(ClientTierError::UnknownTier(backtrace())),
+        }
+    }
+}
+generate_pg_delegate_type_info! { ClientTier, str }
+generate_pg_encode_from_as_str! { ClientTier }
+generate_pg_decode_from_str! { ClientTier }
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum TransferLimitKind {
+    /// Max amount of one transfer.
+    #[display("per-transfer")]
+    PerTransfer,
+    /// Max total amount of outgoing transfers for the last 24 hours (per currency).
+    #[display("daily")]
+    Daily,
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::ClientTier;
+
+    #[test]
+    fn client_tier_from_features() {
+        assert_eq!(ClientTier::from_features(false, false), ClientTier::Standard);
+        assert_eq!(ClientTier::from_features(true, false), ClientTier::Business);
+        assert_eq!(ClientTier::from_features(true, true), ClientTier::SuperBusiness);
+        // Inconsistent flags, but the highest one wins.
+        assert_eq!(ClientTier::from_features(false, true), ClientTier::SuperBusiness);
+    }
+}
@@ -0,0 +1,406 @@
+use chrono::Utc;
+use serde::Serialize;
+use mvv_common::{
+    generate_from_str_new_type_delegate,
+    generate_into_inner_delegate,
+    generate_pg07_decode_from_str as generate_pg_decode_from_str,
+    generate_pg07_delegate_decode as generate_pg_delegate_decode,
+    generate_pg07_delegate_encode as generate_pg_delegate_encode,
+    generate_pg07_delegate_type_info as generate_pg_delegate_type_info,
+    generate_pg07_encode_from_as_str as generate_pg_encode_from_as_str,
+};
+use mvv_common::backtrace::{ backtrace, BacktraceCell };
+use mvv_common::secure::SecureString;
+use crate::entity::ClientId;
+use crate::entity::account_event::{ AccountEvent, EventAmount };
+// -------------------------------------------------------------------------------------------------
+
+
+
+#[derive(Debug, Clone, PartialEq, derive_more::Display)]
+#[display("{}", _0)]
+pub struct WebhookSubscriptionId( #[allow(dead_code)] uuid::Uuid);
+
+generate_into_inner_delegate!   { WebhookSubscriptionId, uuid::Uuid }
+generate_from_str_new_type_delegate! { WebhookSubscriptionId, uuid::Uuid, mvv_common::uuid::UuidFormatError }
+generate_pg_delegate_type_info! { WebhookSubscriptionId, uuid::Uuid }
+generate_pg_delegate_encode!    { WebhookSubscriptionId, uuid::Uuid }
+generate_pg_delegate_decode!    { WebhookSubscriptionId, uuid::Uuid }
+
+impl WebhookSubscriptionId {
+    pub fn generate() -> Self {
+        WebhookSubscriptionId(uuid::Uuid::new_v4())
+    }
+}
+
+
+#[derive(thiserror::Error, Debug)]
+pub enum WebhookEnumError {
+    #[error("Unknown webhook event type")]
+    UnknownEventType(BacktraceCell),
+    #[error("Unknown webhook delivery status")]
+    UnknownDeliveryStatus(BacktraceCell),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum WebhookEventType {
+    /// Money is credited to account of client (transfer or reversal of outgoing transfer).
+    TransferIncoming,
+    /// Money is debited from account of client (transfer or reversal of incoming transfer).
+    TransferOutgoing,
+}
+
+impl WebhookEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventType::TransferIncoming => "TRANSFER_INCOMING",
+            WebhookEventType::TransferOutgoing => "TRANSFER_OUTGOING",
+        }
+    }
+}
+
+impl core::str::FromStr for WebhookEventType {
+    type Err = WebhookEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "TRANSFER_INCOMING" => Ok(WebhookEventType::TransferIncoming),
+            "TRANSFER_OUTGOING" => Ok(WebhookEventType::TransferOutgoing),
+            _ => Err(WebhookEnumError::UnknownEventType(backtrace())),
+        }
+    }
+}
+generate_pg_delegate_type_info! { WebhookEventType, str }
+generate_pg_encode_from_as_str! { WebhookEventType }
+generate_pg_decode_from_str! { WebhookEventType }
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum WebhookDeliveryStatus {
+    /// Not delivered yet (it will be retried).
+    Pending,
+    Delivered,
+    /// All attempts are failed (dead letter), it is not retried anymore.
+    Dead,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookDeliveryStatus::Pending   => "PENDING",
+            WebhookDeliveryStatus::Delivered => "DELIVERED",
+            WebhookDeliveryStatus::Dead      => "DEAD",
+        }
+    }
+}
+
+impl core::str::FromStr for WebhookDeliveryStatus {
+    type Err = WebhookEnumError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "PENDING"   => Ok(WebhookDeliveryStatus::Pending),
+            "DELIVERED" => Ok(WebhookDeliveryStatus::Delivered),
+            "DEAD"      => Ok(WebhookDeliveryStatus::Dead),
+            _ => Err(WebhookEnumError::UnknownDeliveryStatus(backtrace())),
+        }
+    }
+}
+generate_pg_delegate_type_info! { WebhookDeliveryStatus, str }
+generate_pg_encode_from_as_str! { WebhookDeliveryStatus }
+generate_pg_decode_from_str! { WebhookDeliveryStatus }
+
+
+/// Row of WEBHOOK_SUBSCRIPTIONS table.
+#[derive(Debug)]
+#[readonly::make]
+pub struct WebhookSubscription {
+    pub id: WebhookSubscriptionId,
+    pub client_id: ClientId,
+    pub url: String,
+    /// Key of HMAC-SHA256 signature of deliveries.
+    pub secret: SecureString,
+    pub event_types: Vec<WebhookEventType>,
+    /// Notifications are not created for inactive subscription.
+    pub active: bool,
+    pub created_at: chrono::DateTime<Utc>,
+    pub updated_at: chrono::DateTime<Utc>,
+}
+
+pub struct WebhookSubscriptionParts {
+    pub id: WebhookSubscriptionId,
+    pub client_id: ClientId,
+    pub url: String,
+    pub secret: SecureString,
+    pub event_types: Vec<WebhookEventType>,
+    pub active: bool,
+    pub created_at: chrono::DateTime<Utc>,
+    pub updated_at: chrono::DateTime<Utc>,
+}
+
+impl WebhookSubscription {
+    #[inline]
+    pub fn new(args: new::Args) -> Self {
+        WebhookSubscription {
+            id: args.id,
+            client_id: args.client_id,
+            url: args.url,
+            secret: args.secret,
+            event_types: args.event_types,
+            active: args.active,
+            created_at: args.created_at,
+            updated_at: args.updated_at,
+        }
+    }
+
+    pub fn into_parts(self) -> WebhookSubscriptionParts {
+        WebhookSubscriptionParts {
+            id: self.id,
+            client_id: self.client_id,
+            url: self.url,
+            secret: self.secret,
+            event_types: self.event_types,
+            active: self.active,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+
+// Attempt to emulate named args in rust.
+pub mod new {
+    pub type Args = super::WebhookSubscriptionParts;
+}
+
+
+/// Row of WEBHOOK_DELIVERIES table (with its attempts).
+#[derive(Debug)]
+#[readonly::make]
+pub struct WebhookDelivery {
+    pub id: uuid::Uuid,
+    pub subscription_id: WebhookSubscriptionId,
+    /// The same for all subscriptions (receivers should use it to skip duplicates).
+    pub event_id: uuid::Uuid,
+    pub event_type: WebhookEventType,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: u32,
+    /// Only for pending delivery.
+    pub next_attempt_at: Option<chrono::DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<chrono::DateTime<Utc>>,
+    pub created_at: chrono::DateTime<Utc>,
+    /// From the first attempt to the last one.
+    pub attempt_history: Vec<WebhookDeliveryAttempt>,
+}
+
+pub struct WebhookDeliveryParts {
+    pub id: uuid::Uuid,
+    pub subscription_id: WebhookSubscriptionId,
+    pub event_id: uuid::Uuid,
+    pub event_type: WebhookEventType,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: u32,
+    pub next_attempt_at: Option<chrono::DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<chrono::DateTime<Utc>>,
+    pub created_at: chrono::DateTime<Utc>,
+    pub attempt_history: Vec<WebhookDeliveryAttempt>,
+}
+
+impl WebhookDelivery {
+    #[inline]
+    pub fn new(args: WebhookDeliveryParts) -> Self {
+        WebhookDelivery {
+            id: args.id,
+            subscription_id: args.subscription_id,
+            event_id: args.event_id,
+            event_type: args.event_type,
+            status: args.status,
+            attempts: args.attempts,
+            next_attempt_at: args.next_attempt_at,
+            last_error: args.last_error,
+            delivered_at: args.delivered_at,
+            created_at: args.created_at,
+            attempt_history: args.attempt_history,
+        }
+    }
+
+    pub fn into_parts(self) -> WebhookDeliveryParts {
+        WebhookDeliveryParts {
+            id: self.id,
+            subscription_id: self.subscription_id,
+            event_id: self.event_id,
+            event_type: self.event_type,
+            status: self.status,
+            attempts: self.attempts,
+            next_attempt_at: self.next_attempt_at,
+            last_error: self.last_error,
+            delivered_at: self.delivered_at,
+            created_at: self.created_at,
+            attempt_history: self.attempt_history,
+        }
+    }
+}
+
+
+/// Row of WEBHOOK_DELIVERY_ATTEMPTS table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WebhookDeliveryAttempt {
+    pub attempt_no: u32,
+    pub attempted_at: chrono::DateTime<Utc>,
+    /// None if there is no response (connection error, timeout).
+    pub response_status: Option<u16>,
+    pub error: Option<String>,
+    pub duration_ms: u32,
+}
+
+
+/// Payload of webhook delivery (JSON).
+/// It is built for one client, so it contains only balance of account of this client.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferNotification {
+    pub event_id: uuid::Uuid,
+    /// TRANSFER_INCOMING or TRANSFER_OUTGOING
+    #[serde(rename = "type")]
+    pub event_type: &'static str,
+    pub transfer_id: uuid::Uuid,
+    /// Account of client.
+    pub account_id: uuid::Uuid,
+    pub counterparty_account_id: uuid::Uuid,
+    /// Positive (in account currency).
+    pub amount: EventAmount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee: Option<EventAmount>,
+    pub balance_after: EventAmount,
+    /// Only for reversal.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reversed_transfer_id: Option<uuid::Uuid>,
+    pub created_at: chrono::DateTime<Utc>,
+}
+
+/// Notification of one client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientNotification {
+    pub client_id: uuid::Uuid,
+    pub event_type: WebhookEventType,
+    pub notification: TransferNotification,
+}
+
+
+/// Every transfer is 'outgoing' for 'from' client and 'incoming' for 'to' client
+/// (transfer between accounts of the same client produces both of them).
+/// Other events are not sent to webhooks.
+pub fn transfer_notifications(event: &AccountEvent, created_at: chrono::DateTime<Utc>) -> Vec<ClientNotification> {
+
+    let notification = |client_id: &uuid::Uuid, event_type: WebhookEventType, transfer_id: &uuid::Uuid,
+                        account_id: &uuid::Uuid, counterparty_account_id: &uuid::Uuid,
+                        amount: &EventAmount, fee: Option<&EventAmount>, balance_after: &EventAmount,
+                        reversed_transfer_id: Option<&uuid::Uuid>| ClientNotification {
+        client_id: *client_id,
+        event_type,
+        notification: TransferNotification {
+            event_id: uuid::Uuid::new_v4(),
+            event_type: event_type.as_str(),
+            transfer_id: *transfer_id,
+            account_id: *account_id,
+            counterparty_account_id: *counterparty_account_id,
+            amount: amount.clone(),
+            fee: fee.cloned(),
+            balance_after: balance_after.clone(),
+            reversed_transfer_id: reversed_transfer_id.copied(),
+            created_at,
+        },
+    };
+
+    match event {
+        AccountEvent::TransferCompleted {
+            transfer_id, from_account_id, from_client_id, to_account_id, to_client_id,
+            amount, credit_amount, fee, from_balance_after, to_balance_after,
+        } => vec![
+            notification(from_client_id, WebhookEventType::TransferOutgoing, transfer_id,
+                from_account_id, to_account_id, amount, fee.as_ref(), from_balance_after, None),
+            notification(to_client_id, WebhookEventType::TransferIncoming, transfer_id,
+                to_account_id, from_account_id, credit_amount, None, to_balance_after, None),
+        ],
+        AccountEvent::TransferReversed {
+            reversal_transfer_id, reversed_transfer_id, from_account_id, from_client_id,
+            to_account_id, to_client_id, refund_amount, chargeback_amount, fee_refund,
+            from_balance_after, to_balance_after,
+        } => vec![
+            notification(to_client_id, WebhookEventType::TransferOutgoing, reversal_transfer_id,
+                to_account_id, from_account_id, chargeback_amount, None, to_balance_after, Some(reversed_transfer_id)),
+            notification(from_client_id, WebhookEventType::TransferIncoming, reversal_transfer_id,
+                from_account_id, to_account_id, refund_amount, fee_refund.as_ref(), from_balance_after, Some(reversed_transfer_id)),
+        ],
+        AccountEvent::AccountOpened { .. }
+        | AccountEvent::AccountStatusChanged { .. }
+        | AccountEvent::OverdraftLimitChanged { .. }
+        | AccountEvent::InterestCredited { .. } => Vec::new(),
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use mvv_auth::util::test_unwrap::TestResultUnwrap;
+    use mvv_common_bank_entities::make_currency;
+    use crate::entity::account_event::{ AccountEvent, EventAmount };
+    use super::{ transfer_notifications, WebhookEventType };
+
+    fn uuid(s: &str) -> uuid::Uuid {
+        uuid::Uuid::from_str(s).test_unwrap()
+    }
+    fn usd(s: &str) -> EventAmount {
+        EventAmount { value: BigDecimal::from_str(s).test_unwrap(), currency: make_currency!("USD") }
+    }
+
+    #[test]
+    fn transfer_is_outgoing_for_sender_and_incoming_for_beneficiary() {
+        let event = AccountEvent::TransferCompleted {
+            transfer_id: uuid("6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55"),
+            from_account_id: uuid("00000000-0000-0000-0000-000000000101"),
+            from_client_id: uuid("00000000-0000-0000-0000-000000000001"),
+            to_account_id: uuid("00000000-0000-0000-0000-000000000201"),
+            to_client_id: uuid("00000000-0000-0000-0000-000000000002"),
+            amount: usd("10.5"),
+            credit_amount: usd("10.5"),
+            fee: Some(usd("0.5")),
+            from_balance_after: usd("139"),
+            to_balance_after: usd("110.5"),
+        };
+
+        let notifications = transfer_notifications(&event, chrono::Local::now().to_utc());
+        assert_eq!(notifications.len(), 2);
+
+        let outgoing = &notifications[0];
+        assert_eq!(outgoing.client_id, uuid("00000000-0000-0000-0000-000000000001"));
+        assert_eq!(outgoing.event_type, WebhookEventType::TransferOutgoing);
+        assert_eq!(outgoing.notification.account_id, uuid("00000000-0000-0000-0000-000000000101"));
+        assert_eq!(outgoing.notification.fee, Some(usd("0.5")));
+        assert_eq!(outgoing.notification.balance_after, usd("139"));
+
+        let incoming = &notifications[1];
+        assert_eq!(incoming.client_id, uuid("00000000-0000-0000-0000-000000000002"));
+        assert_eq!(incoming.event_type, WebhookEventType::TransferIncoming);
+        assert_eq!(incoming.notification.account_id, uuid("00000000-0000-0000-0000-000000000201"));
+        assert_eq!(incoming.notification.fee, None);
+        // Balance of sender is not visible for beneficiary.
+        assert_eq!(incoming.notification.balance_after, usd("110.5"));
+
+        assert_ne!(outgoing.notification.event_id, incoming.notification.event_id);
+    }
+
+    #[test]
+    fn other_events_are_not_notified() {
+        let event = AccountEvent::InterestCredited {
+            transfer_id: uuid("6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55"),
+            account_id: uuid("00000000-0000-0000-0000-000000000101"),
+            amount: usd("0.5"),
+            balance_after: usd("150.5"),
+        };
+        assert!(transfer_notifications(&event, chrono::Local::now().to_utc()).is_empty());
+    }
+}
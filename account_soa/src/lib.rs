@@ -5,3 +5,4 @@ pub mod database;
 pub mod rest;
 pub mod web;
 pub mod cfg;
+pub mod cli;
@@ -9,3 +9,4 @@ pub mod auth;
 
 mod investigation;
 mod path;
+mod statement;
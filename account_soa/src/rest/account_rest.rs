@@ -1,25 +1,44 @@
+use std::convert::Infallible;
 use std::sync::Arc;
 use axum::{
-    Router, Json, routing::{ post as POST }, extract::{ Path, State, },
+    Router, Json, routing::{ post as POST }, extract::{ Path, Query, State, },
+    http::{ header, HeaderMap, StatusCode },
+    response::{ IntoResponse, Response, sse::{ Event as SseEvent, KeepAlive, Sse } },
 };
+use futures::Stream;
 use axum_valid::{
     Validified, /*Modified,*/
     Valid as Valid,
 };
 use bigdecimal::BigDecimal;
-use tracing::{ debug, info /*, error*/ };
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use tracing::{ debug, info, error };
 use log::{ debug as log_debug, info as log_info /*, error as log_error*/ };
 use serde::{ Deserialize };
 use utoipa::OpenApi;
 use crate::{
-    entity::{ self, AccountId, ClientId },
+    entity::{
+        self, AccountId, ClientId, HoldId, PendingTransferId, StandingOrderId, TransferId,
+        account::AccountName, idempotency::{ Idempotency, IdempotencyKey }, webhook::WebhookSubscriptionId,
+    },
     rest::{
-        auth::{ RequiredAuthorizationExtension, Role, },
+        auth::{ ExtractCurrentUser, RequiredAuthorizationExtension, Role, },
         app_dependencies::Dependencies,
         dto::{ self, CURRENCY_PATTERN, ID_PATTERN },
         error_rest::{ RestAppError },
+        statement::StatementFormat,
+    },
+    service::{
+        account_service::{
+            AccountService, AccountIdWrapper, AccountTransactionsQuery, MAX_TRANSACTIONS_PAGE_SIZE,
+            TransferLeg, TransferLegStatus, MAX_BATCH_TRANSFER_LEGS,
+            NewStandingOrder, StandingOrderChange, NewPendingTransfer,
+            NewWebhookSubscription, WebhookSubscriptionChange,
+        },
+        exchange_rate::ExpectedRate,
+        account_changes::{ AccountChange, AccountChangeListener },
     },
-    service::{ account_service::{ AccountService }, },
 };
 use super::path;
 use mvv_common::{
@@ -42,9 +61,49 @@ pub fn accounts_rest_router <
     let r = Router::new()
         .route_from_open_api(open_api_route!(rest_get_client_account::<AccountS>))
         .route_from_open_api(open_api_route!(rest_get_client_accounts::<AccountS>))
+        .route_from_open_api(open_api_route!(rest_stream_client_accounts::<AccountS>))
         .route_from_open_api(open_api_route!(rest_transfer_amount::<AccountS>))
+        .route_from_open_api(open_api_route!(rest_transfer_batch::<AccountS>))
+        .route_from_open_api(open_api_route!(rest_quote_transfer::<AccountS>))
+        .route_from_open_api(open_api_route!(rest_get_client_account_transactions::<AccountS>))
+        .route_from_open_api(open_api_route!(rest_get_client_account_statement::<AccountS>))
+        .route_from_open_api(open_api_route!(rest_get_client_account_holds::<AccountS>))
+        .route_from_open_api(open_api_route!(rest_get_client_standing_orders::<AccountS>))
+        .route_from_open_api(open_api_route!(rest_get_client_standing_order::<AccountS>))
+        .route_from_open_api(open_api_route!(rest_get_client_pending_transfers::<AccountS>))
+        .route_from_open_api(open_api_route!(rest_get_client_webhook_subscriptions::<AccountS>))
+        .route_from_open_api(open_api_route!(rest_get_client_webhook_subscription::<AccountS>))
+        .route_from_open_api(open_api_route!(rest_get_client_webhook_deliveries::<AccountS>))
         .with_state(Arc::clone(&shared_state))
         .role_required(Role::Read)
+        .merge(Router::new()
+            .route_from_open_api(open_api_route!(rest_open_client_account::<AccountS>))
+            .route_from_open_api(open_api_route!(rest_change_client_account_status::<AccountS>))
+            .route_from_open_api(open_api_route!(rest_authorize_hold::<AccountS>))
+            .route_from_open_api(open_api_route!(rest_capture_hold::<AccountS>))
+            .route_from_open_api(open_api_route!(rest_release_hold::<AccountS>))
+            .route_from_open_api(open_api_route!(rest_create_standing_order::<AccountS>))
+            .route_from_open_api(open_api_route!(rest_change_standing_order::<AccountS>))
+            .route_from_open_api(open_api_route!(rest_delete_standing_order::<AccountS>))
+            .route_from_open_api(open_api_route!(rest_create_webhook_subscription::<AccountS>))
+            .route_from_open_api(open_api_route!(rest_change_webhook_subscription::<AccountS>))
+            .route_from_open_api(open_api_route!(rest_delete_webhook_subscription::<AccountS>))
+            .with_state(Arc::clone(&shared_state))
+            .role_required(Role::Write)
+        )
+        .merge(Router::new()
+            .route_from_open_api(open_api_route!(rest_change_client_account_overdraft_limit::<AccountS>))
+            .route_from_open_api(open_api_route!(rest_reverse_transfer::<AccountS>))
+            .with_state(Arc::clone(&shared_state))
+            .role_required(Role::Admin)
+        )
+        // Approval of large transfers (checker permission).
+        .merge(Router::new()
+            .route_from_open_api(open_api_route!(rest_approve_pending_transfer::<AccountS>))
+            .route_from_open_api(open_api_route!(rest_reject_pending_transfer::<AccountS>))
+            .with_state(Arc::clone(&shared_state))
+            .role_required(Role::SuperUser)
+        )
         // investigation block
         .merge(Router::new()
             .route("/validate_test/input_validate_1", POST(call_rest_input_validate_by_validator::<AccountS>))
@@ -114,6 +173,231 @@ async fn rest_get_client_accounts <
 }
 
 
+#[utoipa::path(
+    get,
+    path = "/client/{client_id}/accounts/stream",
+    operation_id = "streamClientAccounts", // in json format
+    responses(
+        (status = 200,
+         description = "Server-Sent Events stream, every 'accounts' event contains all client accounts. \
+                        The first event is sent at once, next ones after every change of client accounts.",
+         content_type = "text/event-stream", body = Vec<Account>)
+    ),
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_stream_client_accounts <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+) -> Result<Sse<impl Stream<Item = Result<SseEvent, Infallible>>>, RestAppError> {
+    let accounts_stream = rest_service.stream_accounts(client_id).await ?;
+    Ok(Sse::new(accounts_stream).keep_alive(KeepAlive::default()))
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = OpenAccountRequest)]
+#[derive(Debug, Deserialize)]
+#[derive(validify::Validify)]
+struct OpenAccountRequest {
+    /// Account (short) name, it should be unique for client
+    #[validate(length(min=1, max=256))]
+    #[schema(example = "My USD account")]
+    name: String,
+
+    // 'validify' cannot automatically use third-party strings for length validation, but it is ok with regex.
+    #[validate(regex(CURRENCY_PATTERN))] // for 'validify'
+    #[schema(value_type = String, example = "USD")]
+    currency: InnerCurStr,
+}
+
+#[utoipa::path(
+    post,
+    path = "/client/{client_id}/account",
+    operation_id = "openClientAccount", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+    ),
+    responses(
+        (status = 201, description = "Account is opened (IBAN is generated)", body = Account),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_open_client_account <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Json(open_request): Json<OpenAccountRequest>,
+) -> Result<(StatusCode, Json<dto::Account>), RestAppError> {
+
+    // 'utoipa' conflicts with 'validify', we need to call validation manually.
+    use validify::Validate;
+    open_request.validate()
+        .map_err(|err|RestAppError::ValidifyErrors(err, backtrace())) ?;
+
+    let account = rest_service.open_account(client_id, open_request).await ?;
+    Ok((StatusCode::CREATED, Json(account)))
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = ChangeAccountStatusRequest)]
+#[derive(Debug, Deserialize)]
+struct ChangeAccountStatusRequest {
+    #[schema(value_type = AccountStatus)]
+    status: dto::AccountStatus,
+}
+
+#[utoipa::path(
+    put,
+    path = "/client/{client_id}/account/{account_id}/status",
+    operation_id = "changeClientAccountStatus", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("account_id" = String, Path, description = "Account ID or IBAN", example="UA713736572172926969841832393"),
+    ),
+    responses(
+        (status = 200, description = "Account with changed status", body = Account),
+        (status = 409, description = "Status cannot be changed (for example, closed account or not empty account is closed)"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_change_client_account_status <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(account_id): Path<path::AccountId>,
+    Json(status_request): Json<ChangeAccountStatusRequest>,
+) -> Result<Json<dto::Account>, RestAppError> {
+    rest_service.change_account_status(client_id, account_id, status_request.status).to_json().await
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = ChangeOverdraftLimitRequest)]
+#[derive(Debug, Deserialize)]
+#[derive(validify::Validify)]
+#[serde(rename_all = "camelCase")]
+struct ChangeOverdraftLimitRequest {
+    /// Should have account currency
+    #[validify]
+    #[schema(value_type = Amount)]
+    overdraft_limit: dto::Amount,
+}
+
+#[utoipa::path(
+    put,
+    path = "/client/{client_id}/account/{account_id}/overdraft-limit",
+    operation_id = "changeClientAccountOverdraftLimit", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("account_id" = String, Path, description = "Account ID or IBAN", example="UA713736572172926969841832393"),
+    ),
+    responses(
+        (status = 200, description = "Account with changed overdraft limit", body = Account),
+        (status = 400, description = "Negative limit, wrong currency or current balance is less than -limit"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_change_client_account_overdraft_limit <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(account_id): Path<path::AccountId>,
+    Json(limit_request): Json<ChangeOverdraftLimitRequest>,
+) -> Result<Json<dto::Account>, RestAppError> {
+
+    // 'utoipa' conflicts with 'validify', we need to call validation manually.
+    use validify::Validate;
+    limit_request.validate()
+        .map_err(|err|RestAppError::ValidifyErrors(err, backtrace())) ?;
+
+    rest_service.change_account_overdraft_limit(client_id, account_id, limit_request.overdraft_limit).to_json().await
+}
+
+
+const DEFAULT_TRANSACTIONS_PAGE_SIZE: u32 = 50;
+
+#[derive(Debug, Deserialize)]
+struct AccountTransactionsQueryParams {
+    from: Option<chrono::DateTime<Utc>>,
+    to: Option<chrono::DateTime<Utc>>,
+    cursor: Option<String>,
+    limit: Option<u32>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/client/{client_id}/account/{account_id}/transactions",
+    operation_id = "getClientAccountTransactions", // in json format
+    responses(
+        (status = 200, description = "Account transactions (from newest to oldest)", body = AccountTransactions)
+    ),
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("account_id" = String, Path, description = "Account ID or IBAN", example="UA713736572172926969841832393"),
+        ("from" = Option<chrono::DateTime<Utc>>, Query, description = "Start of period (inclusive)", example = "2021-11-10T00:00:00Z"),
+        ("to" = Option<chrono::DateTime<Utc>>, Query, description = "End of period (exclusive)", example = "2021-12-10T00:00:00Z"),
+        ("cursor" = Option<String>, Query, description = "'nextCursor' value from previous page"),
+        ("limit" = Option<u32>, Query, description = "Page size (max 200)", example = 50),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_get_client_account_transactions <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(account_id): Path<path::AccountId>,
+    Query(query): Query<AccountTransactionsQueryParams>,
+) -> Result<Json<dto::AccountTransactions>, RestAppError> {
+    rest_service.get_account_transactions(client_id, account_id, query).to_json().await
+}
+
+
+#[derive(Debug, Deserialize)]
+struct AccountStatementQueryParams {
+    from: chrono::DateTime<Utc>,
+    to: chrono::DateTime<Utc>,
+    format: StatementFormat,
+}
+
+#[utoipa::path(
+    get,
+    path = "/client/{client_id}/account/{account_id}/statement",
+    operation_id = "getClientAccountStatement", // in json format
+    responses(
+        (status = 200, description = "Account statement (CSV or camt.053 XML)", body = String,
+            content_type = ["text/csv", "application/xml"]),
+    ),
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("account_id" = String, Path, description = "Account ID or IBAN", example="UA713736572172926969841832393"),
+        ("from" = chrono::DateTime<Utc>, Query, description = "Start of period (inclusive)", example = "2021-11-01T00:00:00Z"),
+        ("to" = chrono::DateTime<Utc>, Query, description = "End of period (exclusive)", example = "2021-12-01T00:00:00Z"),
+        ("format" = String, Query, description = "Statement format: 'csv' or 'camt053'", example = "csv"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_get_client_account_statement <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(account_id): Path<path::AccountId>,
+    Query(query): Query<AccountStatementQueryParams>,
+) -> Result<Response, RestAppError> {
+    rest_service.get_account_statement(client_id, account_id, query).await
+}
+
+
 #[derive(utoipa::ToSchema)]
 #[schema(as = TransferAmountRequest)] // api::models::Amount)]
 #[derive(PartialEq, Eq, Deserialize)]
@@ -125,11 +409,19 @@ struct TransferAmountRequest {
     #[schema(example = "00000000-0000-0000-0000-000000000101")]
     from_account: String,
 
-    /// Account ID (UUID) or IBAN
+    /// Account ID (UUID) or IBAN.
+    /// Account of another client can be used only by IBAN (and with 'beneficiary_name').
     #[validate(length(min=1, max=320), regex(ID_PATTERN))]
     #[schema(example = "00000000-0000-0000-0000-000000000102")]
     to_account: String,
 
+    /// First and last name of 'to' account owner.
+    /// It is required if 'to' account belongs to another client.
+    #[serde(default)]
+    #[validate(length(min=1, max=201))]
+    #[schema(example = "Tigra Bohdan")]
+    beneficiary_name: Option<String>,
+
     #[serde(with = "mvv_common::json::serde_json_bd::bd_with")]
     #[educe(Debug(method(mvv_common_bank_entities::bd::bd_dbg_fmt)))]
     #[schema(value_type = f64, example = "12.34")]
@@ -139,17 +431,85 @@ struct TransferAmountRequest {
     #[validate(regex(CURRENCY_PATTERN))] // for 'validify'
     #[schema(value_type = String, example = "USD")]
     currency: InnerCurStr,
+
+    /// Expected exchange rate (1 unit of 'from' account currency in 'to' account currency).
+    /// It makes sense only for accounts with different currencies.
+    /// Transfer is rejected if current rate differs from it more than 'max_rate_slippage'.
+    #[serde(default, with = "mvv_common::json::serde_json_bd::bd_opt_with")]
+    #[schema(value_type = Option<f64>, example = "41.25")]
+    expected_rate: Option<BigDecimal>,
+
+    /// Max allowed relative difference between current and expected rates (0.01 is 1%).
+    #[serde(default, with = "mvv_common::json::serde_json_bd::bd_opt_with")]
+    #[schema(value_type = Option<f64>, example = "0.01")]
+    max_rate_slippage: Option<BigDecimal>,
+}
+
+impl TransferAmountRequest {
+    /// SHA-256 (hex) of normalized request (used for idempotency key validation).
+    fn request_hash(&self) -> String {
+        use sha2::{ Digest, Sha256 };
+
+        let opt_bd_to_string = |bd: &Option<BigDecimal>| bd.as_ref()
+            .map(|bd| bd.normalized().to_string())
+            .unwrap_or_default();
+
+        let normalized = format!("{}\n{}\n{}\n{}\n{}\n{}\n{}",
+            self.from_account, self.to_account, self.amount.normalized(), self.currency,
+            opt_bd_to_string(&self.expected_rate), opt_bd_to_string(&self.max_rate_slippage),
+            self.beneficiary_name.as_deref().unwrap_or_default(),
+        );
+        hex::encode(Sha256::digest(normalized.as_bytes()))
+    }
+
+    fn expected_rate(&self) -> Result<Option<ExpectedRate>, RestAppError> {
+        let zero = BigDecimal::from(0i32);
+
+        match (&self.expected_rate, &self.max_rate_slippage) {
+            (None, None) => Ok(None),
+            (None, Some(_)) => Err(RestAppError::IllegalArgument(
+                anyhow::anyhow!("'max_rate_slippage' can be used only together with 'expected_rate'."))),
+            (Some(rate), max_slippage) => {
+                let max_slippage = max_slippage.clone()
+                    .unwrap_or_else(|| DEFAULT_MAX_RATE_SLIPPAGE.clone());
+                if rate <= &zero {
+                    return Err(RestAppError::IllegalArgument(
+                        anyhow::anyhow!("Expected rate should be positive.")));
+                }
+                if max_slippage < zero || max_slippage >= BigDecimal::from(1i32) {
+                    return Err(RestAppError::IllegalArgument(
+                        anyhow::anyhow!("Max rate slippage should be in range [0, 1).")));
+                }
+                Ok(Some(ExpectedRate { rate: rate.clone(), max_slippage }))
+            }
+        }
+    }
 }
 
+/// It is used if 'expected_rate' is passed without 'max_rate_slippage' (0.5%).
+static DEFAULT_MAX_RATE_SLIPPAGE: Lazy<BigDecimal> = Lazy::new(|| BigDecimal::new(5.into(), 3));
+
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
 #[utoipa::path(
     post,
     path = "/client/{client_id}/transfer",
     operation_id = "transferAmount", // in json format
     params(
         ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("Idempotency-Key" = Option<String>, Header,
+            description = "Unique (per client) request key. Retry with the same key returns result of already done transfer.",
+            example = "6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55"),
     ),
     responses(
-        (status = 201, description = "Success/Failure", body = ())
+        (status = 201, description = "Transfer is done (status COMPLETED)", body = TransferOutcome),
+        (status = 202, description = "Amount is above approval threshold, transfer is stored as pending \
+                                      and waits for approval by another user (status PENDING_APPROVAL)",
+                       body = TransferOutcome),
+        (status = 400, description = "Invalid request, not enough balance \
+                                      or beneficiary name does not match owner of 'to' account"),
+        (status = 409, description = "Idempotency key is already used for another request \
+                                      or current exchange rate differs from expected one"),
     ),
     tag = "mvv_account_soa", // as package/namespace
 )]
@@ -158,174 +518,1946 @@ async fn rest_transfer_amount <
 > (
     State(rest_service): State<Arc<AccountRest<AccountS>>>,
     Path(client_id): Path<path::ClientId>,
+    current_user: ExtractCurrentUser,
+    headers: HeaderMap,
     // 'utoipa' conflicts with 'validify'
     // Validified(Json(transfer_request)): Validified<Json<TransferAmountRequest>>,
     Json(transfer_request): Json<TransferAmountRequest>,
 )
-    -> Result<(), RestAppError> {
+    -> Result<(StatusCode, Json<dto::TransferOutcome>), RestAppError> {
 
     // 'utoipa' conflicts with 'validify', we need to call validation manually.
     use validify::Validate;
     transfer_request.validate()
         .map_err(|err|RestAppError::ValidifyErrors(err, backtrace())) ?;
 
-    rest_service.transfer(client_id, transfer_request).await
+    let idempotency_key = headers.get(IDEMPOTENCY_KEY_HEADER)
+        .map(|key| key.to_str().map(str::to_owned).err_to_std_err_bad_req())
+        .transpose() ?;
+
+    let transfer_res = rest_service.transfer(
+        client_id, current_user.user.username, transfer_request, idempotency_key).await ?;
+    let status_code = match transfer_res.status {
+        dto::TransferOutcomeStatus::Completed => StatusCode::CREATED,
+        dto::TransferOutcomeStatus::PendingApproval => StatusCode::ACCEPTED,
+    };
+    Ok((status_code, Json(transfer_res)))
 }
 
 
-#[derive(OpenApi)]
-#[openapi(
-    paths(
-        crate::rest::account_rest::rest_get_client_account,
-        crate::rest::account_rest::rest_get_client_accounts,
-        crate::rest::account_rest::rest_transfer_amount,
+#[utoipa::path(
+    post,
+    path = "/client/{client_id}/transfer/quote",
+    operation_id = "quoteTransfer", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
     ),
-    components(
-        schemas(
-            crate::rest::dto::Amount,
-            crate::rest::dto::Account,
-            TransferAmountRequest,
-        ),
+    responses(
+        (status = 200, description = "Fee and credit amount of transfer (transfer is not executed)", body = TransferQuote),
+        (status = 400, description = "Invalid request"),
+        (status = 409, description = "Account is not active"),
     ),
-// nest(
-//     // you can nest sub apis here
-//     (path = "/api/v1/ones", api = one::OneApi)
-// )
+    tag = "mvv_account_soa", // as package/namespace
 )]
-pub struct AccountRestOpenApi;
-
+async fn rest_quote_transfer <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    // The same request as for transfer ('beneficiary_name' and expected rate are ignored).
+    Json(transfer_request): Json<TransferAmountRequest>,
+)
+    -> Result<Json<dto::TransferQuote>, RestAppError> {
 
-// It will be 'current user' in wab-app.
-// pub struct CurrentUserAccountRest <AS: AccountService> {
+    use validify::Validate;
+    transfer_request.validate()
+        .map_err(|err|RestAppError::ValidifyErrors(err, backtrace())) ?;
 
-pub struct AccountRest <AS: AccountService> {
-    pub account_service: Arc<AS>,
+    rest_service.quote_transfer(client_id, transfer_request).to_json().await
 }
 
-impl<AS: AccountService> AccountRest<AS> {
-
-    #[tracing::instrument( skip(self) )]
-    pub async fn get_account(&self, client_id: path::ClientId, account_id: path::AccountId)
-        -> Result<dto::Account, RestAppError> {
-
-        use mvv_common::obj_ext::ValExt;
-        use core::str::FromStr;
-
-        debug!("TD get_user_account as debug");
-        info! ("TI get_user_account as info");
-        // error!("TI get_user_account as error");
-
-        log_debug!("LD get_user_account as debug");
-        log_info! ("LI get_user_account as info");
-        // log_error!("LE get_user_account as error");
 
-        let client_id = ClientId::from_str(&client_id.into_inner())
-            .err_to_bad_req() ?;
-        let account_id = account_id.into_inner();
+#[derive(utoipa::ToSchema)]
+#[schema(as = TransferBatchRequest)]
+#[derive(PartialEq, Eq, Deserialize)]
+#[derive(Debug)]
+struct TransferBatchRequest {
+    /// Transfers (legs) which are done all together or none of them.
+    legs: Vec<TransferAmountRequest>,
+}
 
-        let is_internal_account_id = account_id.len().is_one_of2(36, 38);
-        let account: entity::Account = if is_internal_account_id {
-            let account_id = AccountId::from_str(&account_id)
-                .err_to_bad_req() ?;
-            self.account_service.get_client_account_by_id(client_id, account_id).await ?
-        } else {
-            let iban = iban::Iban::from_str(&account_id)
-                .err_to_std_err_bad_req() ?;
-            self.account_service.get_client_account_by_iban(client_id, iban).await ?
-        };
+impl TransferBatchRequest {
+    /// SHA-256 (hex) of normalized request (used for idempotency key validation).
+    fn request_hash(&self) -> String {
+        use sha2::{ Digest, Sha256 };
 
-        Ok(map_account_to_rest(account))
+        let legs_hashes = self.legs.iter()
+            .map(TransferAmountRequest::request_hash)
+            .collect::<Vec<_>>()
+            .join("\n");
+        hex::encode(Sha256::digest(legs_hashes.as_bytes()))
     }
+}
 
+#[utoipa::path(
+    post,
+    path = "/client/{client_id}/transfers/batch",
+    operation_id = "transferBatch", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("Idempotency-Key" = Option<String>, Header,
+            description = "Unique (per client) request key. Retry with the same key returns result of already committed batch.",
+            example = "0d5c7a9e-3f1b-4b6a-8e2d-9a4c1f7b3e60"),
+    ),
+    responses(
+        (status = 201, description = "All transfers are done", body = TransferBatchResponse),
+        (status = 400, description = "Invalid request"),
+        (status = 409, description = "Idempotency key is already used for another request"),
+        (status = 422, description = "Batch is rejected (nothing is done), see status of every leg \
+                                      (leg with amount above approval threshold is failed)",
+            body = TransferBatchResponse),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_transfer_batch <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    headers: HeaderMap,
+    Json(batch_request): Json<TransferBatchRequest>,
+)
+    -> Result<(StatusCode, Json<dto::TransferBatchResponse>), RestAppError> {
 
-    #[tracing::instrument( skip(self) )]
-    pub async fn get_accounts(&self, client_id: path::ClientId) -> Result<Vec<dto::Account>, RestAppError> {
-        let client_id = ClientId::from_str(&client_id.into_inner())
-            .err_to_bad_req() ?;
-        let accounts = self.account_service.get_client_accounts(client_id).await ?;
+    if batch_request.legs.is_empty() || batch_request.legs.len() > MAX_BATCH_TRANSFER_LEGS {
+        return Err(RestAppError::IllegalArgument(anyhow::anyhow!(
+            "Batch should have from 1 to {MAX_BATCH_TRANSFER_LEGS} legs.")));
+    }
 
-        let accounts_dto = accounts.into_iter()
-                .map(move |ac| map_account_to_rest(ac))
-                .collect::<Vec<_>>();
-        Ok(accounts_dto)
+    // 'utoipa' conflicts with 'validify', we need to call validation manually.
+    use validify::Validate;
+    for (index, leg) in batch_request.legs.iter().enumerate() {
+        leg.validate()
+            .map_err(|err| RestAppError::IllegalArgument(anyhow::anyhow!("Invalid leg [{index}]: {err}"))) ?;
     }
 
+    let idempotency_key = headers.get(IDEMPOTENCY_KEY_HEADER)
+        .map(|key| key.to_str().map(str::to_owned).err_to_std_err_bad_req())
+        .transpose() ?;
 
-    #[tracing::instrument( skip(self) )]
-    pub async fn transfer(&self, client_id: path::ClientId, transfer_request: TransferAmountRequest)
-        -> Result<(), RestAppError> {
+    let batch_res = rest_service.transfer_batch(client_id, batch_request, idempotency_key).await ?;
+    let status = if batch_res.committed { StatusCode::CREATED } else { StatusCode::UNPROCESSABLE_ENTITY };
+    Ok((status, Json(batch_res)))
+}
+
+
+#[utoipa::path(
+    get,
+    path = "/client/{client_id}/account/{account_id}/holds",
+    operation_id = "getClientAccountHolds", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("account_id" = String, Path, description = "Account ID or IBAN", example="UA713736572172926969841832393"),
+    ),
+    responses(
+        (status = 200, description = "Account holds (from newest to oldest)", body = Vec<Hold>),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_get_client_account_holds <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(account_id): Path<path::AccountId>,
+) -> Result<Json<Vec<dto::Hold>>, RestAppError> {
+    rest_service.get_account_holds(client_id, account_id).to_json().await
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = AuthorizeHoldRequest)]
+#[derive(Debug, Deserialize)]
+#[derive(validify::Validify)]
+struct AuthorizeHoldRequest {
+    /// Amount to reserve (in account currency)
+    #[validify]
+    #[schema(value_type = Amount)]
+    amount: dto::Amount,
+
+    /// Hold is automatically expired after this time
+    #[validate(range(min = 1., max = 2592000.))] // 30 days
+    #[schema(example = 604800)]
+    ttl_seconds: u32,
+}
+
+#[utoipa::path(
+    post,
+    path = "/client/{client_id}/account/{account_id}/hold",
+    operation_id = "authorizeHold", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("account_id" = String, Path, description = "Account ID or IBAN", example="UA713736572172926969841832393"),
+    ),
+    responses(
+        (status = 201, description = "Amount is reserved", body = Hold),
+        (status = 422, description = "Amount is above approval threshold"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_authorize_hold <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(account_id): Path<path::AccountId>,
+    Json(hold_request): Json<AuthorizeHoldRequest>,
+) -> Result<(StatusCode, Json<dto::Hold>), RestAppError> {
+
+    // 'utoipa' conflicts with 'validify', we need to call validation manually.
+    use validify::Validate;
+    hold_request.validate()
+        .map_err(|err|RestAppError::ValidifyErrors(err, backtrace())) ?;
+
+    let hold = rest_service.authorize_hold(client_id, account_id, hold_request).await ?;
+    Ok((StatusCode::CREATED, Json(hold)))
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = CaptureHoldRequest)]
+#[derive(Debug, Deserialize)]
+#[derive(validify::Validify)]
+struct CaptureHoldRequest {
+    /// Account ID (UUID) or IBAN
+    #[validate(length(min=1, max=320), regex(ID_PATTERN))]
+    #[schema(example = "00000000-0000-0000-0000-000000000102")]
+    to_account: String,
+
+    /// First and last name of 'to' account owner.
+    /// It is required if 'to' account belongs to another client.
+    #[serde(default)]
+    #[validate(length(min=1, max=201))]
+    #[schema(example = "Tigra Bohdan")]
+    beneficiary_name: Option<String>,
+
+    /// Captured amount (it should not exceed held one). The whole held amount is captured if it is absent.
+    #[serde(default)]
+    #[schema(value_type = Option<Amount>)]
+    amount: Option<dto::Amount>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/client/{client_id}/hold/{hold_id}/capture",
+    operation_id = "captureHold", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("hold_id" = String, Path, description = "Hold ID", example = "3f0c2a4e-5b7d-4c1e-9a2b-8d6e4f1a0c37"),
+    ),
+    responses(
+        (status = 201, description = "Hold is converted to transfer", body = TransferAmountResponse),
+        (status = 409, description = "Hold is already captured, released or expired"),
+        (status = 422, description = "Amount is above approval threshold (it cannot be captured without approval)"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_capture_hold <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(hold_id): Path<path::HoldId>,
+    Json(capture_request): Json<CaptureHoldRequest>,
+) -> Result<(StatusCode, Json<dto::TransferAmountResponse>), RestAppError> {
+
+    // 'utoipa' conflicts with 'validify', we need to call validation manually.
+    use validify::Validate;
+    capture_request.validate()
+        .map_err(|err|RestAppError::ValidifyErrors(err, backtrace())) ?;
+
+    let transfer_res = rest_service.capture_hold(client_id, hold_id, capture_request).await ?;
+    Ok((StatusCode::CREATED, Json(transfer_res)))
+}
+
+
+#[utoipa::path(
+    post,
+    path = "/client/{client_id}/hold/{hold_id}/release",
+    operation_id = "releaseHold", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("hold_id" = String, Path, description = "Hold ID", example = "3f0c2a4e-5b7d-4c1e-9a2b-8d6e4f1a0c37"),
+    ),
+    responses(
+        (status = 200, description = "Released hold", body = Hold),
+        (status = 409, description = "Hold is already captured, released or expired"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_release_hold <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(hold_id): Path<path::HoldId>,
+) -> Result<Json<dto::Hold>, RestAppError> {
+    rest_service.release_hold(client_id, hold_id).to_json().await
+}
+
+
+#[utoipa::path(
+    post,
+    path = "/client/{client_id}/transfer/{transfer_id}/reversal",
+    operation_id = "reverseTransfer", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id (owner of debited account)", example = "00000000-0000-0000-0000-000000000001"),
+        ("transfer_id" = String, Path, description = "ID of transfer to reverse", example = "6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55"),
+    ),
+    responses(
+        (status = 201, description = "Transfer is reversed, its fee is refunded \
+                                      (ID of compensating transfer is returned)", body = TransferAmountResponse),
+        (status = 400, description = "Transfer is not found"),
+        (status = 409, description = "Transfer is already reversed, it is reversal or fee itself, \
+                                      account is not active or beneficiary account does not have enough funds"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_reverse_transfer <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(transfer_id): Path<path::TransferId>,
+) -> Result<(StatusCode, Json<dto::TransferAmountResponse>), RestAppError> {
+    let reversal_res = rest_service.reverse_transfer(client_id, transfer_id).await ?;
+    Ok((StatusCode::CREATED, Json(reversal_res)))
+}
+
+
+#[utoipa::path(
+    get,
+    path = "/client/{client_id}/standing-order/all",
+    operation_id = "getClientStandingOrders", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+    ),
+    responses(
+        (status = 200, description = "Standing orders (recurring transfers) of client", body = Vec<StandingOrder>),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_get_client_standing_orders <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+) -> Result<Json<Vec<dto::StandingOrder>>, RestAppError> {
+    rest_service.get_standing_orders(client_id).to_json().await
+}
+
+
+#[utoipa::path(
+    get,
+    path = "/client/{client_id}/standing-order/{order_id}",
+    operation_id = "getClientStandingOrder", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("order_id" = String, Path, description = "Standing order ID", example = "0c9d3a7e-2f4b-4e8a-b1c6-5d7e9f0a2b4c"),
+    ),
+    responses(
+        (status = 200, description = "Standing order", body = StandingOrder),
+        (status = 400, description = "Standing order is not found"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_get_client_standing_order <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(order_id): Path<path::StandingOrderId>,
+) -> Result<Json<dto::StandingOrder>, RestAppError> {
+    rest_service.get_standing_order(client_id, order_id).to_json().await
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = StandingOrderRequest)]
+#[derive(Debug, Deserialize)]
+#[derive(validify::Validify)]
+struct StandingOrderRequest {
+    /// Account ID (UUID) or IBAN
+    #[validate(length(min=1, max=320), regex(ID_PATTERN))]
+    #[schema(example = "00000000-0000-0000-0000-000000000101")]
+    from_account: String,
+
+    /// Account ID (UUID) or IBAN.
+    /// Account of another client can be used only by IBAN (and with 'beneficiary_name').
+    #[validate(length(min=1, max=320), regex(ID_PATTERN))]
+    #[schema(example = "UA853052990000000000000002011")]
+    to_account: String,
+
+    /// First and last name of 'to' account owner.
+    /// It is required if 'to' account belongs to another client.
+    #[serde(default)]
+    #[validate(length(min=1, max=201))]
+    #[schema(example = "Tigra Bohdan")]
+    beneficiary_name: Option<String>,
+
+    /// Amount of every transfer (in 'from' account currency)
+    #[validify]
+    #[schema(value_type = Amount)]
+    amount: dto::Amount,
+
+    #[schema(value_type = StandingOrderPeriod)]
+    period: dto::StandingOrderPeriod,
+
+    /// Time of the first transfer (should be in the future)
+    start_at: chrono::DateTime<Utc>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/client/{client_id}/standing-order",
+    operation_id = "createStandingOrder", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+    ),
+    responses(
+        (status = 201, description = "Standing order is created", body = StandingOrder),
+        (status = 422, description = "Amount is above approval threshold (order is executed without approval)"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_create_standing_order <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Json(order_request): Json<StandingOrderRequest>,
+) -> Result<(StatusCode, Json<dto::StandingOrder>), RestAppError> {
+
+    // 'utoipa' conflicts with 'validify', we need to call validation manually.
+    use validify::Validate;
+    order_request.validate()
+        .map_err(|err|RestAppError::ValidifyErrors(err, backtrace())) ?;
+
+    let standing_order = rest_service.create_standing_order(client_id, order_request).await ?;
+    Ok((StatusCode::CREATED, Json(standing_order)))
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = ChangeStandingOrderRequest)]
+#[derive(Debug, Deserialize)]
+#[derive(validify::Validify)]
+struct ChangeStandingOrderRequest {
+    /// First and last name of 'to' account owner.
+    #[serde(default)]
+    #[validate(length(min=1, max=201))]
+    #[schema(example = "Tigra Bohdan")]
+    beneficiary_name: Option<String>,
+
+    /// Amount of every transfer (in 'from' account currency)
+    #[validify]
+    #[schema(value_type = Amount)]
+    amount: dto::Amount,
+
+    #[schema(value_type = StandingOrderPeriod)]
+    period: dto::StandingOrderPeriod,
+
+    /// Schedule is started again from this time (should be in the future)
+    start_at: chrono::DateTime<Utc>,
+}
+
+#[utoipa::path(
+    put,
+    path = "/client/{client_id}/standing-order/{order_id}",
+    operation_id = "changeStandingOrder", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("order_id" = String, Path, description = "Standing order ID", example = "0c9d3a7e-2f4b-4e8a-b1c6-5d7e9f0a2b4c"),
+    ),
+    responses(
+        (status = 200, description = "Changed standing order", body = StandingOrder),
+        (status = 400, description = "Standing order is not found"),
+        (status = 422, description = "Amount is above approval threshold (order is executed without approval)"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_change_standing_order <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(order_id): Path<path::StandingOrderId>,
+    Json(change_request): Json<ChangeStandingOrderRequest>,
+) -> Result<Json<dto::StandingOrder>, RestAppError> {
+
+    // 'utoipa' conflicts with 'validify', we need to call validation manually.
+    use validify::Validate;
+    change_request.validate()
+        .map_err(|err|RestAppError::ValidifyErrors(err, backtrace())) ?;
+
+    rest_service.change_standing_order(client_id, order_id, change_request).to_json().await
+}
+
+
+#[utoipa::path(
+    delete,
+    path = "/client/{client_id}/standing-order/{order_id}",
+    operation_id = "deleteStandingOrder", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("order_id" = String, Path, description = "Standing order ID", example = "0c9d3a7e-2f4b-4e8a-b1c6-5d7e9f0a2b4c"),
+    ),
+    responses(
+        (status = 204, description = "Standing order is deleted"),
+        (status = 400, description = "Standing order is not found"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_delete_standing_order <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(order_id): Path<path::StandingOrderId>,
+) -> Result<StatusCode, RestAppError> {
+    rest_service.delete_standing_order(client_id, order_id).await ?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+
+#[utoipa::path(
+    get,
+    path = "/client/{client_id}/webhook/all",
+    operation_id = "getClientWebhookSubscriptions", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+    ),
+    responses(
+        (status = 200, description = "Webhook subscriptions of client", body = Vec<WebhookSubscription>),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_get_client_webhook_subscriptions <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+) -> Result<Json<Vec<dto::WebhookSubscription>>, RestAppError> {
+    rest_service.get_webhook_subscriptions(client_id).to_json().await
+}
+
+
+#[utoipa::path(
+    get,
+    path = "/client/{client_id}/webhook/{subscription_id}",
+    operation_id = "getClientWebhookSubscription", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("subscription_id" = String, Path, description = "Webhook subscription ID", example = "9b2e4c6a-1d3f-4a5b-8c7d-0e1f2a3b4c5d"),
+    ),
+    responses(
+        (status = 200, description = "Webhook subscription", body = WebhookSubscription),
+        (status = 400, description = "Webhook subscription is not found"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_get_client_webhook_subscription <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(subscription_id): Path<path::WebhookSubscriptionId>,
+) -> Result<Json<dto::WebhookSubscription>, RestAppError> {
+    rest_service.get_webhook_subscription(client_id, subscription_id).to_json().await
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = WebhookSubscriptionRequest)]
+#[derive(Debug, Deserialize)]
+#[derive(validify::Validify)]
+struct WebhookSubscriptionRequest {
+    /// HTTP(S) URL, notifications are sent by POST
+    #[validate(length(min=1, max=2000))]
+    #[schema(example = "https://partner.example.com/bank/webhook")]
+    url: String,
+
+    /// Key of HMAC-SHA256 signature ('X-Webhook-Signature' header), it is never returned
+    #[validate(length(min=16, max=256))]
+    #[schema(example = "0123456789abcdef0123456789abcdef")]
+    secret: String,
+
+    #[validate(length(min=1, max=2))]
+    #[schema(value_type = Vec<WebhookEventType>)]
+    event_types: Vec<dto::WebhookEventType>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/client/{client_id}/webhook",
+    operation_id = "createWebhookSubscription", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+    ),
+    responses(
+        (status = 201, description = "Webhook subscription is created", body = WebhookSubscription),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_create_webhook_subscription <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Json(subscription_request): Json<WebhookSubscriptionRequest>,
+) -> Result<(StatusCode, Json<dto::WebhookSubscription>), RestAppError> {
+
+    // 'utoipa' conflicts with 'validify', we need to call validation manually.
+    use validify::Validate;
+    subscription_request.validate()
+        .map_err(|err|RestAppError::ValidifyErrors(err, backtrace())) ?;
+
+    let subscription = rest_service.create_webhook_subscription(client_id, subscription_request).await ?;
+    Ok((StatusCode::CREATED, Json(subscription)))
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = ChangeWebhookSubscriptionRequest)]
+#[derive(Debug, Deserialize)]
+#[derive(validify::Validify)]
+struct ChangeWebhookSubscriptionRequest {
+    #[validate(length(min=1, max=2000))]
+    #[schema(example = "https://partner.example.com/bank/webhook")]
+    url: String,
+
+    /// New secret (current one is kept if it is absent)
+    #[serde(default)]
+    #[validate(length(min=16, max=256))]
+    #[schema(example = "0123456789abcdef0123456789abcdef")]
+    secret: Option<String>,
+
+    #[validate(length(min=1, max=2))]
+    #[schema(value_type = Vec<WebhookEventType>)]
+    event_types: Vec<dto::WebhookEventType>,
+
+    active: bool,
+}
+
+#[utoipa::path(
+    put,
+    path = "/client/{client_id}/webhook/{subscription_id}",
+    operation_id = "changeWebhookSubscription", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("subscription_id" = String, Path, description = "Webhook subscription ID", example = "9b2e4c6a-1d3f-4a5b-8c7d-0e1f2a3b4c5d"),
+    ),
+    responses(
+        (status = 200, description = "Changed webhook subscription", body = WebhookSubscription),
+        (status = 400, description = "Webhook subscription is not found"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_change_webhook_subscription <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(subscription_id): Path<path::WebhookSubscriptionId>,
+    Json(change_request): Json<ChangeWebhookSubscriptionRequest>,
+) -> Result<Json<dto::WebhookSubscription>, RestAppError> {
+
+    // 'utoipa' conflicts with 'validify', we need to call validation manually.
+    use validify::Validate;
+    change_request.validate()
+        .map_err(|err|RestAppError::ValidifyErrors(err, backtrace())) ?;
+
+    rest_service.change_webhook_subscription(client_id, subscription_id, change_request).to_json().await
+}
+
+
+#[utoipa::path(
+    delete,
+    path = "/client/{client_id}/webhook/{subscription_id}",
+    operation_id = "deleteWebhookSubscription", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("subscription_id" = String, Path, description = "Webhook subscription ID", example = "9b2e4c6a-1d3f-4a5b-8c7d-0e1f2a3b4c5d"),
+    ),
+    responses(
+        (status = 204, description = "Webhook subscription (and its delivery history) is deleted"),
+        (status = 400, description = "Webhook subscription is not found"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_delete_webhook_subscription <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(subscription_id): Path<path::WebhookSubscriptionId>,
+) -> Result<StatusCode, RestAppError> {
+    rest_service.delete_webhook_subscription(client_id, subscription_id).await ?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+
+#[utoipa::path(
+    get,
+    path = "/client/{client_id}/webhook/{subscription_id}/delivery/all",
+    operation_id = "getClientWebhookDeliveries", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("subscription_id" = String, Path, description = "Webhook subscription ID", example = "9b2e4c6a-1d3f-4a5b-8c7d-0e1f2a3b4c5d"),
+    ),
+    responses(
+        (status = 200, description = "The last deliveries (newest first) with history of attempts", body = Vec<WebhookDelivery>),
+        (status = 400, description = "Webhook subscription is not found"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_get_client_webhook_deliveries <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(subscription_id): Path<path::WebhookSubscriptionId>,
+) -> Result<Json<Vec<dto::WebhookDelivery>>, RestAppError> {
+    rest_service.get_webhook_deliveries(client_id, subscription_id).to_json().await
+}
+
+
+#[utoipa::path(
+    get,
+    path = "/client/{client_id}/pending-transfer/all",
+    operation_id = "getClientPendingTransfers", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+    ),
+    responses(
+        (status = 200, description = "Pending (and already decided) transfers of client (newest first)",
+                       body = Vec<PendingTransfer>),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_get_client_pending_transfers <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+) -> Result<Json<Vec<dto::PendingTransfer>>, RestAppError> {
+    rest_service.get_pending_transfers(client_id).to_json().await
+}
+
+
+#[utoipa::path(
+    post,
+    path = "/client/{client_id}/pending-transfer/{pending_transfer_id}/approval",
+    operation_id = "approvePendingTransfer", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("pending_transfer_id" = String, Path, description = "Pending transfer ID", example = "3f1c2b7d-9a4e-4c8b-8e21-7b5d0c6a9f13"),
+    ),
+    responses(
+        (status = 200, description = "Transfer is approved and executed", body = PendingTransfer),
+        (status = 400, description = "Pending transfer is not found or transfer cannot be executed"),
+        (status = 403, description = "Creator of transfer cannot approve it"),
+        (status = 409, description = "Transfer is already approved or rejected"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_approve_pending_transfer <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(pending_transfer_id): Path<path::PendingTransferId>,
+    current_user: ExtractCurrentUser,
+) -> Result<Json<dto::PendingTransfer>, RestAppError> {
+    rest_service.approve_pending_transfer(client_id, pending_transfer_id, current_user.user.username).to_json().await
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = RejectPendingTransferRequest)]
+#[derive(Debug, Deserialize)]
+#[derive(validify::Validify)]
+struct RejectPendingTransferRequest {
+    #[serde(default)]
+    #[validate(length(min=1, max=1000))]
+    #[schema(example = "Suspicious beneficiary")]
+    reason: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/client/{client_id}/pending-transfer/{pending_transfer_id}/rejection",
+    operation_id = "rejectPendingTransfer", // in json format
+    params(
+        ("client_id" = String, Path, description = "Client id", example = "00000000-0000-0000-0000-000000000001"),
+        ("pending_transfer_id" = String, Path, description = "Pending transfer ID", example = "3f1c2b7d-9a4e-4c8b-8e21-7b5d0c6a9f13"),
+    ),
+    responses(
+        (status = 200, description = "Transfer is rejected", body = PendingTransfer),
+        (status = 400, description = "Pending transfer is not found"),
+        (status = 403, description = "Creator of transfer cannot reject it"),
+        (status = 409, description = "Transfer is already approved or rejected"),
+    ),
+    tag = "mvv_account_soa", // as package/namespace
+)]
+async fn rest_reject_pending_transfer <
+    AccountS: AccountService + 'static,
+> (
+    State(rest_service): State<Arc<AccountRest<AccountS>>>,
+    Path(client_id): Path<path::ClientId>,
+    Path(pending_transfer_id): Path<path::PendingTransferId>,
+    current_user: ExtractCurrentUser,
+    Json(reject_request): Json<RejectPendingTransferRequest>,
+) -> Result<Json<dto::PendingTransfer>, RestAppError> {
+
+    // 'utoipa' conflicts with 'validify', we need to call validation manually.
+    use validify::Validate;
+    reject_request.validate()
+        .map_err(|err|RestAppError::ValidifyErrors(err, backtrace())) ?;
+
+    rest_service.reject_pending_transfer(client_id, pending_transfer_id, current_user.user.username, reject_request)
+        .to_json().await
+}
+
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        crate::rest::account_rest::rest_get_client_account,
+        crate::rest::account_rest::rest_get_client_accounts,
+        crate::rest::account_rest::rest_stream_client_accounts,
+        crate::rest::account_rest::rest_transfer_amount,
+        crate::rest::account_rest::rest_transfer_batch,
+        crate::rest::account_rest::rest_quote_transfer,
+        crate::rest::account_rest::rest_get_client_account_transactions,
+        crate::rest::account_rest::rest_get_client_account_statement,
+        crate::rest::account_rest::rest_open_client_account,
+        crate::rest::account_rest::rest_change_client_account_status,
+        crate::rest::account_rest::rest_change_client_account_overdraft_limit,
+        crate::rest::account_rest::rest_get_client_account_holds,
+        crate::rest::account_rest::rest_authorize_hold,
+        crate::rest::account_rest::rest_capture_hold,
+        crate::rest::account_rest::rest_release_hold,
+        crate::rest::account_rest::rest_reverse_transfer,
+        crate::rest::account_rest::rest_get_client_standing_orders,
+        crate::rest::account_rest::rest_get_client_standing_order,
+        crate::rest::account_rest::rest_create_standing_order,
+        crate::rest::account_rest::rest_change_standing_order,
+        crate::rest::account_rest::rest_delete_standing_order,
+        crate::rest::account_rest::rest_get_client_pending_transfers,
+        crate::rest::account_rest::rest_approve_pending_transfer,
+        crate::rest::account_rest::rest_reject_pending_transfer,
+        crate::rest::account_rest::rest_get_client_webhook_subscriptions,
+        crate::rest::account_rest::rest_get_client_webhook_subscription,
+        crate::rest::account_rest::rest_create_webhook_subscription,
+        crate::rest::account_rest::rest_change_webhook_subscription,
+        crate::rest::account_rest::rest_delete_webhook_subscription,
+        crate::rest::account_rest::rest_get_client_webhook_deliveries,
+    ),
+    components(
+        schemas(
+            crate::rest::dto::Amount,
+            crate::rest::dto::Account,
+            crate::rest::dto::AccountStatus,
+            crate::rest::dto::AccountTransaction,
+            crate::rest::dto::AccountTransactions,
+            crate::rest::dto::TransferAmountResponse,
+            crate::rest::dto::TransferOutcome,
+            crate::rest::dto::TransferOutcomeStatus,
+            crate::rest::dto::TransferBatchResponse,
+            crate::rest::dto::TransferLegResult,
+            crate::rest::dto::TransferLegStatus,
+            crate::rest::dto::TransferQuote,
+            crate::rest::dto::Hold,
+            crate::rest::dto::HoldStatus,
+            crate::rest::dto::StandingOrder,
+            crate::rest::dto::StandingOrderPeriod,
+            crate::rest::dto::PendingTransfer,
+            crate::rest::dto::PendingTransferStatus,
+            crate::rest::dto::WebhookEventType,
+            crate::rest::dto::WebhookSubscription,
+            crate::rest::dto::WebhookDeliveryStatus,
+            crate::rest::dto::WebhookDelivery,
+            crate::rest::dto::WebhookDeliveryAttempt,
+            TransferAmountRequest,
+            TransferBatchRequest,
+            OpenAccountRequest,
+            ChangeAccountStatusRequest,
+            ChangeOverdraftLimitRequest,
+            AuthorizeHoldRequest,
+            CaptureHoldRequest,
+            StandingOrderRequest,
+            ChangeStandingOrderRequest,
+            RejectPendingTransferRequest,
+            WebhookSubscriptionRequest,
+            ChangeWebhookSubscriptionRequest,
+        ),
+    ),
+// nest(
+//     // you can nest sub apis here
+//     (path = "/api/v1/ones", api = one::OneApi)
+// )
+)]
+pub struct AccountRestOpenApi;
+
+
+// It will be 'current user' in wab-app.
+// pub struct CurrentUserAccountRest <AS: AccountService> {
+
+pub struct AccountRest <AS: AccountService> {
+    pub account_service: Arc<AS>,
+    pub account_changes: Arc<AccountChangeListener>,
+}
+
+impl<AS: AccountService> AccountRest<AS> {
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn get_account(&self, client_id: path::ClientId, account_id: path::AccountId)
+        -> Result<dto::Account, RestAppError> {
 
         use mvv_common::obj_ext::ValExt;
         use core::str::FromStr;
 
-        let TransferAmountRequest {
-            from_account: from_account_id,
-            to_account: to_account_id,
-            amount,
-            currency,
-        } = transfer_request;
+        debug!("TD get_user_account as debug");
+        info! ("TI get_user_account as info");
+        // error!("TI get_user_account as error");
+
+        log_debug!("LD get_user_account as debug");
+        log_info! ("LI get_user_account as info");
+        // log_error!("LE get_user_account as error");
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let account_id = account_id.into_inner();
+
+        let is_internal_account_id = account_id.len().is_one_of2(36, 38);
+        let account: entity::Account = if is_internal_account_id {
+            let account_id = AccountId::from_str(&account_id)
+                .err_to_bad_req() ?;
+            self.account_service.get_client_account_by_id(client_id, account_id).await ?
+        } else {
+            let iban = iban::Iban::from_str(&account_id)
+                .err_to_std_err_bad_req() ?;
+            self.account_service.get_client_account_by_iban(client_id, iban).await ?
+        };
+
+        Ok(map_account_to_rest(account))
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn get_accounts(&self, client_id: path::ClientId) -> Result<Vec<dto::Account>, RestAppError> {
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let accounts = self.account_service.get_client_accounts(client_id).await ?;
+
+        let accounts_dto = accounts.into_iter()
+                .map(move |ac| map_account_to_rest(ac))
+                .collect::<Vec<_>>();
+        Ok(accounts_dto)
+    }
+
+
+    /// Stream ends if accounts cannot be loaded (client should reconnect).
+    #[tracing::instrument( skip(self) )]
+    pub async fn stream_accounts(&self, client_id: path::ClientId)
+        -> Result<impl Stream<Item = Result<SseEvent, Infallible>> + Send + 'static, RestAppError>
+        where AS: 'static {
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+
+        // Subscribe before loading the first snapshot, otherwise changes between them can be lost.
+        let mut changes = self.account_changes.subscribe();
+        let first_event = accounts_sse_event(&*self.account_service, &client_id).await ?;
+        let account_service = Arc::clone(&self.account_service);
+
+        Ok(async_stream::stream! {
+            yield Ok(first_event);
+
+            loop {
+                use tokio::sync::broadcast::error::RecvError;
+                match changes.recv().await {
+                    Ok(AccountChange::Client(ref changed_client_id)) if changed_client_id != &client_id => continue,
+                    // Some changes are missed, so accounts are reloaded anyway.
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => break,
+                }
+
+                match accounts_sse_event(&*account_service, &client_id).await {
+                    Ok(event) => yield Ok(event),
+                    Err(err) => {
+                        error!("Error of loading accounts of client [{client_id}]: {err:?}");
+                        break;
+                    }
+                }
+            }
+        })
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn open_account(&self, client_id: path::ClientId, open_request: OpenAccountRequest)
+        -> Result<dto::Account, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let OpenAccountRequest { name, currency } = open_request;
+        let name = AccountName::from_str(&name)
+            .err_to_std_err_bad_req() ?;
+        let currency = entity::prelude::Currency::from_inner(currency)
+            .err_to_bad_req() ?;
+
+        let account = self.account_service.open_client_account(client_id, name, currency).await ?;
+        Ok(map_account_to_rest(account))
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn change_account_status(&self, client_id: path::ClientId, account_id: path::AccountId, status: dto::AccountStatus)
+        -> Result<dto::Account, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let account_id = to_account_id_wrapper(&account_id.into_inner()) ?;
+
+        let account = self.account_service.change_client_account_status(
+            client_id, account_id, map_account_status_from_rest(status)).await ?;
+        Ok(map_account_to_rest(account))
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn change_account_overdraft_limit(&self, client_id: path::ClientId, account_id: path::AccountId, overdraft_limit: dto::Amount)
+        -> Result<dto::Account, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let account_id = to_account_id_wrapper(&account_id.into_inner()) ?;
+
+        let dto::Amount { value, currency } = overdraft_limit;
+        if value < BigDecimal::from(0i32) {
+            return Err(RestAppError::IllegalArgument(
+                anyhow::anyhow!("Overdraft limit should not be negative.")));
+        }
+        let currency = entity::prelude::Currency::from_inner(currency)
+            .err_to_bad_req() ?;
+
+        let account = self.account_service.change_client_account_overdraft_limit(
+            client_id, account_id, entity::prelude::Amount::new(value, currency)).await ?;
+        Ok(map_account_to_rest(account))
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn get_account_transactions(&self, client_id: path::ClientId, account_id: path::AccountId, query: AccountTransactionsQueryParams)
+        -> Result<dto::AccountTransactions, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let account_id = to_account_id_wrapper(&account_id.into_inner()) ?;
+
+        let AccountTransactionsQueryParams { from, to, cursor, limit } = query;
+
+        let cursor = cursor
+            .map(|cursor| i64::from_str(&cursor).err_to_std_err_bad_req())
+            .transpose() ?;
+        let limit = limit.unwrap_or(DEFAULT_TRANSACTIONS_PAGE_SIZE);
+        if limit == 0 || limit > MAX_TRANSACTIONS_PAGE_SIZE {
+            return Err(RestAppError::IllegalArgument(
+                anyhow::anyhow!("Page size should be in range [1, {MAX_TRANSACTIONS_PAGE_SIZE}].")));
+        }
+
+        let page = self.account_service.get_client_account_transactions(
+            client_id, account_id, AccountTransactionsQuery { from, to, cursor, limit },
+        ).await ?;
+
+        Ok(dto::AccountTransactions {
+            transactions: page.transactions.into_iter()
+                .map(map_transaction_to_rest)
+                .collect::<Vec<_>>(),
+            next_cursor: page.next_cursor.map(|cursor| cursor.to_string()),
+        })
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn get_account_statement(&self, client_id: path::ClientId, account_id: path::AccountId, query: AccountStatementQueryParams)
+        -> Result<Response, RestAppError> {
+
+        use core::str::FromStr;
+        use futures::{ stream, StreamExt, TryStreamExt };
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let account_id = to_account_id_wrapper(&account_id.into_inner()) ?;
+
+        let AccountStatementQueryParams { from, to, format } = query;
+        if from >= to {
+            return Err(RestAppError::IllegalArgument(anyhow::anyhow!("'from' should be before 'to'.")));
+        }
+
+        let statement = self.account_service.get_client_account_statement(
+            client_id, account_id, from, to).await ?;
+
+        let created_at = chrono::Local::now().to_utc();
+        let statement_id = format!("{}-{}", from.format("%Y%m%d%H%M%S"), to.format("%Y%m%d%H%M%S"));
+        let file_name = format!("statement-{}-{}.{}", statement.account.iban, statement_id, format.file_extension());
+
+        let statement_header = format.header(&statement, &statement_id, &created_at);
+        let statement_footer = format.footer(&statement);
+
+        // Entries are written as soon as they are loaded from DB (statement is not built in memory).
+        // If loading fails in the middle, response is aborted (client gets truncated body/connection error).
+        let entries = statement.entries
+            .map_ok(move |entry| format.entry(&entry))
+            .map_err(|err| std::io::Error::other(err.to_string()));
+
+        let body = stream::once(async move { Ok::<String, std::io::Error>(statement_header) })
+            .chain(entries)
+            .chain(stream::once(async move { Ok(statement_footer) }));
+
+        Ok((
+            [
+                (header::CONTENT_TYPE, format.content_type().to_owned()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{file_name}\"")),
+            ],
+            axum::body::Body::from_stream(body),
+        ).into_response())
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn get_account_holds(&self, client_id: path::ClientId, account_id: path::AccountId)
+        -> Result<Vec<dto::Hold>, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let account_id = to_account_id_wrapper(&account_id.into_inner()) ?;
+
+        let holds = self.account_service.get_client_account_holds(client_id, account_id).await ?;
+        Ok(holds.into_iter().map(map_hold_to_rest).collect::<Vec<_>>())
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn authorize_hold(&self, client_id: path::ClientId, account_id: path::AccountId, hold_request: AuthorizeHoldRequest)
+        -> Result<dto::Hold, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let account_id = to_account_id_wrapper(&account_id.into_inner()) ?;
+        let AuthorizeHoldRequest { amount, ttl_seconds } = hold_request;
+        let amount = map_amount_from_rest(amount) ?;
+        let ttl = chrono::Duration::seconds(i64::from(ttl_seconds));
+
+        let hold = self.account_service.authorize_hold(client_id, account_id, amount, ttl).await ?;
+        Ok(map_hold_to_rest(hold))
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn capture_hold(&self, client_id: path::ClientId, hold_id: path::HoldId, capture_request: CaptureHoldRequest)
+        -> Result<dto::TransferAmountResponse, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let hold_id = HoldId::from_str(&hold_id.into_inner())
+            .err_to_bad_req() ?;
+        let CaptureHoldRequest { to_account, beneficiary_name, amount } = capture_request;
+        let to_account_id = to_account_id_wrapper(&to_account) ?;
+        let amount = amount.map(map_amount_from_rest).transpose() ?;
+
+        let transfer_id = self.account_service.capture_hold(
+            client_id, hold_id, to_account_id, beneficiary_name, amount).await ?;
+        Ok(dto::TransferAmountResponse { transfer_id: transfer_id.into_inner() })
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn release_hold(&self, client_id: path::ClientId, hold_id: path::HoldId)
+        -> Result<dto::Hold, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let hold_id = HoldId::from_str(&hold_id.into_inner())
+            .err_to_bad_req() ?;
+
+        let hold = self.account_service.release_hold(client_id, hold_id).await ?;
+        Ok(map_hold_to_rest(hold))
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn transfer(&self, client_id: path::ClientId, user_name: String, transfer_request: TransferAmountRequest,
+                          idempotency_key: Option<String>)
+        -> Result<dto::TransferOutcome, RestAppError> {
+
+        use core::str::FromStr;
+
+        let idempotency = idempotency_key
+            .map(|key| IdempotencyKey::from_str(&key).err_to_std_err_bad_req())
+            .transpose() ?
+            .map(|key| Idempotency { key, request_hash: transfer_request.request_hash() });
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let TransferLeg { from_account, to_account, beneficiary_name, amount, expected_rate } =
+            map_transfer_request_to_leg(transfer_request) ?;
+
+        if self.account_service.is_transfer_approval_required(&amount).await ? {
+            // Pending transfer is executed at exchange rate of approval time (expected rate is ignored).
+            let pending_transfer = self.account_service.create_pending_transfer(
+                client_id, user_name,
+                NewPendingTransfer { from_account, to_account, beneficiary_name, amount },
+                idempotency,
+            ).await ?;
+            return Ok(dto::TransferOutcome {
+                status: dto::TransferOutcomeStatus::PendingApproval,
+                transfer_id: None,
+                pending_transfer: Some(map_pending_transfer_to_rest(pending_transfer)),
+            });
+        }
+
+        let transfer_id = self.account_service.transfer(
+            client_id, from_account, to_account, beneficiary_name,
+            amount,
+            expected_rate,
+            idempotency,
+        ).await ?;
+
+        Ok(dto::TransferOutcome {
+            status: dto::TransferOutcomeStatus::Completed,
+            transfer_id: Some(transfer_id.into_inner()),
+            pending_transfer: None,
+        })
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn quote_transfer(&self, client_id: path::ClientId, transfer_request: TransferAmountRequest)
+        -> Result<dto::TransferQuote, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let TransferLeg { from_account, to_account, beneficiary_name, amount, .. } =
+            map_transfer_request_to_leg(transfer_request) ?;
+
+        let quote = self.account_service.quote_transfer(
+            client_id, from_account, to_account, beneficiary_name, amount).await ?;
+
+        Ok(dto::TransferQuote {
+            amount: map_amount_to_rest(quote.amount),
+            fee: map_amount_to_rest(quote.fee),
+            total_debit_amount: map_amount_to_rest(quote.total_debit_amount),
+            credit_amount: map_amount_to_rest(quote.credit_amount),
+            exchange_rate: quote.exchange_rate.map(|rate| rate.rate),
+        })
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn get_pending_transfers(&self, client_id: path::ClientId)
+        -> Result<Vec<dto::PendingTransfer>, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let pending_transfers = self.account_service.get_client_pending_transfers(client_id).await ?;
+        Ok(pending_transfers.into_iter().map(map_pending_transfer_to_rest).collect::<Vec<_>>())
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn approve_pending_transfer(&self, client_id: path::ClientId, pending_transfer_id: path::PendingTransferId,
+                                          user_name: String)
+        -> Result<dto::PendingTransfer, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let pending_transfer_id = PendingTransferId::from_str(&pending_transfer_id.into_inner())
+            .err_to_bad_req() ?;
+
+        let pending_transfer = self.account_service.approve_pending_transfer(
+            client_id, pending_transfer_id, user_name).await ?;
+        Ok(map_pending_transfer_to_rest(pending_transfer))
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn reject_pending_transfer(&self, client_id: path::ClientId, pending_transfer_id: path::PendingTransferId,
+                                         user_name: String, reject_request: RejectPendingTransferRequest)
+        -> Result<dto::PendingTransfer, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let pending_transfer_id = PendingTransferId::from_str(&pending_transfer_id.into_inner())
+            .err_to_bad_req() ?;
+
+        let pending_transfer = self.account_service.reject_pending_transfer(
+            client_id, pending_transfer_id, user_name, reject_request.reason).await ?;
+        Ok(map_pending_transfer_to_rest(pending_transfer))
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn get_standing_orders(&self, client_id: path::ClientId)
+        -> Result<Vec<dto::StandingOrder>, RestAppError> {
+
+        use core::str::FromStr;
 
         let client_id = ClientId::from_str(&client_id.into_inner())
             .err_to_bad_req() ?;
-        let from_account_id = from_account_id; // .into_inner();
-        let to_account_id = to_account_id; // .into_inner();
+        let standing_orders = self.account_service.get_client_standing_orders(client_id).await ?;
+        Ok(standing_orders.into_iter().map(map_standing_order_to_rest).collect::<Vec<_>>())
+    }
 
-        let is_internal_from_account_id = from_account_id.len().is_one_of2(36, 38);
-        let is_internal_to_account_id = to_account_id.len().is_one_of2(36, 38);
 
-        let currency = entity::prelude::Currency::from_inner(currency)
+    #[tracing::instrument( skip(self) )]
+    pub async fn get_standing_order(&self, client_id: path::ClientId, order_id: path::StandingOrderId)
+        -> Result<dto::StandingOrder, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let order_id = StandingOrderId::from_str(&order_id.into_inner())
+            .err_to_bad_req() ?;
+
+        let standing_order = self.account_service.get_client_standing_order(client_id, order_id).await ?;
+        Ok(map_standing_order_to_rest(standing_order))
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn create_standing_order(&self, client_id: path::ClientId, order_request: StandingOrderRequest)
+        -> Result<dto::StandingOrder, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let StandingOrderRequest {
+            from_account, to_account, beneficiary_name, amount, period, start_at,
+        } = order_request;
+        validate_standing_order_start_at(&start_at) ?;
+
+        let order = NewStandingOrder {
+            from_account: to_account_id_wrapper(&from_account) ?,
+            to_account: to_account_id_wrapper(&to_account) ?,
+            beneficiary_name,
+            amount: map_amount_from_rest(amount) ?,
+            period: map_standing_order_period_from_rest(period),
+            start_at,
+        };
+
+        let standing_order = self.account_service.create_standing_order(client_id, order).await ?;
+        Ok(map_standing_order_to_rest(standing_order))
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn change_standing_order(&self, client_id: path::ClientId, order_id: path::StandingOrderId,
+                                       change_request: ChangeStandingOrderRequest)
+        -> Result<dto::StandingOrder, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let order_id = StandingOrderId::from_str(&order_id.into_inner())
+            .err_to_bad_req() ?;
+        let ChangeStandingOrderRequest { beneficiary_name, amount, period, start_at } = change_request;
+        validate_standing_order_start_at(&start_at) ?;
+
+        let change = StandingOrderChange {
+            beneficiary_name,
+            amount: map_amount_from_rest(amount) ?,
+            period: map_standing_order_period_from_rest(period),
+            start_at,
+        };
+
+        let standing_order = self.account_service.change_client_standing_order(client_id, order_id, change).await ?;
+        Ok(map_standing_order_to_rest(standing_order))
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn delete_standing_order(&self, client_id: path::ClientId, order_id: path::StandingOrderId)
+        -> Result<(), RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let order_id = StandingOrderId::from_str(&order_id.into_inner())
+            .err_to_bad_req() ?;
+
+        self.account_service.delete_client_standing_order(client_id, order_id).await ?;
+        Ok(())
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn get_webhook_subscriptions(&self, client_id: path::ClientId)
+        -> Result<Vec<dto::WebhookSubscription>, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let subscriptions = self.account_service.get_client_webhook_subscriptions(client_id).await ?;
+        Ok(subscriptions.into_iter().map(map_webhook_subscription_to_rest).collect::<Vec<_>>())
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn get_webhook_subscription(&self, client_id: path::ClientId, subscription_id: path::WebhookSubscriptionId)
+        -> Result<dto::WebhookSubscription, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let subscription_id = WebhookSubscriptionId::from_str(&subscription_id.into_inner())
+            .err_to_bad_req() ?;
+
+        let subscription = self.account_service.get_client_webhook_subscription(client_id, subscription_id).await ?;
+        Ok(map_webhook_subscription_to_rest(subscription))
+    }
+
+
+    // 'secret' should not be logged.
+    #[tracing::instrument( skip(self, subscription_request) )]
+    pub async fn create_webhook_subscription(&self, client_id: path::ClientId, subscription_request: WebhookSubscriptionRequest)
+        -> Result<dto::WebhookSubscription, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let WebhookSubscriptionRequest { url, secret, event_types } = subscription_request;
+
+        let subscription = NewWebhookSubscription {
+            url: validate_webhook_url(url) ?,
+            secret: secret.into(),
+            event_types: map_webhook_event_types_from_rest(event_types),
+        };
+
+        let subscription = self.account_service.create_webhook_subscription(client_id, subscription).await ?;
+        Ok(map_webhook_subscription_to_rest(subscription))
+    }
+
+
+    // 'secret' should not be logged.
+    #[tracing::instrument( skip(self, change_request) )]
+    pub async fn change_webhook_subscription(&self, client_id: path::ClientId, subscription_id: path::WebhookSubscriptionId,
+                                             change_request: ChangeWebhookSubscriptionRequest)
+        -> Result<dto::WebhookSubscription, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let subscription_id = WebhookSubscriptionId::from_str(&subscription_id.into_inner())
+            .err_to_bad_req() ?;
+        let ChangeWebhookSubscriptionRequest { url, secret, event_types, active } = change_request;
+
+        let change = WebhookSubscriptionChange {
+            url: validate_webhook_url(url) ?,
+            secret: secret.map(Into::into),
+            event_types: map_webhook_event_types_from_rest(event_types),
+            active,
+        };
+
+        let subscription = self.account_service.change_client_webhook_subscription(
+            client_id, subscription_id, change).await ?;
+        Ok(map_webhook_subscription_to_rest(subscription))
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn delete_webhook_subscription(&self, client_id: path::ClientId, subscription_id: path::WebhookSubscriptionId)
+        -> Result<(), RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let subscription_id = WebhookSubscriptionId::from_str(&subscription_id.into_inner())
+            .err_to_bad_req() ?;
+
+        self.account_service.delete_client_webhook_subscription(client_id, subscription_id).await ?;
+        Ok(())
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn get_webhook_deliveries(&self, client_id: path::ClientId, subscription_id: path::WebhookSubscriptionId)
+        -> Result<Vec<dto::WebhookDelivery>, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let subscription_id = WebhookSubscriptionId::from_str(&subscription_id.into_inner())
+            .err_to_bad_req() ?;
+
+        let deliveries = self.account_service.get_client_webhook_deliveries(client_id, subscription_id).await ?;
+        Ok(deliveries.into_iter().map(map_webhook_delivery_to_rest).collect::<Vec<_>>())
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn reverse_transfer(&self, client_id: path::ClientId, transfer_id: path::TransferId)
+        -> Result<dto::TransferAmountResponse, RestAppError> {
+
+        use core::str::FromStr;
+
+        let client_id = ClientId::from_str(&client_id.into_inner())
             .err_to_bad_req() ?;
+        let transfer_id = TransferId::from_str(&transfer_id.into_inner())
+            .err_to_bad_req() ?;
+
+        let reversal_transfer_id = self.account_service.reverse_transfer(client_id, transfer_id).await ?;
+        Ok(dto::TransferAmountResponse { transfer_id: reversal_transfer_id.into_inner() })
+    }
+
+
+    #[tracing::instrument( skip(self) )]
+    pub async fn transfer_batch(&self, client_id: path::ClientId, batch_request: TransferBatchRequest,
+                                idempotency_key: Option<String>)
+        -> Result<dto::TransferBatchResponse, RestAppError> {
 
-        let transfer_res =
-            if is_internal_from_account_id && is_internal_to_account_id {
-                let account_id_from = AccountId::from_str(&from_account_id)
-                    .err_to_bad_req() ?;
-                let account_id_to = AccountId::from_str(&to_account_id)
-                    .err_to_bad_req() ?;
+        use core::str::FromStr;
+
+        let idempotency = idempotency_key
+            .map(|key| IdempotencyKey::from_str(&key).err_to_std_err_bad_req())
+            .transpose() ?
+            .map(|key| Idempotency { key, request_hash: batch_request.request_hash() });
 
-                self.account_service.transfer_by_id(
-                    client_id, account_id_from, account_id_to,
-                    entity::prelude::Amount::new(amount, currency),
-                ).await?
+        let client_id = ClientId::from_str(&client_id.into_inner())
+            .err_to_bad_req() ?;
+        let legs = batch_request.legs.into_iter()
+            .enumerate()
+            .map(|(index, leg)| map_transfer_request_to_leg(leg)
+                .map_err(|err| RestAppError::IllegalArgument(anyhow::anyhow!("Invalid leg [{index}]: {err}"))))
+            .collect::<Result<Vec<_>, _>>() ?;
 
-            } else if !is_internal_from_account_id && !is_internal_to_account_id {
-                let account_iban_from = iban::Iban::from_str(&from_account_id)
-                    .err_to_std_err_bad_req() ?;
-                let account_iban_to = iban::Iban::from_str(&to_account_id)
-                    .err_to_std_err_bad_req() ?;
+        let batch_res = self.account_service.transfer_batch(client_id, legs, idempotency).await ?;
 
-                self.account_service.transfer_by_iban(
-                    client_id, account_iban_from, account_iban_to,
-                    entity::prelude::Amount::new(amount, currency),
-                ).await?
-            } else {
-                return Err(RestAppError::IllegalArgument(
-                    anyhow::anyhow!("All account IDs should have the same type.")));
-            };
+        let committed = batch_res.committed;
+        let legs = batch_res.legs.into_iter()
+            .enumerate()
+            .map(|(index, leg_status)| match leg_status {
+                TransferLegStatus::Ok(transfer_id) => dto::TransferLegResult {
+                    index,
+                    status: dto::TransferLegStatus::Ok,
+                    // Transfer ID of rejected batch does not make sense (it is not stored).
+                    transfer_id: if committed { Some(transfer_id.into_inner()) } else { None },
+                    error: None,
+                },
+                TransferLegStatus::Failed(err) => dto::TransferLegResult {
+                    index,
+                    status: dto::TransferLegStatus::Failed,
+                    transfer_id: None,
+                    error: Some(err.to_string()),
+                },
+            })
+            .collect::<Vec<_>>();
 
-        Ok(transfer_res)
+        Ok(dto::TransferBatchResponse { committed, legs })
     }
 }
 
 
+fn map_transfer_request_to_leg(transfer_request: TransferAmountRequest) -> Result<TransferLeg, RestAppError> {
+    let expected_rate = transfer_request.expected_rate() ?;
+
+    let TransferAmountRequest {
+        from_account: from_account_id,
+        to_account: to_account_id,
+        beneficiary_name,
+        amount,
+        currency,
+        ..
+    } = transfer_request;
+
+    let from_account = to_account_id_wrapper(&from_account_id) ?;
+    let to_account = to_account_id_wrapper(&to_account_id) ?;
+    let amount = map_amount_from_rest(dto::Amount { value: amount, currency }) ?;
+
+    Ok(TransferLeg {
+        from_account,
+        to_account,
+        beneficiary_name,
+        amount,
+        expected_rate,
+    })
+}
+
+
+fn to_account_id_wrapper(account_id: &str) -> Result<AccountIdWrapper, RestAppError> {
+    use mvv_common::obj_ext::ValExt;
+    use core::str::FromStr;
+
+    let is_internal_account_id = account_id.len().is_one_of2(36, 38);
+    let account_id = if is_internal_account_id {
+        AccountIdWrapper::Id(AccountId::from_str(account_id).err_to_bad_req() ?)
+    } else {
+        AccountIdWrapper::Iban(iban::Iban::from_str(account_id).err_to_std_err_bad_req() ?)
+    };
+    Ok(account_id)
+}
+
+
+async fn accounts_sse_event<AS: AccountService>(account_service: &AS, client_id: &ClientId)
+    -> Result<SseEvent, RestAppError> {
+
+    let accounts = account_service.get_client_accounts(client_id.clone()).await ?
+        .into_iter()
+        .map(map_account_to_rest)
+        .collect::<Vec<_>>();
+
+    SseEvent::default()
+        .event("accounts")
+        .json_data(accounts)
+        .map_err(|err| RestAppError::AnyhowError(anyhow::anyhow!(err)))
+}
+
+
 fn map_account_to_rest(account: entity::Account) -> dto::Account {
     use crate::entity::account::AccountParts;
     use mvv_common_bank_entities::amount::AmountParts;
 
-    let AccountParts { id, iban, client_id, name, amount, created_at, updated_at } = account.into_parts();
+    let AccountParts {
+        id, iban, client_id, name, amount, overdraft_limit, held_amount, status, created_at, updated_at,
+    } = account.into_parts();
     let AmountParts { value: amount_value, currency } = amount.into_parts();
+    let AmountParts { value: overdraft_limit_value, currency: overdraft_limit_currency } = overdraft_limit.into_parts();
+    // All amounts of account have the same (account) currency.
+    let available_value = &amount_value + &overdraft_limit_value - held_amount.value_ref();
     dto::Account {
         id: id.into_inner(), // .to_string(),
         iban: iban.to_string(), // hm... where 'into_inner()' ??
         client_id: client_id.into_inner(), //.to_string(),
         name: name.into_inner(),
-        amount: dto::Amount { value: amount_value, currency: currency.into_inner() },
+        amount: dto::Amount { value: amount_value.clone(), currency: currency.into_inner() },
+        ledger: dto::Amount { value: amount_value, currency: currency.into_inner() },
+        available: dto::Amount { value: available_value, currency: currency.into_inner() },
+        overdraft_limit: dto::Amount { value: overdraft_limit_value, currency: overdraft_limit_currency.into_inner() },
+        status: map_account_status_to_rest(status),
+        created_at,
+        updated_at,
+    }
+}
+
+
+fn map_account_status_to_rest(status: entity::AccountStatus) -> dto::AccountStatus {
+    match status {
+        entity::AccountStatus::Pending   => dto::AccountStatus::Pending,
+        entity::AccountStatus::Active    => dto::AccountStatus::Active,
+        entity::AccountStatus::Suspended => dto::AccountStatus::Suspended,
+        entity::AccountStatus::Closed    => dto::AccountStatus::Closed,
+    }
+}
+
+fn map_account_status_from_rest(status: dto::AccountStatus) -> entity::AccountStatus {
+    match status {
+        dto::AccountStatus::Pending   => entity::AccountStatus::Pending,
+        dto::AccountStatus::Active    => entity::AccountStatus::Active,
+        dto::AccountStatus::Suspended => entity::AccountStatus::Suspended,
+        dto::AccountStatus::Closed    => entity::AccountStatus::Closed,
+    }
+}
+
+
+fn map_hold_to_rest(hold: entity::Hold) -> dto::Hold {
+    use crate::entity::hold::HoldParts;
+
+    let HoldParts {
+        id, account_id, amount, status, captured_amount, transfer_id, expires_at, created_at, updated_at,
+    } = hold.into_parts();
+
+    dto::Hold {
+        id: id.into_inner(),
+        account_id: account_id.into_inner(),
+        amount: map_amount_to_rest(amount),
+        status: map_hold_status_to_rest(status),
+        captured_amount: captured_amount.map(map_amount_to_rest),
+        transfer_id: transfer_id.map(|id| id.into_inner()),
+        expires_at,
+        created_at,
+        updated_at,
+    }
+}
+
+
+fn validate_standing_order_start_at(start_at: &chrono::DateTime<Utc>) -> Result<(), RestAppError> {
+    if start_at <= &Utc::now() {
+        return Err(RestAppError::IllegalArgument(anyhow::anyhow!("Start time should be in the future.")));
+    }
+    Ok(())
+}
+
+
+fn map_standing_order_to_rest(standing_order: entity::StandingOrder) -> dto::StandingOrder {
+    use crate::entity::standing_order::StandingOrderParts;
+
+    let StandingOrderParts {
+        id, from_account_id, to_iban, beneficiary_name, amount, period, start_at,
+        next_execution_at, failed_attempts, retry_at, last_executed_at, last_transfer_id, last_error,
+        created_at, updated_at, ..
+    } = standing_order.into_parts();
+
+    dto::StandingOrder {
+        id: id.into_inner(),
+        from_account_id: from_account_id.into_inner(),
+        to_iban: to_iban.to_string(),
+        beneficiary_name,
+        amount: map_amount_to_rest(amount),
+        period: map_standing_order_period_to_rest(period),
+        start_at,
+        next_execution_at,
+        failed_attempts,
+        retry_at,
+        last_executed_at,
+        last_transfer_id: last_transfer_id.map(|id| id.into_inner()),
+        last_error,
+        created_at,
+        updated_at,
+    }
+}
+
+
+fn map_pending_transfer_to_rest(pending_transfer: entity::PendingTransfer) -> dto::PendingTransfer {
+    use crate::entity::pending_transfer::PendingTransferParts;
+
+    let PendingTransferParts {
+        id, from_account_id, to_iban, beneficiary_name, amount, status, created_by,
+        decided_by, decided_at, transfer_id, reject_reason, created_at, updated_at, ..
+    } = pending_transfer.into_parts();
+
+    dto::PendingTransfer {
+        id: id.into_inner(),
+        from_account_id: from_account_id.into_inner(),
+        to_iban: to_iban.to_string(),
+        beneficiary_name,
+        amount: map_amount_to_rest(amount),
+        status: map_pending_transfer_status_to_rest(status),
+        created_by,
+        decided_by,
+        decided_at,
+        transfer_id: transfer_id.map(|id| id.into_inner()),
+        reject_reason,
+        created_at,
+        updated_at,
+    }
+}
+
+
+fn map_pending_transfer_status_to_rest(status: entity::PendingTransferStatus) -> dto::PendingTransferStatus {
+    match status {
+        entity::PendingTransferStatus::Pending  => dto::PendingTransferStatus::Pending,
+        entity::PendingTransferStatus::Approved => dto::PendingTransferStatus::Approved,
+        entity::PendingTransferStatus::Rejected => dto::PendingTransferStatus::Rejected,
+    }
+}
+
+
+fn map_standing_order_period_to_rest(period: entity::StandingOrderPeriod) -> dto::StandingOrderPeriod {
+    match period {
+        entity::StandingOrderPeriod::Daily   => dto::StandingOrderPeriod::Daily,
+        entity::StandingOrderPeriod::Weekly  => dto::StandingOrderPeriod::Weekly,
+        entity::StandingOrderPeriod::Monthly => dto::StandingOrderPeriod::Monthly,
+    }
+}
+
+
+fn map_standing_order_period_from_rest(period: dto::StandingOrderPeriod) -> entity::StandingOrderPeriod {
+    match period {
+        dto::StandingOrderPeriod::Daily   => entity::StandingOrderPeriod::Daily,
+        dto::StandingOrderPeriod::Weekly  => entity::StandingOrderPeriod::Weekly,
+        dto::StandingOrderPeriod::Monthly => entity::StandingOrderPeriod::Monthly,
+    }
+}
+
+
+/// Only absolute HTTP(S) URL is allowed.
+fn validate_webhook_url(url: String) -> Result<String, RestAppError> {
+    crate::service::webhook_delivery::validate_webhook_url(&url)
+        .map_err(RestAppError::IllegalArgument) ?;
+    Ok(url)
+}
+
+
+fn map_webhook_subscription_to_rest(subscription: entity::webhook::WebhookSubscription) -> dto::WebhookSubscription {
+    use crate::entity::webhook::WebhookSubscriptionParts;
+
+    let WebhookSubscriptionParts {
+        id, url, event_types, active, created_at, updated_at, ..
+    } = subscription.into_parts();
+
+    dto::WebhookSubscription {
+        id: id.into_inner(),
+        url,
+        event_types: event_types.into_iter().map(map_webhook_event_type_to_rest).collect(),
+        active,
         created_at,
         updated_at,
     }
 }
 
 
+fn map_webhook_delivery_to_rest(delivery: entity::webhook::WebhookDelivery) -> dto::WebhookDelivery {
+    use crate::entity::webhook::{ WebhookDeliveryParts, WebhookDeliveryStatus };
+
+    let WebhookDeliveryParts {
+        id, event_id, event_type, status, attempts, next_attempt_at, last_error, delivered_at, created_at,
+        attempt_history, ..
+    } = delivery.into_parts();
+
+    dto::WebhookDelivery {
+        id,
+        event_id,
+        event_type: map_webhook_event_type_to_rest(event_type),
+        status: match status {
+            WebhookDeliveryStatus::Pending   => dto::WebhookDeliveryStatus::Pending,
+            WebhookDeliveryStatus::Delivered => dto::WebhookDeliveryStatus::Delivered,
+            WebhookDeliveryStatus::Dead      => dto::WebhookDeliveryStatus::Dead,
+        },
+        attempts,
+        next_attempt_at,
+        last_error,
+        delivered_at,
+        created_at,
+        attempt_history: attempt_history.into_iter()
+            .map(|attempt| dto::WebhookDeliveryAttempt {
+                attempt_no: attempt.attempt_no,
+                attempted_at: attempt.attempted_at,
+                response_status: attempt.response_status,
+                error: attempt.error,
+                duration_ms: attempt.duration_ms,
+            })
+            .collect(),
+    }
+}
+
+
+fn map_webhook_event_type_to_rest(event_type: entity::webhook::WebhookEventType) -> dto::WebhookEventType {
+    use crate::entity::webhook::WebhookEventType;
+    match event_type {
+        WebhookEventType::TransferIncoming => dto::WebhookEventType::TransferIncoming,
+        WebhookEventType::TransferOutgoing => dto::WebhookEventType::TransferOutgoing,
+    }
+}
+
+
+/// Duplicates are removed.
+fn map_webhook_event_types_from_rest(event_types: Vec<dto::WebhookEventType>) -> Vec<entity::webhook::WebhookEventType> {
+    use crate::entity::webhook::WebhookEventType;
+
+    let mut mapped: Vec<WebhookEventType> = Vec::with_capacity(event_types.len());
+    for event_type in event_types {
+        let event_type = match event_type {
+            dto::WebhookEventType::TransferIncoming => WebhookEventType::TransferIncoming,
+            dto::WebhookEventType::TransferOutgoing => WebhookEventType::TransferOutgoing,
+        };
+        if !mapped.contains(&event_type) {
+            mapped.push(event_type);
+        }
+    }
+    mapped
+}
+
+
+fn map_hold_status_to_rest(status: entity::HoldStatus) -> dto::HoldStatus {
+    match status {
+        entity::HoldStatus::Active   => dto::HoldStatus::Active,
+        entity::HoldStatus::Captured => dto::HoldStatus::Captured,
+        entity::HoldStatus::Released => dto::HoldStatus::Released,
+        entity::HoldStatus::Expired  => dto::HoldStatus::Expired,
+    }
+}
+
+
+fn map_amount_to_rest(amount: entity::prelude::Amount) -> dto::Amount {
+    use mvv_common_bank_entities::amount::AmountParts;
+    let AmountParts { value, currency } = amount.into_parts();
+    dto::Amount { value, currency: currency.into_inner() }
+}
+
+
+fn map_amount_from_rest(amount: dto::Amount) -> Result<entity::prelude::Amount, RestAppError> {
+    let dto::Amount { value, currency } = amount;
+    if value <= BigDecimal::from(0i32) {
+        return Err(RestAppError::IllegalArgument(anyhow::anyhow!("Amount should be positive.")));
+    }
+    let currency = entity::prelude::Currency::from_inner(currency)
+        .err_to_bad_req() ?;
+    Ok(entity::prelude::Amount::new(value, currency))
+}
+
+
+fn map_transaction_to_rest(transaction: entity::AccountTransaction) -> dto::AccountTransaction {
+    use crate::entity::transaction::AccountTransactionParts;
+    use mvv_common_bank_entities::amount::AmountParts;
+
+    let AccountTransactionParts {
+        id, transfer_id, account_id, counterparty_account_id, amount, balance_after,
+        exchange_rate, exchange_rate_at, reversed_transfer_id, fee_for_transfer_id, created_at,
+    } = transaction.into_parts();
+    let AmountParts { value: amount_value, currency } = amount.into_parts();
+    let AmountParts { value: balance_after_value, currency: balance_after_currency } = balance_after.into_parts();
+
+    dto::AccountTransaction {
+        id,
+        transfer_id: transfer_id.into_inner(),
+        account_id: account_id.into_inner(),
+        counterparty_account_id: counterparty_account_id.map(|id| id.into_inner()),
+        amount: dto::Amount { value: amount_value, currency: currency.into_inner() },
+        balance_after: dto::Amount { value: balance_after_value, currency: balance_after_currency.into_inner() },
+        exchange_rate,
+        exchange_rate_at,
+        reversed_transfer_id: reversed_transfer_id.map(|id| id.into_inner()),
+        fee_for_transfer_id: fee_for_transfer_id.map(|id| id.into_inner()),
+        created_at,
+    }
+}
+
 
 //--------------------------------------------------------------------------------------------------
 //
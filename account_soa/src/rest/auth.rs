@@ -40,6 +40,8 @@ pub type AuthBackend = CompositeAuthBackend;
 // pub type AuthnBackend = LoginFormAuthnBackend;
 pub type AuthSession = axum_login::AuthSession<AuthBackend>;
 
+pub type ExtractCurrentUser = mvv_auth::extract::ExtractCurrentUser<AuthUser, AuthBackend>;
+
 
 #[extension_trait::extension_trait]
 //noinspection DuplicatedCode
@@ -57,6 +57,19 @@ pub struct Amount {
 }
 
 
+#[derive(utoipa::ToSchema)]
+#[schema(as = AccountStatus)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AccountStatus {
+    Pending,
+    Active,
+    Suspended,
+    Closed,
+}
+
+
 #[derive(utoipa::ToSchema)]
 #[schema(as = Account)] // api::models::Account)]
 #[derive(Debug, PartialEq)]
@@ -87,15 +100,529 @@ pub struct Account {
     #[schema(value_type = String, example = "My AUD account")]
     pub name: AccountNameInner, // String
 
+    /// Ledger balance (the same as 'ledger', it is kept for compatibility)
     #[validify]
     #[schema(value_type = Amount)] // api::models::Amount)]
     pub amount: Amount,
 
+    /// Ledger balance (sum of settled transactions)
+    #[validify]
+    #[schema(value_type = Amount)]
+    pub ledger: Amount,
+
+    /// Funds which can be spent (ledger balance + overdraft limit - active holds)
+    #[validify]
+    #[schema(value_type = Amount)]
+    pub available: Amount,
+
+    /// Balance can go down to -overdraftLimit
+    #[validify]
+    #[schema(value_type = Amount)]
+    pub overdraft_limit: Amount,
+
+    #[schema(value_type = AccountStatus)]
+    pub status: AccountStatus,
+
+    pub created_at: chrono::DateTime<Utc>,
+    pub updated_at: chrono::DateTime<Utc>,
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = AccountTransaction)]
+#[derive(Debug, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[derive(derive_more::Display)]
+#[display("AccountTransaction {{ {id}, transfer: {transfer_id}, account: {account_id}, amount: {amount}, balance after: {balance_after}, created at: {created_at} }}")]
+#[serde(rename_all = "camelCase")]
+pub struct AccountTransaction {
+    /// Ledger entry ID
+    #[schema(example = 1001)]
+    pub id: i64,
+
+    /// Transfer ID (UUID), debit and credit entries of the same transfer have the same ID
+    #[schema(example = "6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55")]
+    pub transfer_id: uuid::Uuid,
+
+    /// Account ID (UUID)
+    #[schema(example = "00000000-0000-0000-0000-000000000101")]
+    pub account_id: uuid::Uuid,
+
+    /// Account ID (UUID) of other side of transfer
+    #[schema(example = "00000000-0000-0000-0000-000000000102")]
+    pub counterparty_account_id: Option<uuid::Uuid>,
+
+    /// Signed amount: negative for debit, positive for credit
+    #[schema(value_type = Amount)]
+    pub amount: Amount,
+
+    /// Account balance after this entry
+    #[schema(value_type = Amount)]
+    pub balance_after: Amount,
+
+    /// Exchange rate used for cross-currency transfer (source currency => target currency)
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "mvv_common::json::serde_json_bd::bd_opt_with")]
+    #[schema(value_type = Option<f64>, example = 41.25)]
+    pub exchange_rate: Option<BigDecimal>,
+
+    /// Time when exchange rate was fixed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exchange_rate_at: Option<chrono::DateTime<Utc>>,
+
+    /// Only for compensating (reversal) entry: ID of reversed transfer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55")]
+    pub reversed_transfer_id: Option<uuid::Uuid>,
+
+    /// Only for fee entry: ID of transfer for which fee is charged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55")]
+    pub fee_for_transfer_id: Option<uuid::Uuid>,
+
+    pub created_at: chrono::DateTime<Utc>,
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = AccountTransactions)]
+#[derive(Debug, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountTransactions {
+    /// Entries from newest to oldest
+    pub transactions: Vec<AccountTransaction>,
+
+    /// Cursor of the next page (it is absent for the last page)
+    #[schema(example = "1001")]
+    pub next_cursor: Option<String>,
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = HoldStatus)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HoldStatus {
+    Active,
+    Captured,
+    Released,
+    Expired,
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = Hold)]
+#[derive(Debug, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[derive(derive_more::Display)]
+#[display("Hold {{ {id}, account: {account_id}, amount: {amount}, status: {status:?}, expires at: {expires_at} }}")]
+#[serde(rename_all = "camelCase")]
+pub struct Hold {
+    /// Hold ID (UUID)
+    #[schema(example = "3f0c2a4e-5b7d-4c1e-9a2b-8d6e4f1a0c37")]
+    pub id: uuid::Uuid,
+
+    /// Account ID (UUID)
+    #[schema(example = "00000000-0000-0000-0000-000000000101")]
+    pub account_id: uuid::Uuid,
+
+    /// Authorized (reserved) amount
+    #[schema(value_type = Amount)]
+    pub amount: Amount,
+
+    #[schema(value_type = HoldStatus)]
+    pub status: HoldStatus,
+
+    /// Captured amount (only for captured hold)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Amount>)]
+    pub captured_amount: Option<Amount>,
+
+    /// Transfer ID (only for captured hold)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55")]
+    pub transfer_id: Option<uuid::Uuid>,
+
+    pub expires_at: chrono::DateTime<Utc>,
+    pub created_at: chrono::DateTime<Utc>,
+    pub updated_at: chrono::DateTime<Utc>,
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = TransferAmountResponse)]
+#[derive(Debug, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferAmountResponse {
+    /// Transfer ID (UUID), it can be used to find transfer in account history
+    #[schema(example = "6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55")]
+    pub transfer_id: uuid::Uuid,
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = TransferOutcomeStatus)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransferOutcomeStatus {
+    Completed,
+    PendingApproval,
+}
+
+
+/// Transfer is done at once or (if amount is above approval threshold) it waits for approval.
+#[derive(utoipa::ToSchema)]
+#[schema(as = TransferOutcome)]
+#[derive(Debug, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferOutcome {
+    #[schema(value_type = TransferOutcomeStatus)]
+    pub status: TransferOutcomeStatus,
+
+    /// Transfer ID (only for completed transfer)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55")]
+    pub transfer_id: Option<uuid::Uuid>,
+
+    /// Stored transfer (only for transfer which waits for approval)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<PendingTransfer>)]
+    pub pending_transfer: Option<PendingTransfer>,
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = StandingOrderPeriod)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StandingOrderPeriod {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = StandingOrder)]
+#[derive(Debug, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StandingOrder {
+    /// Standing order ID (UUID)
+    #[schema(example = "0c9d3a7e-2f4b-4e8a-b1c6-5d7e9f0a2b4c")]
+    pub id: uuid::Uuid,
+
+    /// Account ID (UUID)
+    #[schema(example = "00000000-0000-0000-0000-000000000101")]
+    pub from_account_id: uuid::Uuid,
+
+    #[schema(example = "UA853052990000000000000002011")]
+    pub to_iban: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "Tigra Bohdan")]
+    pub beneficiary_name: Option<String>,
+
+    #[schema(value_type = Amount)]
+    pub amount: Amount,
+
+    #[schema(value_type = StandingOrderPeriod)]
+    pub period: StandingOrderPeriod,
+
+    /// Time of the first execution (next ones are calculated from it)
+    pub start_at: chrono::DateTime<Utc>,
+    pub next_execution_at: chrono::DateTime<Utc>,
+
+    /// Failed attempts of the next execution (due to not enough balance)
+    #[schema(example = 0)]
+    pub failed_attempts: u32,
+
+    /// Time of the next attempt (only if previous one is failed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_at: Option<chrono::DateTime<Utc>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_executed_at: Option<chrono::DateTime<Utc>>,
+
+    /// Transfer ID of the last successful execution
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55")]
+    pub last_transfer_id: Option<uuid::Uuid>,
+
+    /// Error of the last failed execution attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "NotEnoughBalance (available funds: 10 USD)")]
+    pub last_error: Option<String>,
+
     pub created_at: chrono::DateTime<Utc>,
     pub updated_at: chrono::DateTime<Utc>,
 }
 
 
+#[derive(utoipa::ToSchema)]
+#[schema(as = TransferLegStatus)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TransferLegStatus {
+    Ok,
+    Failed,
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = TransferLegResult)]
+#[derive(Debug, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferLegResult {
+    /// Index of leg in request (starting from 0)
+    #[schema(example = 0)]
+    pub index: usize,
+
+    #[schema(value_type = TransferLegStatus)]
+    pub status: TransferLegStatus,
+
+    /// Transfer ID (only for successful leg of committed batch)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55")]
+    pub transfer_id: Option<uuid::Uuid>,
+
+    /// Failure reason (only for failed leg)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "NotEnoughBalance (available funds: 10 USD)")]
+    pub error: Option<String>,
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = TransferBatchResponse)]
+#[derive(Debug, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferBatchResponse {
+    /// True if all legs are done, false if whole batch is rejected (nothing is done)
+    pub committed: bool,
+
+    /// Result of every leg (in the same order as in request)
+    pub legs: Vec<TransferLegResult>,
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = TransferQuote)]
+#[derive(Debug, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferQuote {
+    /// Transfer amount (in 'from' account currency)
+    #[schema(value_type = Amount)]
+    pub amount: Amount,
+
+    /// Fee (in 'from' account currency), it is zero for free transfer
+    #[schema(value_type = Amount)]
+    pub fee: Amount,
+
+    /// Amount + fee, it is debited from 'from' account
+    #[schema(value_type = Amount)]
+    pub total_debit_amount: Amount,
+
+    /// Amount which is credited to 'to' account (in its currency)
+    #[schema(value_type = Amount)]
+    pub credit_amount: Amount,
+
+    /// Current exchange rate (only for cross-currency transfer)
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "mvv_common::json::serde_json_bd::bd_opt_with")]
+    #[schema(value_type = Option<f64>, example = 41.25)]
+    pub exchange_rate: Option<BigDecimal>,
+}
+
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = PendingTransferStatus)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PendingTransferStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+
+/// Transfer above approval threshold which is executed only after approval by another user.
+#[derive(utoipa::ToSchema)]
+#[schema(as = PendingTransfer)]
+#[derive(Debug, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTransfer {
+    /// Pending transfer ID (UUID)
+    #[schema(example = "3f1c2b7d-9a4e-4c8b-8e21-7b5d0c6a9f13")]
+    pub id: uuid::Uuid,
+
+    /// Account ID (UUID)
+    #[schema(example = "00000000-0000-0000-0000-000000000101")]
+    pub from_account_id: uuid::Uuid,
+
+    #[schema(example = "UA853052990000000000000002011")]
+    pub to_iban: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "Tigra Bohdan")]
+    pub beneficiary_name: Option<String>,
+
+    #[schema(value_type = Amount)]
+    pub amount: Amount,
+
+    #[schema(value_type = PendingTransferStatus)]
+    pub status: PendingTransferStatus,
+
+    /// User who requested transfer
+    #[schema(example = "vovan-write")]
+    pub created_by: String,
+
+    /// User who approved/rejected transfer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "vovan-super-user")]
+    pub decided_by: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decided_at: Option<chrono::DateTime<Utc>>,
+
+    /// ID of executed transfer (only for approved one)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55")]
+    pub transfer_id: Option<uuid::Uuid>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "Suspicious beneficiary")]
+    pub reject_reason: Option<String>,
+
+    pub created_at: chrono::DateTime<Utc>,
+    pub updated_at: chrono::DateTime<Utc>,
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = WebhookEventType)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WebhookEventType {
+    TransferIncoming,
+    TransferOutgoing,
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = WebhookSubscription)]
+#[derive(Debug, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscription {
+    /// Webhook subscription ID (UUID)
+    #[schema(example = "9b2e4c6a-1d3f-4a5b-8c7d-0e1f2a3b4c5d")]
+    pub id: uuid::Uuid,
+
+    #[schema(example = "https://partner.example.com/bank/webhook")]
+    pub url: String,
+
+    #[schema(value_type = Vec<WebhookEventType>)]
+    pub event_types: Vec<WebhookEventType>,
+
+    /// Notifications are not sent to inactive subscription
+    pub active: bool,
+
+    pub created_at: chrono::DateTime<Utc>,
+    pub updated_at: chrono::DateTime<Utc>,
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = WebhookDeliveryStatus)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    /// All attempts are failed (it is not retried anymore)
+    Dead,
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = WebhookDeliveryAttempt)]
+#[derive(Debug, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDeliveryAttempt {
+    #[schema(example = 1)]
+    pub attempt_no: u32,
+
+    pub attempted_at: chrono::DateTime<Utc>,
+
+    /// HTTP status of response (absent if there is no response)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = 503)]
+    pub response_status: Option<u16>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(example = "Response status 503 Service Unavailable.")]
+    pub error: Option<String>,
+
+    #[schema(example = 120)]
+    pub duration_ms: u32,
+}
+
+
+#[derive(utoipa::ToSchema)]
+#[schema(as = WebhookDelivery)]
+#[derive(Debug, PartialEq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDelivery {
+    /// Delivery ID (UUID)
+    #[schema(example = "5d4c3b2a-1f0e-4d9c-8b7a-6f5e4d3c2b1a")]
+    pub id: uuid::Uuid,
+
+    /// Event ID (UUID), it is sent in 'X-Webhook-Event-Id' header
+    #[schema(example = "7e6d5c4b-3a29-4817-9f6e-5d4c3b2a1908")]
+    pub event_id: uuid::Uuid,
+
+    #[schema(value_type = WebhookEventType)]
+    pub event_type: WebhookEventType,
+
+    #[schema(value_type = WebhookDeliveryStatus)]
+    pub status: WebhookDeliveryStatus,
+
+    #[schema(example = 1)]
+    pub attempts: u32,
+
+    /// Only for pending delivery
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_attempt_at: Option<chrono::DateTime<Utc>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delivered_at: Option<chrono::DateTime<Utc>>,
+
+    pub created_at: chrono::DateTime<Utc>,
+
+    /// From the first attempt to the last one
+    pub attempt_history: Vec<WebhookDeliveryAttempt>,
+}
+
+
 // See https://crates.io/crates/axum-valid
 #[derive(Debug, validator::Validate, serde::Deserialize)]
 pub struct SomeRequest {
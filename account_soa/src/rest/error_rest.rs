@@ -110,13 +110,33 @@ impl IntoResponse for RestAppError {
             }
             RestAppError::AccountProcessError(ref err, ref backtrace) => {
                 error!("AccountProcessError: {err:?} \n {backtrace}");
-                (StatusCode::BAD_REQUEST, Json(err.to_string())).into_response()
+                (account_process_error_status(err), Json(err.to_string())).into_response()
             }
         }
     }
 }
 
 
+fn account_process_error_status(err: &AccountProcessError) -> StatusCode {
+    match err {
+        AccountProcessError::IdempotencyKeyConflict(..)
+        | AccountProcessError::ExchangeRateChanged(..)
+        | AccountProcessError::AccountNotActive(..)
+        | AccountProcessError::AccountStatusChangeNotAllowed(..)
+        | AccountProcessError::AccountIsNotEmpty(..)
+        | AccountProcessError::HoldNotActive(..)
+        | AccountProcessError::TransferAlreadyReversed(..)
+        | AccountProcessError::TransferNotReversible(..)
+        | AccountProcessError::ReversalNotEnoughBalance(..)
+        | AccountProcessError::PendingTransferIsDecided(..) => StatusCode::CONFLICT,
+        AccountProcessError::LimitExceeded(..)
+        | AccountProcessError::ApprovalRequired(..) => StatusCode::UNPROCESSABLE_ENTITY,
+        AccountProcessError::SelfApprovalNotAllowed(..) => StatusCode::FORBIDDEN,
+        _ => StatusCode::BAD_REQUEST,
+    }
+}
+
+
 /*
 // This enables using `?` on functions that return `Result<_, anyhow::Error>` to turn them into
 // `Result<_, AppError>`. That way you don't need to do that manually.
@@ -25,3 +25,43 @@ pub struct ClientId {
     pub client_id: String,
 }
 generate_into_inner_delegate! { ClientId, client_id, String }
+
+
+#[derive(Debug, derive_more::Display, Clone, serde::Deserialize)]
+#[display("PathHoldId({})", hold_id)]
+pub struct HoldId {
+    pub hold_id: String,
+}
+generate_into_inner_delegate! { HoldId, hold_id, String }
+
+
+#[derive(Debug, derive_more::Display, Clone, serde::Deserialize)]
+#[display("PathTransferId({})", transfer_id)]
+pub struct TransferId {
+    pub transfer_id: String,
+}
+generate_into_inner_delegate! { TransferId, transfer_id, String }
+
+
+#[derive(Debug, derive_more::Display, Clone, serde::Deserialize)]
+#[display("PathStandingOrderId({})", order_id)]
+pub struct StandingOrderId {
+    pub order_id: String,
+}
+generate_into_inner_delegate! { StandingOrderId, order_id, String }
+
+
+#[derive(Debug, derive_more::Display, Clone, serde::Deserialize)]
+#[display("PathPendingTransferId({})", pending_transfer_id)]
+pub struct PendingTransferId {
+    pub pending_transfer_id: String,
+}
+generate_into_inner_delegate! { PendingTransferId, pending_transfer_id, String }
+
+
+#[derive(Debug, derive_more::Display, Clone, serde::Deserialize)]
+#[display("PathWebhookSubscriptionId({})", subscription_id)]
+pub struct WebhookSubscriptionId {
+    pub subscription_id: String,
+}
+generate_into_inner_delegate! { WebhookSubscriptionId, subscription_id, String }
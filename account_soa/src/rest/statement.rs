@@ -0,0 +1,201 @@
+use bigdecimal::BigDecimal;
+use chrono::{ SecondsFormat, Utc };
+use mvv_common_bank_entities::amount::Amount;
+use crate::entity::AccountTransaction;
+use crate::service::account_service::AccountStatement;
+//--------------------------------------------------------------------------------------------------
+
+
+
+/// Statement export formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementFormat {
+    Csv,
+    /// ISO 20022 'BankToCustomerStatement' (camt.053.001.02).
+    Camt053,
+}
+
+
+/// Header (balances) and footer of statement are generated from AccountStatement,
+/// entries are generated one by one (they are streamed).
+impl StatementFormat {
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            StatementFormat::Csv     => "text/csv; charset=utf-8",
+            StatementFormat::Camt053 => "application/xml; charset=utf-8",
+        }
+    }
+
+    pub fn file_extension(&self) -> &'static str {
+        match self {
+            StatementFormat::Csv     => "csv",
+            StatementFormat::Camt053 => "xml",
+        }
+    }
+
+    pub fn header(&self, statement: &AccountStatement, statement_id: &str, created_at: &chrono::DateTime<Utc>) -> String {
+        match self {
+            StatementFormat::Csv => format!(
+                "RECORD_TYPE,BOOKED_AT,ENTRY_ID,TRANSFER_ID,COUNTERPARTY_ACCOUNT_ID,AMOUNT,CURRENCY,BALANCE_AFTER,REVERSED_TRANSFER_ID\r\n\
+                 OPENING_BALANCE,{},,,,{},{},,\r\n",
+                format_date_time(&statement.from),
+                format_amount_value(statement.opening_balance.value_ref()),
+                statement.opening_balance.currency(),
+            ),
+            StatementFormat::Camt053 => {
+                let currency = statement.account.amount.currency();
+                format!(
+                    "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                     <Document xmlns=\"urn:iso:std:iso:20022:tech:xsd:camt.053.001.02\">\n\
+                     <BkToCstmrStmt>\n\
+                     <GrpHdr><MsgId>{statement_id}</MsgId><CreDtTm>{created_at}</CreDtTm></GrpHdr>\n\
+                     <Stmt>\n\
+                     <Id>{statement_id}</Id>\n\
+                     <CreDtTm>{created_at}</CreDtTm>\n\
+                     <FrToDt><FrDtTm>{from}</FrDtTm><ToDtTm>{to}</ToDtTm></FrToDt>\n\
+                     <Acct><Id><IBAN>{iban}</IBAN></Id><Ccy>{currency}</Ccy></Acct>\n\
+                     {opening_balance}\
+                     {closing_balance}",
+                    statement_id = xml_escape(statement_id),
+                    created_at = format_date_time(created_at),
+                    from = format_date_time(&statement.from),
+                    to = format_date_time(&statement.to),
+                    iban = xml_escape(&statement.account.iban.to_string()),
+                    opening_balance = camt053_balance("OPBD", &statement.opening_balance, &statement.from),
+                    closing_balance = camt053_balance("CLBD", &statement.closing_balance, &statement.to),
+                )
+            }
+        }
+    }
+
+    pub fn entry(&self, entry: &AccountTransaction) -> String {
+        match self {
+            StatementFormat::Csv => format!(
+                "ENTRY,{},{},{},{},{},{},{},{}\r\n",
+                format_date_time(&entry.created_at),
+                entry.id,
+                entry.transfer_id,
+                entry.counterparty_account_id.as_ref().map(|id| id.to_string()).unwrap_or_default(),
+                format_amount_value(entry.amount.value_ref()),
+                entry.amount.currency(),
+                format_amount_value(entry.balance_after.value_ref()),
+                entry.reversed_transfer_id.as_ref().map(|id| id.to_string()).unwrap_or_default(),
+            ),
+            StatementFormat::Camt053 => {
+                let booked_at = format_date_time(&entry.created_at);
+                let additional_info = match entry.reversed_transfer_id {
+                    None => String::new(),
+                    Some(ref reversed_transfer_id) =>
+                        format!("<AddtlNtryInf>Reversal of {reversed_transfer_id}</AddtlNtryInf>"),
+                };
+                format!(
+                    "<Ntry>\
+                     <NtryRef>{id}</NtryRef>\
+                     <Amt Ccy=\"{currency}\">{amount}</Amt>\
+                     <CdtDbtInd>{credit_debit}</CdtDbtInd>\
+                     <Sts>BOOK</Sts>\
+                     <BookgDt><DtTm>{booked_at}</DtTm></BookgDt>\
+                     <ValDt><DtTm>{booked_at}</DtTm></ValDt>\
+                     <BkTxCd><Prtry><Cd>TRANSFER</Cd></Prtry></BkTxCd>\
+                     <NtryDtls><TxDtls><Refs><EndToEndId>{transfer_id}</EndToEndId></Refs></TxDtls></NtryDtls>\
+                     {additional_info}\
+                     </Ntry>\n",
+                    id = entry.id,
+                    currency = entry.amount.currency(),
+                    amount = format_amount_value(&entry.amount.value_ref().abs()),
+                    credit_debit = credit_debit_indicator(entry.amount.value_ref()),
+                    transfer_id = entry.transfer_id,
+                )
+            }
+        }
+    }
+
+    pub fn footer(&self, statement: &AccountStatement) -> String {
+        match self {
+            StatementFormat::Csv => format!(
+                "CLOSING_BALANCE,{},,,,{},{},,\r\n",
+                format_date_time(&statement.to),
+                format_amount_value(statement.closing_balance.value_ref()),
+                statement.closing_balance.currency(),
+            ),
+            StatementFormat::Camt053 =>
+                "</Stmt>\n</BkToCstmrStmt>\n</Document>\n".to_owned(),
+        }
+    }
+}
+
+
+fn camt053_balance(balance_type: &str, balance: &Amount, at: &chrono::DateTime<Utc>) -> String {
+    format!(
+        "<Bal><Tp><CdOrPrtry><Cd>{balance_type}</Cd></CdOrPrtry></Tp>\
+         <Amt Ccy=\"{currency}\">{amount}</Amt>\
+         <CdtDbtInd>{credit_debit}</CdtDbtInd>\
+         <Dt><DtTm>{at}</DtTm></Dt></Bal>\n",
+        currency = balance.currency(),
+        amount = format_amount_value(&balance.value_ref().abs()),
+        credit_debit = credit_debit_indicator(balance.value_ref()),
+        at = format_date_time(at),
+    )
+}
+
+/// Negative balance (overdraft) and debit entries are 'DBIT' (amounts are always positive in camt.053).
+fn credit_debit_indicator(value: &BigDecimal) -> &'static str {
+    if value < &BigDecimal::from(0i32) { "DBIT" } else { "CRDT" }
+}
+
+/// Exact decimal value (without exponent and without trailing zeroes), but at least with 2 fraction digits.
+fn format_amount_value(value: &BigDecimal) -> String {
+    let normalized = value.normalized();
+    let (_, scale) = normalized.as_bigint_and_exponent();
+    if scale < 2 { normalized.with_scale(2).to_string() }
+    else { normalized.to_string() }
+}
+
+fn format_date_time(date_time: &chrono::DateTime<Utc>) -> String {
+    date_time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use mvv_auth::util::test_unwrap::TestResultUnwrap;
+    use super::{ credit_debit_indicator, format_amount_value, xml_escape };
+
+    #[test]
+    fn amount_value_format() {
+        let format = |s: &str| format_amount_value(&BigDecimal::from_str(s).test_unwrap());
+
+        assert_eq!(format("150.000000"), "150.00");
+        assert_eq!(format("1500"), "1500.00");
+        assert_eq!(format("12.5"), "12.50");
+        assert_eq!(format("-12.345678"), "-12.345678");
+        assert_eq!(format("0.000001"), "0.000001");
+        assert_eq!(format("0"), "0.00");
+    }
+
+    #[test]
+    fn camt053_credit_debit_indicator() {
+        assert_eq!(credit_debit_indicator(&BigDecimal::from_str("-0.01").test_unwrap()), "DBIT");
+        assert_eq!(credit_debit_indicator(&BigDecimal::from_str("0").test_unwrap()), "CRDT");
+        assert_eq!(credit_debit_indicator(&BigDecimal::from_str("10").test_unwrap()), "CRDT");
+    }
+
+    #[test]
+    fn xml_escape_test() {
+        assert_eq!(xml_escape("a<b>&\"c'"), "a&lt;b&gt;&amp;&quot;c&apos;");
+    }
+}
@@ -10,7 +10,7 @@ use mvv_auth::PlainPasswordComparator;
 
 use mvv_common::{
     db::pg07::pg07_db_connection as pg_db_connection,
-    env::process_env_load_res,
+    env::{ env_var_static, required_env_var_static, process_env_load_res },
     exe::{current_exe_name, current_exe_dir},
     rest::health_check_router,
     server::start_axum_server,
@@ -19,8 +19,18 @@ use mvv_common::{
     utoipa::{generate_open_api, nest_open_api, to_generate_open_api},
 };
 use crate::cfg::AccountSoaServerConfig;
+use crate::database::migration::migrate_up;
 use crate::service::{
     account_service::{ AccountService, AccountServiceImpl },
+    exchange_rate::SqlExchangeRateProvider,
+    standing_order_scheduler::{ StandingOrderScheduler, DEFAULT_SCHEDULER_INTERVAL },
+    outbox::{ EventSink, LogEventSink, OutboxDispatcher, WebhookEventSink, DEFAULT_DISPATCHER_INTERVAL },
+    webhook_delivery::{
+        create_webhook_delivery_http_client, create_webhook_http_client,
+        WebhookDeliveryWorker, DEFAULT_DELIVERY_INTERVAL,
+    },
+    account_changes::AccountChangeListener,
+    reconciliation::{ ReconciliationJob, DEFAULT_RECONCILIATION_INTERVAL },
 };
 use crate::rest::{
     app_dependencies::{ Dependencies, DependenciesState },
@@ -35,11 +45,26 @@ use crate::web::{
 
 
 
+pub(crate) fn create_prod_db_connection() -> Result<Arc<sqlx_postgres::PgPool>, anyhow::Error> {
+    Ok(Arc::new(pg_db_connection("account_soa", ConnectionType::Ssl) ?))
+}
+
+pub(crate) fn create_prod_account_service(db: &Arc<sqlx_postgres::PgPool>) -> Arc<AccountServiceImpl> {
+    let exchange_rate_provider = Arc::new(SqlExchangeRateProvider { database_connection: Arc::clone(db) });
+    Arc::new(AccountServiceImpl {
+        database_connection: Arc::clone(db),
+        exchange_rate_provider,
+    })
+}
+
 fn create_prod_dependencies() -> Result<Dependencies<AccountServiceImpl>, anyhow::Error> {
 
-    let db = Arc::new(pg_db_connection("account_soa", ConnectionType::Ssl) ?);
-    let account_service = Arc::new(AccountServiceImpl { database_connection: Arc::clone(&db) });
-    let account_rest = Arc::new(AccountRest::<AccountServiceImpl> { account_service: Arc::clone(&account_service) });
+    let db = create_prod_db_connection() ?;
+    let account_service = create_prod_account_service(&db);
+    let account_rest = Arc::new(AccountRest::<AccountServiceImpl> {
+        account_service: Arc::clone(&account_service),
+        account_changes: Arc::new(AccountChangeListener::new()),
+    });
 
     Ok(Dependencies::<AccountServiceImpl> { state: Arc::new(DependenciesState {
         database_connection: Arc::clone(&db),
@@ -50,6 +75,115 @@ fn create_prod_dependencies() -> Result<Dependencies<AccountServiceImpl>, anyhow
     })})
 }
 
+/// With ACCOUNT_SOA_AUTO_MIGRATE=true pending DB migrations are applied before server start,
+/// otherwise DB should be migrated by 'mvv_account_soa migrate up'.
+async fn auto_migrate_db <
+    AccountS: AccountService + Send + Sync + 'static,
+> (dependencies: &Dependencies<AccountS>) -> Result<(), anyhow::Error> {
+
+    let auto_migrate = env_var_static("ACCOUNT_SOA_AUTO_MIGRATE") ?
+        .map(|value| value.parse::<bool>())
+        .transpose() ?
+        .unwrap_or(false);
+
+    if auto_migrate {
+        let applied = migrate_up(&dependencies.state.database_connection).await ?;
+        log::info!("DB is migrated ({} migrations are applied)", applied.len());
+    }
+    Ok(())
+}
+
+/// Interval of background task in seconds ('tokio::time::interval' panics on zero period).
+fn interval_env_var(var_name: &'static str, default_interval: std::time::Duration)
+    -> Result<std::time::Duration, anyhow::Error> {
+
+    let Some(secs) = env_var_static(var_name) ? else { return Ok(default_interval) };
+    let secs = secs.parse::<u64>() ?;
+    if secs == 0 {
+        anyhow::bail!("{var_name} should be positive.");
+    }
+    Ok(std::time::Duration::from_secs(secs))
+}
+
+/// Scheduler task lives until the process is stopped.
+fn start_standing_order_scheduler <
+    AccountS: AccountService + Send + Sync + 'static,
+> (dependencies: &Dependencies<AccountS>) -> Result<(), anyhow::Error> {
+
+    let interval = interval_env_var("ACCOUNT_SOA_STANDING_ORDERS_INTERVAL_SECS", DEFAULT_SCHEDULER_INTERVAL) ?;
+
+    let scheduler = Arc::new(StandingOrderScheduler::<AccountS> {
+        database_connection: Arc::clone(&dependencies.state.database_connection),
+        account_service: Arc::clone(&dependencies.state.account_service),
+    });
+    let _scheduler_task = scheduler.start(interval);
+    Ok(())
+}
+
+/// Event sink is chosen by ACCOUNT_SOA_EVENT_SINK: 'log' (default), 'webhook' or 'none'
+/// (with 'none' events are kept in outbox till another replica/sink publishes them).
+fn start_outbox_dispatcher <
+    AccountS: AccountService + Send + Sync + 'static,
+> (dependencies: &Dependencies<AccountS>) -> Result<(), anyhow::Error> {
+
+    let sink: Arc<dyn EventSink> = match env_var_static("ACCOUNT_SOA_EVENT_SINK") ?.as_deref() {
+        None | Some("log") => Arc::new(LogEventSink),
+        Some("webhook") => {
+            let url = required_env_var_static("ACCOUNT_SOA_EVENT_WEBHOOK_URL") ?;
+            Arc::new(WebhookEventSink { client: create_webhook_http_client() ?, url })
+        }
+        Some("none") => return Ok(()),
+        Some(other) => anyhow::bail!("Unknown event sink [{other}]."),
+    };
+
+    let interval = interval_env_var("ACCOUNT_SOA_OUTBOX_INTERVAL_SECS", DEFAULT_DISPATCHER_INTERVAL) ?;
+
+    let dispatcher = Arc::new(OutboxDispatcher {
+        database_connection: Arc::clone(&dependencies.state.database_connection),
+        sink,
+        dispatcher_id: uuid::Uuid::new_v4(),
+    });
+    let _dispatcher_task = dispatcher.start(interval);
+    Ok(())
+}
+
+/// Sends notifications of client webhook subscriptions.
+fn start_webhook_delivery_worker <
+    AccountS: AccountService + Send + Sync + 'static,
+> (dependencies: &Dependencies<AccountS>) -> Result<(), anyhow::Error> {
+
+    let interval = interval_env_var("ACCOUNT_SOA_WEBHOOK_DELIVERY_INTERVAL_SECS", DEFAULT_DELIVERY_INTERVAL) ?;
+
+    let worker = Arc::new(WebhookDeliveryWorker {
+        database_connection: Arc::clone(&dependencies.state.database_connection),
+        client: create_webhook_delivery_http_client() ?,
+    });
+    let _worker_task = worker.start(interval);
+    Ok(())
+}
+
+/// Drift of balances is reported by error log (alarm).
+fn start_reconciliation_job <
+    AccountS: AccountService + Send + Sync + 'static,
+> (dependencies: &Dependencies<AccountS>) -> Result<(), anyhow::Error> {
+
+    let interval = interval_env_var("ACCOUNT_SOA_RECONCILIATION_INTERVAL_SECS", DEFAULT_RECONCILIATION_INTERVAL) ?;
+
+    let job = Arc::new(ReconciliationJob {
+        database_connection: Arc::clone(&dependencies.state.database_connection),
+    });
+    let _job_task = job.start(interval);
+    Ok(())
+}
+
+/// Account changes (committed by any replica) are pushed to SSE streams of this replica.
+fn start_account_change_listener <
+    AccountS: AccountService + Send + Sync + 'static,
+> (dependencies: &Dependencies<AccountS>) {
+    let listener = Arc::clone(&dependencies.state.account_rest.account_changes);
+    let _listener_task = listener.start(Arc::clone(&dependencies.state.database_connection));
+}
+
 //noinspection DuplicatedCode
 fn init_logger() {
 
@@ -173,7 +307,8 @@ async fn create_app_route <
 }
 
 
-pub async fn web_app_main() -> Result<(), anyhow::Error> {
+/// Loads '.{exe_name}.env' file and initializes logger (it is shared by server and CLI sub-commands).
+pub(crate) fn init_app_env() -> Result<(), anyhow::Error> {
 
     let env_filename = format!(".{}.env", current_exe_name() ?);
 
@@ -181,17 +316,32 @@ pub async fn web_app_main() -> Result<(), anyhow::Error> {
     let dotenv_res = dotenv::from_filename(&env_filename);
 
     init_logger();
-    log::info!("Hello from [web_app_main]");
 
     // !!! After logger initialization !!!
     process_env_load_res(&env_filename, dotenv_res) ?;
+    Ok(())
+}
+
+
+pub async fn web_app_main() -> Result<(), anyhow::Error> {
+
+    init_app_env() ?;
+    log::info!("Hello from [web_app_main]");
 
     if to_generate_open_api() {
         generate_open_api(&create_open_api(), env!("CARGO_PKG_NAME"), UpdateFile::IfModelChanged, None) ?;
         return Ok(())
     }
 
-    let app_router = create_app_route(create_prod_dependencies() ?).await ?;
+    let dependencies = create_prod_dependencies() ?;
+    auto_migrate_db(&dependencies).await ?;
+    start_standing_order_scheduler(&dependencies) ?;
+    start_outbox_dispatcher(&dependencies) ?;
+    start_webhook_delivery_worker(&dependencies) ?;
+    start_account_change_listener(&dependencies);
+    start_reconciliation_job(&dependencies) ?;
+
+    let app_router = create_app_route(dependencies).await ?;
     let server_cfg = AccountSoaServerConfig::load_from_env("account_soa".into(), "ACCOUNT_SOA".into()) ?;
 
     start_axum_server(server_cfg, app_router).await ?;
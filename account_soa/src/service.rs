@@ -1 +1,9 @@
-pub mod account_service;
\ No newline at end of file
+pub mod account_service;
+pub mod exchange_rate;
+pub mod standing_order_scheduler;
+pub mod interest_accrual;
+pub mod outbox;
+pub mod webhook_delivery;
+pub mod account_changes;
+pub mod reconciliation;
+pub mod background;
@@ -0,0 +1,128 @@
+use std::sync::Arc;
+use std::time::Duration;
+use log::{ error, info, warn };
+use tokio::sync::broadcast;
+use crate::entity::ClientId;
+//--------------------------------------------------------------------------------------------------
+
+
+
+/// Postgres NOTIFY channel, payload is client ID whose account balances are changed.
+/// Notification is sent by AccountServiceImpl in the same DB transaction as account change,
+/// so it is delivered (to all replicas) only after commit.
+pub const ACCOUNT_CHANGES_CHANNEL: &str = "account_changes";
+
+/// Max count of not received changes per subscriber, slower subscriber gets RecvError::Lagged.
+const CHANGES_CAPACITY: usize = 1024;
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccountChange {
+    /// Accounts of this client are changed.
+    Client(ClientId),
+    /// Connection to DB was lost and some notifications could be missed
+    /// (all subscribers should reload their accounts).
+    Unknown,
+}
+
+
+/// Listens Postgres notifications (ACCOUNT_CHANGES_CHANNEL) and re-broadcasts them
+/// to in-process subscribers (for example, SSE streams of client accounts).
+/// Listener holds one connection of the pool all the time.
+pub struct AccountChangeListener {
+    sender: broadcast::Sender<AccountChange>,
+}
+
+impl AccountChangeListener {
+
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANGES_CAPACITY);
+        AccountChangeListener { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<AccountChange> {
+        self.sender.subscribe()
+    }
+
+    pub fn start(self: Arc<Self>, database_connection: Arc<sqlx_postgres::PgPool>) -> tokio::task::JoinHandle<()> {
+        info!("### Starting account changes listener");
+
+        tokio::spawn(async move {
+            loop {
+                if let Err(err) = self.listen(&database_connection).await {
+                    error!("### Error of listening account changes: {err:?}");
+                }
+                self.publish(AccountChange::Unknown);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        })
+    }
+
+    async fn listen(&self, database_connection: &sqlx_postgres::PgPool) -> Result<(), sqlx::Error> {
+        let mut listener = sqlx_postgres::PgListener::connect_with(database_connection).await ?;
+        listener.listen(ACCOUNT_CHANGES_CHANNEL).await ?;
+
+        loop {
+            match listener.try_recv().await ? {
+                Some(notification) => match parse_client_id(notification.payload()) {
+                    Some(client_id) => self.publish(AccountChange::Client(client_id)),
+                    None => warn!("### Unexpected account change notification [{}]", notification.payload()),
+                },
+                // Connection is lost, listener reconnects by next try_recv().
+                None => self.publish(AccountChange::Unknown),
+            }
+        }
+    }
+
+    fn publish(&self, change: AccountChange) {
+        // Error only means that there are no subscribers now.
+        let _ = self.sender.send(change);
+    }
+}
+
+impl Default for AccountChangeListener {
+    fn default() -> Self {
+        AccountChangeListener::new()
+    }
+}
+
+
+fn parse_client_id(payload: &str) -> Option<ClientId> {
+    ClientId::from_str(payload.trim()).ok()
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use mvv_auth::util::test_unwrap::TestResultUnwrap;
+    use crate::entity::ClientId;
+    use super::{ parse_client_id, AccountChange, AccountChangeListener };
+
+    #[test]
+    fn parse_notification_payload() {
+        assert_eq!(
+            parse_client_id("00000000-0000-0000-0000-000000000001"),
+            Some(ClientId::from_str("00000000-0000-0000-0000-000000000001").test_unwrap()),
+        );
+        assert_eq!(parse_client_id(""), None);
+        assert_eq!(parse_client_id("not-a-client-id"), None);
+    }
+
+    #[tokio::test]
+    async fn changes_are_broadcast_to_all_subscribers() {
+        let listener = AccountChangeListener::new();
+        // No subscribers yet, change is just dropped.
+        listener.publish(AccountChange::Unknown);
+
+        let mut subscriber1 = listener.subscribe();
+        let mut subscriber2 = listener.subscribe();
+
+        let client_id = ClientId::from_str("00000000-0000-0000-0000-000000000001").test_unwrap();
+        listener.publish(AccountChange::Client(client_id.clone()));
+
+        assert_eq!(subscriber1.recv().await.test_unwrap(), AccountChange::Client(client_id.clone()));
+        assert_eq!(subscriber2.recv().await.test_unwrap(), AccountChange::Client(client_id));
+    }
+}
@@ -1,24 +1,43 @@
 use std::sync::Arc;
 use bigdecimal::BigDecimal;
 use chrono::Utc;
-use log::{ debug, info };
-use sqlx::Transaction;
+use futures::stream::BoxStream;
+use log::{ debug, info, warn };
+use sqlx::{ Acquire, Transaction };
 use sqlx_postgres::Postgres;
 use mvv_common::{
     backtrace::{backtrace, BacktraceCell},
+    secure::SecureString,
 };
 use mvv_common_bank_entities::{
-    amount::Amount, bd::{BigDecimalWrapper }, amount::ops::AmountOpsError, AmountParts,
+    amount::Amount, bd::{BigDecimalWrapper, BigDecimalRefWrapper }, amount::ops::AmountOpsError, AmountParts,
 };
 use crate::entity::{
-    account::{ self },
-    IbanWrapper, IbanRefWrapper, prelude::{ Account, AccountId },
+    account::{ self, AccountName, AccountStatus },
+    transaction::{ self, AccountTransaction, TransferId },
+    hold::{ self, Hold, HoldId, HoldStatus },
+    standing_order::{ self, StandingOrder, StandingOrderId, StandingOrderPeriod },
+    transfer_limit::{ ClientTier, TransferLimitKind },
+    fee::{ FeeRule, FeeTransferType },
+    pending_transfer::{ self, PendingTransfer, PendingTransferId, PendingTransferStatus },
+    interest::{ self, DayCountConvention, InterestAccrual, is_last_day_of_month },
+    idempotency::{ Idempotency, IdempotencyKey },
+    account_event::{ AccountEvent, EventAmount },
+    webhook::{
+        self, ClientNotification, WebhookDelivery, WebhookDeliveryAttempt, WebhookEventType,
+        WebhookSubscription, WebhookSubscriptionId, transfer_notifications,
+    },
+    IbanWrapper, IbanRefWrapper, make_iban, prelude::{ Account, AccountId, Currency },
     ClientId,
 };
+use crate::service::exchange_rate::{
+    ExchangeRate, ExchangeRateError, ExchangeRateProvider, ExpectedRate, SqlExchangeRateProvider,
+};
+use crate::service::account_changes::ACCOUNT_CHANGES_CHANNEL;
 //--------------------------------------------------------------------------------------------------
 
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AccountIdWrapper {
     Id(AccountId),
     Iban(iban::Iban),
@@ -34,10 +53,56 @@ pub enum AccountIdWrapper {
 pub enum AccountProcessError {
     #[error("AccountNotFound")]
     AccountNotFound(AccountIdWrapper, BacktraceCell),
-    #[error("NotEnoughBalance")]
-    NotEnoughBalance(AccountIdWrapper, BacktraceCell),
+    #[error("NotEnoughBalance (available funds: {1})")]
+    NotEnoughBalance(AccountIdWrapper, Amount, BacktraceCell),
+    #[error("AccountNotActive (account {0:?} has status {1})")]
+    AccountNotActive(AccountIdWrapper, AccountStatus, BacktraceCell),
+    #[error("AccountStatusChangeNotAllowed (account {0:?}: {1} => {2})")]
+    AccountStatusChangeNotAllowed(AccountIdWrapper, AccountStatus, AccountStatus, BacktraceCell),
+    #[error("AccountIsNotEmpty (account {0:?} cannot be closed)")]
+    AccountIsNotEmpty(AccountIdWrapper, BacktraceCell),
+    #[error("SameAccountTransfer (account {0:?})")]
+    SameAccountTransfer(AccountIdWrapper, BacktraceCell),
+    #[error("BeneficiaryMismatch (beneficiary name is absent or does not match owner of account {0:?})")]
+    BeneficiaryMismatch(AccountIdWrapper, BacktraceCell),
+    #[error("IdempotencyKeyConflict (key [{0}] is already used for another request)")]
+    IdempotencyKeyConflict(IdempotencyKey, BacktraceCell),
+    #[error("HoldNotFound (hold {0})")]
+    HoldNotFound(HoldId, BacktraceCell),
+    #[error("HoldNotActive (hold {0} has status {1})")]
+    HoldNotActive(HoldId, HoldStatus, BacktraceCell),
+    #[error("HoldAmountExceeded (captured amount exceeds held amount {1} of hold {0})")]
+    HoldAmountExceeded(HoldId, Amount, BacktraceCell),
+    #[error("StandingOrderNotFound (standing order {0})")]
+    StandingOrderNotFound(StandingOrderId, BacktraceCell),
+    #[error("TransferNotFound (transfer {0})")]
+    TransferNotFound(TransferId, BacktraceCell),
+    #[error("TransferAlreadyReversed (transfer {0})")]
+    TransferAlreadyReversed(TransferId, BacktraceCell),
+    #[error("TransferNotReversible (transfer {0} is reversal or fee, it cannot be reversed)")]
+    TransferNotReversible(TransferId, BacktraceCell),
+    #[error("ReversalNotEnoughBalance (transfer {0} cannot be reversed, available funds of beneficiary account {1}: {2})")]
+    ReversalNotEnoughBalance(TransferId, AccountId, Amount, BacktraceCell),
+    #[error("LimitExceeded ({0} limit {2} of client tier {1} is exceeded)")]
+    LimitExceeded(TransferLimitKind, ClientTier, Amount, BacktraceCell),
+    #[error("WebhookSubscriptionNotFound (webhook subscription {0})")]
+    WebhookSubscriptionNotFound(WebhookSubscriptionId, BacktraceCell),
+    #[error("PendingTransferNotFound (pending transfer {0})")]
+    PendingTransferNotFound(PendingTransferId, BacktraceCell),
+    #[error("PendingTransferIsDecided (pending transfer {0} has status {1})")]
+    PendingTransferIsDecided(PendingTransferId, PendingTransferStatus, BacktraceCell),
+    #[error("SelfApprovalNotAllowed (pending transfer {0} cannot be approved/rejected by its creator)")]
+    SelfApprovalNotAllowed(PendingTransferId, BacktraceCell),
+    #[error("ApprovalRequired (amount {0} is above approval threshold, transfer should be created as pending one)")]
+    ApprovalRequired(Amount, BacktraceCell),
+    #[error("IllegalArgument ({0})")]
+    IllegalArgument(String, BacktraceCell),
+    #[error("ExchangeRateChanged (current rate [{0}] differs from expected [{1}] more than allowed)")]
+    ExchangeRateChanged(BigDecimal, BigDecimal, BacktraceCell),
     #[error("AmountOpsError {{ {0} }}")]
     AmountOpsError(#[from] AmountOpsError),
+    #[error("ExchangeRateError {{ {0} }}")]
+    ExchangeRateError(#[from] ExchangeRateError),
     #[error("Internal")]
     Internal(#[source] anyhow::Error),
     #[error("Sqlx error")]
@@ -45,6 +110,149 @@ pub enum AccountProcessError {
 }
 
 
+/// Filter/pagination params for account history.
+#[derive(Debug, Clone)]
+pub struct AccountTransactionsQuery {
+    /// Inclusive.
+    pub from: Option<chrono::DateTime<Utc>>,
+    /// Exclusive.
+    pub to: Option<chrono::DateTime<Utc>>,
+    /// ID of the last entry of previous page (entries are sorted from newest to oldest).
+    pub cursor: Option<i64>,
+    pub limit: u32,
+}
+
+#[derive(Debug)]
+pub struct AccountTransactionsPage {
+    pub transactions: Vec<AccountTransaction>,
+    /// It is None if there are no more entries.
+    pub next_cursor: Option<i64>,
+}
+
+pub const MAX_TRANSACTIONS_PAGE_SIZE: u32 = 200;
+
+
+/// One transfer of batch (see AccountService::transfer()).
+#[derive(Debug)]
+pub struct TransferLeg {
+    pub from_account: AccountIdWrapper,
+    pub to_account: AccountIdWrapper,
+    pub beneficiary_name: Option<String>,
+    pub amount: Amount,
+    pub expected_rate: Option<ExpectedRate>,
+}
+
+#[derive(Debug)]
+pub enum TransferLegStatus {
+    /// Leg is done (if batch is committed) or it could be done (if batch is rejected due to other legs).
+    Ok(TransferId),
+    Failed(AccountProcessError),
+}
+
+#[derive(Debug)]
+pub struct BatchTransferResult {
+    /// If it is false, no leg is applied.
+    pub committed: bool,
+    /// In the same order as legs of request.
+    pub legs: Vec<TransferLegStatus>,
+}
+
+pub const MAX_BATCH_TRANSFER_LEGS: usize = 100;
+
+
+/// Transfer which needs approval (see AccountService::create_pending_transfer()).
+#[derive(Debug)]
+pub struct NewPendingTransfer {
+    pub from_account: AccountIdWrapper,
+    pub to_account: AccountIdWrapper,
+    pub beneficiary_name: Option<String>,
+    pub amount: Amount,
+}
+
+
+/// Preliminary calculation of transfer (see AccountService::quote_transfer()).
+#[derive(Debug)]
+pub struct TransferQuote {
+    pub amount: Amount,
+    /// Zero if transfer is free.
+    pub fee: Amount,
+    /// Amount + fee (in 'from' account currency).
+    pub total_debit_amount: Amount,
+    /// In 'to' account currency.
+    pub credit_amount: Amount,
+    /// Only for cross-currency transfer.
+    pub exchange_rate: Option<ExchangeRate>,
+}
+
+
+/// Account activity for period [from, to).
+pub struct AccountStatement {
+    pub account: Account,
+    pub from: chrono::DateTime<Utc>,
+    pub to: chrono::DateTime<Utc>,
+    /// Balance at 'from'.
+    pub opening_balance: Amount,
+    /// Balance at 'to'.
+    pub closing_balance: Amount,
+    /// Ledger entries from oldest to newest. They are loaded lazily
+    /// (from the same DB snapshot as balances), so statement is not kept in memory.
+    pub entries: BoxStream<'static, Result<AccountTransaction, AccountProcessError>>,
+}
+
+
+/// Recurring transfer (see AccountService::create_standing_order()).
+#[derive(Debug)]
+pub struct NewStandingOrder {
+    pub from_account: AccountIdWrapper,
+    pub to_account: AccountIdWrapper,
+    pub beneficiary_name: Option<String>,
+    pub amount: Amount,
+    pub period: StandingOrderPeriod,
+    /// Time of the first execution.
+    pub start_at: chrono::DateTime<Utc>,
+}
+
+/// Editable fields of standing order (accounts cannot be changed).
+#[derive(Debug)]
+pub struct StandingOrderChange {
+    pub beneficiary_name: Option<String>,
+    pub amount: Amount,
+    pub period: StandingOrderPeriod,
+    pub start_at: chrono::DateTime<Utc>,
+}
+
+/// Webhook subscription (see AccountService::create_webhook_subscription()).
+#[derive(Debug)]
+pub struct NewWebhookSubscription {
+    pub url: String,
+    pub secret: SecureString,
+    pub event_types: Vec<WebhookEventType>,
+}
+
+#[derive(Debug)]
+pub struct WebhookSubscriptionChange {
+    pub url: String,
+    /// If it is None, current secret is kept.
+    pub secret: Option<SecureString>,
+    pub event_types: Vec<WebhookEventType>,
+    pub active: bool,
+}
+
+/// Max count of deliveries returned by AccountService::get_client_webhook_deliveries().
+pub const MAX_WEBHOOK_DELIVERIES_PAGE_SIZE: i64 = 100;
+
+/// Country code and bank code of IBANs of new accounts.
+const IBAN_COUNTRY_CODE: &str = "UA";
+const IBAN_BANK_CODE: &str = "305299";
+const IBAN_ACCOUNT_NUMBER_LEN: usize = 19;
+
+/// Max TTL of hold (usual card authorization lives up to 7 days, but some merchants need up to 30).
+pub const MAX_HOLD_TTL: chrono::Duration = chrono::Duration::days(30);
+
+/// Max count of attempts of DB transaction which failed due to serialization failure or deadlock.
+const MAX_TX_ATTEMPTS: u32 = 3;
+
+
 // #[trait_variant::make(SendAccountService: Send)]
 #[trait_variant::make(Send)]
 // or #[async_trait] // https://github.com/dtolnay/async-trait#dyn-traits
@@ -52,16 +260,143 @@ pub trait AccountService: Send + Sync {
     async fn get_client_accounts(&self, client_id: ClientId) -> Result<Vec<Account>, AccountProcessError>;
     async fn get_client_account_by_id(&self, client_id: ClientId, account_id: AccountId) -> Result<Account, AccountProcessError>;
     async fn get_client_account_by_iban(&self, client_id: ClientId, iban: iban::Iban) -> Result<Account, AccountProcessError>;
-    async fn transfer_by_iban(&self, client_id: ClientId, from_account: iban::Iban, to_account: iban::Iban, amount: Amount) -> Result<(), AccountProcessError>;
-    async fn transfer_by_id(&self, client_id: ClientId, from_account: AccountId, to_account: AccountId, amount: Amount) -> Result<(), AccountProcessError>;
+    /// Amount should have currency of 'from' account. If 'to' account has another currency
+    /// amount is converted using current exchange rate (it should not differ from 'expected_rate' if it is passed).
+    ///
+    /// If the same idempotency key was already used (for the same request) result of previous transfer is returned.
+    ///
+    /// Each account can be given by ID or by IBAN independently.
+    /// 'from' account should belong to client. 'to' (beneficiary) account given by IBAN can belong
+    /// to any client of the bank, in this case 'beneficiary_name' is required
+    /// and should match first/last name of account owner.
+    ///
+    /// Amount above approval threshold is rejected (see create_pending_transfer()).
+    /// The same is true for every leg of batch, hold capture and standing order.
+    async fn transfer(&self, client_id: ClientId, from_account: AccountIdWrapper, to_account: AccountIdWrapper,
+                      beneficiary_name: Option<String>, amount: Amount,
+                      expected_rate: Option<ExpectedRate>, idempotency: Option<Idempotency>)
+        -> Result<TransferId, AccountProcessError>;
+    /// Calculates fee and credit amount of transfer without executing it.
+    /// Real transfer can differ if fee rules or exchange rate are changed meanwhile.
+    async fn quote_transfer(&self, client_id: ClientId, from_account: AccountIdWrapper, to_account: AccountIdWrapper,
+                            beneficiary_name: Option<String>, amount: Amount)
+        -> Result<TransferQuote, AccountProcessError>;
+    /// All legs are applied in one DB transaction (all or nothing).
+    /// If any leg is failed, nothing is applied and status of every leg is returned
+    /// (with Ok result of BatchTransferResult::committed = false).
+    ///
+    /// If the same idempotency key was already used (for the same request) by committed batch,
+    /// result of that batch is returned. Key of rejected batch is not stored.
+    async fn transfer_batch(&self, client_id: ClientId, legs: Vec<TransferLeg>, idempotency: Option<Idempotency>)
+        -> Result<BatchTransferResult, AccountProcessError>;
+    /// Creates compensating ledger entries (linked to original transfer) and returns their transfer ID.
+    /// Amounts are returned as they were (without exchange rate re-calculation).
+    /// Fee charged for original transfer is refunded in the same transaction.
+    /// Reversal and fee transfers cannot be reversed.
+    /// Client is the owner of debited ('from') account of original transfer.
+    async fn reverse_transfer(&self, client_id: ClientId, transfer_id: TransferId)
+        -> Result<TransferId, AccountProcessError>;
+    async fn get_client_account_transactions(&self, client_id: ClientId, account_id: AccountIdWrapper, query: AccountTransactionsQuery)
+        -> Result<AccountTransactionsPage, AccountProcessError>;
+    /// Balances and entries for period [from, to).
+    async fn get_client_account_statement(&self, client_id: ClientId, account_id: AccountIdWrapper,
+                                          from: chrono::DateTime<Utc>, to: chrono::DateTime<Utc>)
+        -> Result<AccountStatement, AccountProcessError>;
+    /// Opens new (empty and active) account with generated IBAN.
+    async fn open_client_account(&self, client_id: ClientId, name: AccountName, currency: Currency)
+        -> Result<Account, AccountProcessError>;
+    /// Only transitions allowed by AccountStatus::can_be_changed_to() are allowed.
+    /// Account can be closed only if its balance is zero.
+    async fn change_client_account_status(&self, client_id: ClientId, account_id: AccountIdWrapper, status: AccountStatus)
+        -> Result<Account, AccountProcessError>;
+    /// Limit should have account currency. Current balance should not be less than -limit.
+    async fn change_client_account_overdraft_limit(&self, client_id: ClientId, account_id: AccountIdWrapper, overdraft_limit: Amount)
+        -> Result<Account, AccountProcessError>;
+    /// Reserves amount (of account currency) until hold is captured/released or 'ttl' is over.
+    /// Hold reduces available funds, but not ledger balance.
+    /// Amount should not be above approval threshold (capture is executed without approval).
+    async fn authorize_hold(&self, client_id: ClientId, account_id: AccountIdWrapper, amount: Amount, ttl: chrono::Duration)
+        -> Result<Hold, AccountProcessError>;
+    /// Converts active hold to real transfer (the same as AccountService::transfer()).
+    /// If 'amount' is less than held one (or partial capture), the rest is released.
+    async fn capture_hold(&self, client_id: ClientId, hold_id: HoldId, to_account: AccountIdWrapper,
+                          beneficiary_name: Option<String>, amount: Option<Amount>)
+        -> Result<TransferId, AccountProcessError>;
+    async fn release_hold(&self, client_id: ClientId, hold_id: HoldId)
+        -> Result<Hold, AccountProcessError>;
+    /// All (including captured/released/expired) holds of account, from newest to oldest.
+    async fn get_client_account_holds(&self, client_id: ClientId, account_id: AccountIdWrapper)
+        -> Result<Vec<Hold>, AccountProcessError>;
+    /// Order is executed by StandingOrderScheduler (as AccountService::transfer()).
+    /// 'to' account is stored by IBAN, it can belong to another client (then 'beneficiary_name' is required).
+    /// Amount should not be above approval threshold.
+    async fn create_standing_order(&self, client_id: ClientId, order: NewStandingOrder)
+        -> Result<StandingOrder, AccountProcessError>;
+    async fn get_client_standing_orders(&self, client_id: ClientId)
+        -> Result<Vec<StandingOrder>, AccountProcessError>;
+    async fn get_client_standing_order(&self, client_id: ClientId, order_id: StandingOrderId)
+        -> Result<StandingOrder, AccountProcessError>;
+    /// Schedule is started again from new 'start_at' (retry state of the next execution is reset).
+    /// Amount should not be above approval threshold.
+    async fn change_client_standing_order(&self, client_id: ClientId, order_id: StandingOrderId, change: StandingOrderChange)
+        -> Result<StandingOrder, AccountProcessError>;
+    async fn delete_client_standing_order(&self, client_id: ClientId, order_id: StandingOrderId)
+        -> Result<(), AccountProcessError>;
+    /// Transfer with amount above threshold (configured per currency) should be approved
+    /// by another user (see create_pending_transfer()).
+    async fn is_transfer_approval_required(&self, amount: &Amount) -> Result<bool, AccountProcessError>;
+    /// Stores transfer (it is not executed) which waits for approval by another user.
+    /// Retry with the same idempotency key returns the same pending transfer.
+    async fn create_pending_transfer(&self, client_id: ClientId, created_by: String, transfer: NewPendingTransfer,
+                                     idempotency: Option<Idempotency>)
+        -> Result<PendingTransfer, AccountProcessError>;
+    /// From newest to oldest.
+    async fn get_client_pending_transfers(&self, client_id: ClientId)
+        -> Result<Vec<PendingTransfer>, AccountProcessError>;
+    /// Executes pending transfer. Its creator cannot approve it.
+    /// If transfer fails (for example, due to not enough balance), it stays pending.
+    async fn approve_pending_transfer(&self, client_id: ClientId, pending_transfer_id: PendingTransferId, approved_by: String)
+        -> Result<PendingTransfer, AccountProcessError>;
+    /// Its creator cannot reject it either.
+    async fn reject_pending_transfer(&self, client_id: ClientId, pending_transfer_id: PendingTransferId, rejected_by: String,
+                                     reason: Option<String>)
+        -> Result<PendingTransfer, AccountProcessError>;
+    /// Not closed accounts with positive interest rate.
+    async fn get_interest_bearing_account_ids(&self) -> Result<Vec<AccountId>, AccountProcessError>;
+    /// Accrues interest of one day (by end of day balance) into ACCRUED_INTEREST of account.
+    /// On the last day of month accrued interest (rounded down to minor units of currency)
+    /// is credited to account by transfer from bank interest account of account currency
+    /// (it is booked at the end of accrual date).
+    /// Returns None if interest of this day is already accrued (so date range can be safely re-processed).
+    async fn accrue_account_interest(&self, account_id: AccountId, date: chrono::NaiveDate)
+        -> Result<Option<InterestAccrual>, AccountProcessError>;
+    /// Notifications about transfers of client (of subscribed types) are sent to 'url'
+    /// by WebhookDeliveryWorker (with HMAC-SHA256 signature by 'secret').
+    async fn create_webhook_subscription(&self, client_id: ClientId, subscription: NewWebhookSubscription)
+        -> Result<WebhookSubscription, AccountProcessError>;
+    async fn get_client_webhook_subscriptions(&self, client_id: ClientId)
+        -> Result<Vec<WebhookSubscription>, AccountProcessError>;
+    async fn get_client_webhook_subscription(&self, client_id: ClientId, subscription_id: WebhookSubscriptionId)
+        -> Result<WebhookSubscription, AccountProcessError>;
+    /// Already created deliveries are not changed (they are sent to new url with new secret).
+    async fn change_client_webhook_subscription(&self, client_id: ClientId, subscription_id: WebhookSubscriptionId,
+                                                change: WebhookSubscriptionChange)
+        -> Result<WebhookSubscription, AccountProcessError>;
+    /// Deliveries of subscription (and their history) are deleted too.
+    async fn delete_client_webhook_subscription(&self, client_id: ClientId, subscription_id: WebhookSubscriptionId)
+        -> Result<(), AccountProcessError>;
+    /// The last deliveries (newest first) with history of their attempts.
+    async fn get_client_webhook_deliveries(&self, client_id: ClientId, subscription_id: WebhookSubscriptionId)
+        -> Result<Vec<WebhookDelivery>, AccountProcessError>;
 }
 
-pub struct AccountServiceImpl {
+pub struct AccountServiceImpl <ExRateP: ExchangeRateProvider = SqlExchangeRateProvider> {
     pub database_connection: Arc<sqlx_postgres::PgPool>,
+    pub exchange_rate_provider: Arc<ExRateP>,
 }
 
 // ??? Hm... cannot use there AccountServiceSafe !?
-impl AccountService for AccountServiceImpl {
+impl<ExRateP: ExchangeRateProvider> AccountService for AccountServiceImpl<ExRateP> {
 
     async fn get_client_accounts(&self, client_id: ClientId) -> Result<Vec<Account>, AccountProcessError> {
 
@@ -70,7 +405,7 @@ impl AccountService for AccountServiceImpl {
         let res= sqlx::query_as(
             "select \
                  ID, IBAN, CLIENT_ID, NAME, \
-                 AMOUNT, CUR, \
+                 AMOUNT, CUR, OVERDRAFT_LIMIT, STATUS, ACCOUNT_HELD_AMOUNT(ID) as HELD_AMOUNT, \
                  CREATED_AT, UPDATED_AT \
                  from ACCOUNTS \
                  where CLIENT_ID = $1 order by CREATED_AT ")
@@ -111,180 +446,2455 @@ impl AccountService for AccountServiceImpl {
         res
     }
 
-    async fn transfer_by_iban(&self, client_id: ClientId, from_account_id: iban::Iban, to_account_id: iban::Iban, amount: Amount)
-        -> Result<(), AccountProcessError> {
+    async fn transfer(&self, client_id: ClientId, from_account_id: AccountIdWrapper, to_account_id: AccountIdWrapper,
+                      beneficiary_name: Option<String>, amount: Amount,
+                      expected_rate: Option<ExpectedRate>, idempotency: Option<Idempotency>)
+        -> Result<TransferId, AccountProcessError> {
+
+        info!("### transfer from ACCOUNT [{from_account_id:?}] to [{to_account_id:?}] of client [{client_id}] from database");
+
+        retry_on_tx_conflict(|| self.transfer_impl(
+            &client_id, &from_account_id, &to_account_id, beneficiary_name.as_deref(),
+            &amount, expected_rate.as_ref(), idempotency.as_ref(),
+        )).await
+    }
+
+    async fn quote_transfer(&self, client_id: ClientId, from_account_id: AccountIdWrapper, to_account_id: AccountIdWrapper,
+                            beneficiary_name: Option<String>, amount: Amount)
+        -> Result<TransferQuote, AccountProcessError> {
+
+        debug!("### Transfer quote from ACCOUNT [{from_account_id:?}] to [{to_account_id:?}] of client [{client_id}]");
+
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
+
+        // Accounts are not locked (nothing is changed), but they are validated as by transfer
+        // (quote should not be returned for transfer which would be rejected).
+        let (from_account, to_account) = self.validate_transfer_accounts_impl(
+            &mut tx, &client_id, &from_account_id, &to_account_id, beneficiary_name.as_deref(), &amount).await ?;
+
+        let fee = self.transfer_fee_impl(&mut tx, &from_account, &to_account, &amount).await ?
+            .unwrap_or_else(|| amount.with_value(BigDecimal::from(0i32)));
+        let total_debit_amount = (&amount + &fee) ?;
+        let (credit_amount, exchange_rate) = self.credit_amount_impl(
+            &from_account, &to_account, &amount, None).await ?;
+
+        Ok(TransferQuote { amount, fee, total_debit_amount, credit_amount, exchange_rate })
+    }
+
+    async fn transfer_batch(&self, client_id: ClientId, legs: Vec<TransferLeg>, idempotency: Option<Idempotency>)
+        -> Result<BatchTransferResult, AccountProcessError> {
+
+        info!("### Batch transfer ({} legs) of client [{client_id}]", legs.len());
+
+        if legs.is_empty() || legs.len() > MAX_BATCH_TRANSFER_LEGS {
+            return Err(AccountProcessError::IllegalArgument(
+                format!("Batch should have [1, {MAX_BATCH_TRANSFER_LEGS}] legs."), backtrace()));
+        }
+
+        retry_on_tx_conflict(|| self.transfer_batch_impl(&client_id, &legs, idempotency.as_ref())).await
+    }
+
+    async fn reverse_transfer(&self, client_id: ClientId, transfer_id: TransferId)
+        -> Result<TransferId, AccountProcessError> {
+
+        info!("### Reversing transfer [{transfer_id}] of client [{client_id}]");
+        retry_on_tx_conflict(|| self.reverse_transfer_impl(&client_id, &transfer_id)).await
+    }
+
+    async fn get_client_account_transactions(&self, client_id: ClientId, account_id: AccountIdWrapper, query: AccountTransactionsQuery)
+        -> Result<AccountTransactionsPage, AccountProcessError> {
+
+        info!("### Loading TRANSACTIONS of ACCOUNT [{account_id:?}] of client [{client_id}] from database");
+
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
 
-        info!("### transfer from ACCOUNT [{from_account_id}] to [{to_account_id}] of client [{client_id}] from database");
+        // To make sure that account belongs to this client.
+        let account = self.get_client_account_impl(&mut tx, &client_id, &account_id).await ?;
+
+        let limit = query.limit.clamp(1, MAX_TRANSACTIONS_PAGE_SIZE);
+
+        // One extra entry is loaded to find out whether there is next page.
+        let mut transactions: Vec<AccountTransaction> = sqlx::query_as(
+            "select \
+                 ID, TRANSFER_ID, ACCOUNT_ID, COUNTERPARTY_ACCOUNT_ID, \
+                 AMOUNT, CUR, BALANCE_AFTER, \
+                 EXCHANGE_RATE, EXCHANGE_RATE_AT, REVERSED_TRANSFER_ID, FEE_FOR_TRANSFER_ID, \
+                 CREATED_AT \
+                 from TRANSACTIONS \
+                 where ACCOUNT_ID = $1 \
+                   and ($2::timestamptz is null or CREATED_AT >= $2) \
+                   and ($3::timestamptz is null or CREATED_AT < $3) \
+                   and ($4::bigint is null or ID < $4) \
+                 order by ID desc \
+                 limit $5 ")
+            .bind(&account.id)
+            .bind(query.from)
+            .bind(query.to)
+            .bind(query.cursor)
+            .bind(i64::from(limit) + 1)
+            .fetch_all(&mut *tx)
+            .await ?;
+
+        let next_cursor = if transactions.len() > limit as usize {
+            transactions.truncate(limit as usize);
+            transactions.last().map(|tr| tr.id)
+        } else {
+            None
+        };
+
+        Ok(AccountTransactionsPage { transactions, next_cursor })
+    }
+
+    async fn get_client_account_statement(&self, client_id: ClientId, account_id: AccountIdWrapper,
+                                          from: chrono::DateTime<Utc>, to: chrono::DateTime<Utc>)
+        -> Result<AccountStatement, AccountProcessError> {
+
+        use futures::TryStreamExt;
+
+        info!("### Loading statement of ACCOUNT [{account_id:?}] of client [{client_id}] ({from} - {to})");
+
+        // Balances and entries should be loaded from the same snapshot
+        // (transfers committed meanwhile should not break 'opening + entries = closing').
+        let mut tx: Transaction<'static, Postgres> = self.database_connection.begin().await ?;
+        sqlx::query("set transaction isolation level repeatable read read only")
+            .execute(&mut *tx)
+            .await ?;
+
+        // To make sure that account belongs to this client.
+        let account = self.get_client_account_impl(&mut tx, &client_id, &account_id).await ?;
+
+        // Balances are calculated back from current one
+        // (there may be no entries before period, for example, for initial balance).
+        let (after_from, after_to): (BigDecimalWrapper, BigDecimalWrapper) = sqlx::query_as(
+            "select \
+                 coalesce(sum(AMOUNT), 0), \
+                 coalesce(sum(AMOUNT) filter (where CREATED_AT >= $3), 0) \
+                 from TRANSACTIONS \
+                 where ACCOUNT_ID = $1 and CREATED_AT >= $2 ")
+            .bind(&account.id)
+            .bind(&from)
+            .bind(&to)
+            .fetch_one(&mut *tx)
+            .await ?;
+
+        let opening_balance = account.amount.with_value(account.amount.value_ref() - after_from.0);
+        let closing_balance = account.amount.with_value(account.amount.value_ref() - after_to.0);
+
+        let entries_account_id = account.id.clone();
+        let entries = async_stream::try_stream! {
+            let mut tx = tx;
+            let mut rows = sqlx::query_as::<_, AccountTransaction>(
+                "select \
+                     ID, TRANSFER_ID, ACCOUNT_ID, COUNTERPARTY_ACCOUNT_ID, \
+                     AMOUNT, CUR, BALANCE_AFTER, \
+                     EXCHANGE_RATE, EXCHANGE_RATE_AT, REVERSED_TRANSFER_ID, FEE_FOR_TRANSFER_ID, \
+                     CREATED_AT \
+                     from TRANSACTIONS \
+                     where ACCOUNT_ID = $1 and CREATED_AT >= $2 and CREATED_AT < $3 \
+                     order by ID ")
+                .bind(entries_account_id)
+                .bind(from)
+                .bind(to)
+                .fetch(&mut *tx);
+
+            while let Some(entry) = rows.try_next().await ? {
+                yield entry;
+            }
+        };
+
+        Ok(AccountStatement {
+            account,
+            from,
+            to,
+            opening_balance,
+            closing_balance,
+            entries: Box::pin(entries),
+        })
+    }
+
+    async fn open_client_account(&self, client_id: ClientId, name: AccountName, currency: Currency)
+        -> Result<Account, AccountProcessError> {
+
+        info!("### Opening ACCOUNT [{name}] ({currency}) of client [{client_id}]");
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        let iban = generate_iban() ?;
 
         let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
 
-        let from_account = self.get_client_account_by_iban_impl(&mut tx, &client_id, &from_account_id).await ?;
-        let to_account = self.get_client_account_by_iban_impl(&mut tx, &client_id, &to_account_id).await ?;
+        let account: Account = sqlx::query_as(
+            " insert into ACCOUNTS \
+                 (ID, IBAN, CLIENT_ID, NAME, AMOUNT, CUR, STATUS, CREATED_AT, UPDATED_AT) \
+                 values ($1, $2, $3, $4, $5, $6, $7, $8, $8) \
+                 returning \
+                 ID, IBAN, CLIENT_ID, NAME, \
+                 AMOUNT, CUR, OVERDRAFT_LIMIT, STATUS, ACCOUNT_HELD_AMOUNT(ID) as HELD_AMOUNT, \
+                 CREATED_AT, UPDATED_AT ")
+            .bind(AccountId::generate())
+            .bind(&IbanRefWrapper(&iban))
+            .bind(&client_id)
+            .bind(&name)
+            .bind(BigDecimalWrapper(BigDecimal::from(0i32)))
+            .bind(&currency)
+            .bind(AccountStatus::Active)
+            .bind(&now)
+            .fetch_one(&mut *tx)
+            .await ?;
+
+        self.insert_event_impl(&mut tx, &AccountEvent::AccountOpened {
+            account_id: account.id.clone().into_inner(),
+            client_id: account.client_id.clone().into_inner(),
+            iban: account.iban.to_string(),
+            currency: account.amount.currency(),
+        }).await ?;
+
+        tx.commit().await ?;
+        Ok(account)
+    }
+
+    async fn change_client_account_status(&self, client_id: ClientId, account_id: AccountIdWrapper, status: AccountStatus)
+        -> Result<Account, AccountProcessError> {
+
+        info!("### Changing status of ACCOUNT [{account_id:?}] of client [{client_id}] to [{status}]");
+
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
 
-        let new_from_account_amount = (&from_account.amount - &amount) ?;
-        let new_to_account_amount = (&to_account.amount + &amount) ?;
+        let account = self.get_client_account_impl(&mut tx, &client_id, &account_id).await ?;
+        let account = self.lock_client_account_impl(&mut tx, &client_id, &account.id).await ?;
 
+        if !account.status.can_be_changed_to(status) {
+            return Err(AccountProcessError::AccountStatusChangeNotAllowed(
+                account_id, account.status, status, backtrace()));
+        }
         let zero = BigDecimal::from(0i32);
-        if new_from_account_amount.value.le(&zero) {
-            return Err(AccountProcessError::NotEnoughBalance(
-                AccountIdWrapper::Iban(from_account_id), backtrace()));
+        if status == AccountStatus::Closed
+            && (account.amount.value_ref() != &zero || account.held_amount.value_ref() != &zero) {
+            return Err(AccountProcessError::AccountIsNotEmpty(account_id, backtrace()));
         }
 
-        self.update_account_by_iban_impl(&mut tx, &client_id, &from_account_id, new_from_account_amount).await ?;
-        self.update_account_by_iban_impl(&mut tx, &client_id, &to_account_id, new_to_account_amount).await ?;
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        let old_status = account.status;
+
+        let account: Account = sqlx::query_as(
+            " update ACCOUNTS \
+                 set STATUS = $3, UPDATED_AT = $4 \
+                 where CLIENT_ID = $1 and ID = $2 \
+                 returning \
+                 ID, IBAN, CLIENT_ID, NAME, \
+                 AMOUNT, CUR, OVERDRAFT_LIMIT, STATUS, ACCOUNT_HELD_AMOUNT(ID) as HELD_AMOUNT, \
+                 CREATED_AT, UPDATED_AT ")
+            .bind(&client_id)
+            .bind(&account.id)
+            .bind(status)
+            .bind(&now)
+            .fetch_one(&mut *tx)
+            .await ?;
+
+        self.insert_event_impl(&mut tx, &AccountEvent::AccountStatusChanged {
+            account_id: account.id.clone().into_inner(),
+            client_id: account.client_id.clone().into_inner(),
+            old_status: old_status.as_str().to_owned(),
+            new_status: status.as_str().to_owned(),
+        }).await ?;
 
         tx.commit().await ?;
-        Ok(())
+        Ok(account)
     }
 
-    async fn transfer_by_id(&self, client_id: ClientId, from_account_id: AccountId, to_account_id: AccountId, amount: Amount)
-        -> Result<(), AccountProcessError> {
+    async fn change_client_account_overdraft_limit(&self, client_id: ClientId, account_id: AccountIdWrapper, overdraft_limit: Amount)
+        -> Result<Account, AccountProcessError> {
 
-        info!("### transfer from ACCOUNT [{from_account_id}] to [{to_account_id}] of client [{client_id}] from database");
+        info!("### Changing overdraft limit of ACCOUNT [{account_id:?}] of client [{client_id}] to [{overdraft_limit}]");
 
         let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
 
-        let from_account = self.get_client_account_by_id_impl(&mut tx, &client_id, &from_account_id).await ?;
-        let to_account = self.get_client_account_by_id_impl(&mut tx, &client_id, &to_account_id).await ?;
+        let account = self.get_client_account_impl(&mut tx, &client_id, &account_id).await ?;
+        let account = self.lock_client_account_impl(&mut tx, &client_id, &account.id).await ?;
 
-        let new_from_account_amount = (&from_account.amount - &amount) ?;
-        let new_to_account_amount = (&to_account.amount + &amount) ?;
+        // It also validates currency.
+        let available_funds = (&account.amount + &overdraft_limit) ?;
+        if available_funds.value_ref() < &BigDecimal::from(0i32) {
+            return Err(AccountProcessError::NotEnoughBalance(account_id, available_funds, backtrace()));
+        }
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+
+        let account: Account = sqlx::query_as(
+            " update ACCOUNTS \
+                 set OVERDRAFT_LIMIT = $3, UPDATED_AT = $4 \
+                 where CLIENT_ID = $1 and ID = $2 \
+                 returning \
+                 ID, IBAN, CLIENT_ID, NAME, \
+                 AMOUNT, CUR, OVERDRAFT_LIMIT, STATUS, ACCOUNT_HELD_AMOUNT(ID) as HELD_AMOUNT, \
+                 CREATED_AT, UPDATED_AT ")
+            .bind(&client_id)
+            .bind(&account.id)
+            .bind(&BigDecimalRefWrapper(overdraft_limit.value_ref()))
+            .bind(&now)
+            .fetch_one(&mut *tx)
+            .await ?;
+
+        self.insert_event_impl(&mut tx, &AccountEvent::OverdraftLimitChanged {
+            account_id: account.id.clone().into_inner(),
+            client_id: account.client_id.clone().into_inner(),
+            overdraft_limit: EventAmount::from(&overdraft_limit),
+        }).await ?;
 
-        if new_from_account_amount.value.le(&BigDecimal::from(0i32)) {
-            return Err(AccountProcessError::NotEnoughBalance(
-                AccountIdWrapper::Id(from_account_id), backtrace()));
+        tx.commit().await ?;
+        Ok(account)
+    }
+
+    async fn authorize_hold(&self, client_id: ClientId, account_id: AccountIdWrapper, amount: Amount, ttl: chrono::Duration)
+        -> Result<Hold, AccountProcessError> {
+
+        info!("### Authorizing HOLD [{amount}] of ACCOUNT [{account_id:?}] of client [{client_id}]");
+
+        if ttl <= chrono::Duration::zero() || ttl > MAX_HOLD_TTL {
+            return Err(AccountProcessError::IllegalArgument(format!("Hold TTL [{ttl}] is out of range."), backtrace()));
         }
 
-        self.update_account_by_id_impl(&mut tx, &client_id, &from_account_id, new_from_account_amount).await ?;
-        self.update_account_by_id_impl(&mut tx, &client_id, &to_account_id, new_to_account_amount).await ?;
+        retry_on_tx_conflict(|| self.authorize_hold_impl(&client_id, &account_id, &amount, ttl)).await
+    }
+
+    async fn capture_hold(&self, client_id: ClientId, hold_id: HoldId, to_account_id: AccountIdWrapper,
+                          beneficiary_name: Option<String>, amount: Option<Amount>)
+        -> Result<TransferId, AccountProcessError> {
+
+        info!("### Capturing HOLD [{hold_id}] of client [{client_id}] to ACCOUNT [{to_account_id:?}]");
+
+        retry_on_tx_conflict(|| self.capture_hold_impl(
+            &client_id, &hold_id, &to_account_id, beneficiary_name.as_deref(), amount.as_ref(),
+        )).await
+    }
+
+    async fn release_hold(&self, client_id: ClientId, hold_id: HoldId)
+        -> Result<Hold, AccountProcessError> {
+
+        info!("### Releasing HOLD [{hold_id}] of client [{client_id}]");
+
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
+
+        let hold = self.lock_client_hold_impl(&mut tx, &client_id, &hold_id).await ?;
+        validate_hold_is_active(&hold) ?;
+
+        let hold = self.update_hold_status_impl(&mut tx, &hold_id, HoldStatus::Released, None, None).await ?;
 
         tx.commit().await ?;
-        Ok(())
+        Ok(hold)
     }
-}
 
+    async fn get_client_account_holds(&self, client_id: ClientId, account_id: AccountIdWrapper)
+        -> Result<Vec<Hold>, AccountProcessError> {
 
-impl AccountServiceImpl {
+        info!("### Loading HOLDS of ACCOUNT [{account_id:?}] of client [{client_id}] from database");
 
-    async fn get_client_account_by_id_impl(
-        &self, tx: &mut Transaction<'_, Postgres>,
-        client_id: &ClientId, account_id: &AccountId,
-    ) -> Result<Account, AccountProcessError> {
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
 
-        debug!("### Loading user ACCOUNT [{account_id}] of client [{client_id}] from database");
+        // To make sure that account belongs to this client.
+        let account = self.get_client_account_impl(&mut tx, &client_id, &account_id).await ?;
 
-        let res = sqlx::query_as(
+        let holds = sqlx::query_as(
             "select \
-                 ID, IBAN, CLIENT_ID, NAME, \
-                 AMOUNT, CUR, \
-                 CREATED_AT, UPDATED_AT \
-                 from ACCOUNTS \
-                 where CLIENT_ID = $1 and ID = $2 ")
-            .bind(client_id.inner_ref())
-            .bind(account_id.inner_ref())
-            .fetch_one(&mut **tx)
-            .await
-            // .map_err(Self::Error::Sqlx)?)
-            .map_err(From::<sqlx::Error>::from);
-        res
+                 ID, ACCOUNT_ID, AMOUNT, CUR, \
+                 case when STATUS = 'ACTIVE' and EXPIRES_AT <= CURRENT_TIMESTAMP then 'EXPIRED' else STATUS end as STATUS, \
+                 CAPTURED_AMOUNT, TRANSFER_ID, \
+                 EXPIRES_AT, CREATED_AT, UPDATED_AT \
+                 from HOLDS \
+                 where ACCOUNT_ID = $1 \
+                 order by CREATED_AT desc ")
+            .bind(&account.id)
+            .fetch_all(&mut *tx)
+            .await ?;
+
+        Ok(holds)
     }
 
-    async fn get_client_account_by_iban_impl(
-        &self, tx: &mut Transaction<'_, Postgres>,
-        client_id: &ClientId, iban: &iban::Iban,
-    ) -> Result<Account, AccountProcessError> {
+    async fn create_standing_order(&self, client_id: ClientId, order: NewStandingOrder)
+        -> Result<StandingOrder, AccountProcessError> {
 
-        debug!("### Loading user ACCOUNT [{iban}] of client [{client_id}] from database");
+        info!("### Creating STANDING ORDER from ACCOUNT [{:?}] of client [{client_id}]", order.from_account);
 
-        let res = sqlx::query_as(
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
+
+        // Order is validated as transfer (otherwise every execution fails).
+        let (from_account, to_account) = self.validate_transfer_accounts_impl(
+            &mut tx, &client_id, &order.from_account, &order.to_account,
+            order.beneficiary_name.as_deref(), &order.amount).await ?;
+        // Orders are executed without approval.
+        self.validate_approval_not_required_impl(&mut tx, &order.amount).await ?;
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        let NewStandingOrder { beneficiary_name, amount, period, start_at, .. } = order;
+        let AmountParts { value: amount, currency } = amount.into_parts();
+
+        let standing_order = sqlx::query_as(
+            " insert into STANDING_ORDERS \
+                 (ID, CLIENT_ID, FROM_ACCOUNT_ID, TO_IBAN, BENEFICIARY_NAME, AMOUNT, CUR, \
+                  PERIOD, START_AT, NEXT_EXECUTION_NO, NEXT_EXECUTION_AT, CREATED_AT, UPDATED_AT) \
+                 values ($1, $2, $3, $4, $5, $6, $7, $8, $9, 0, $9, $10, $10) \
+                 returning \
+                 ID, CLIENT_ID, FROM_ACCOUNT_ID, TO_IBAN, BENEFICIARY_NAME, AMOUNT, CUR, \
+                 PERIOD, START_AT, NEXT_EXECUTION_NO, NEXT_EXECUTION_AT, FAILED_ATTEMPTS, RETRY_AT, \
+                 LAST_EXECUTED_AT, LAST_TRANSFER_ID, LAST_ERROR, CREATED_AT, UPDATED_AT ")
+            .bind(StandingOrderId::generate())
+            .bind(&client_id)
+            .bind(&from_account.id)
+            .bind(&IbanRefWrapper(&to_account.iban))
+            .bind(beneficiary_name)
+            .bind(BigDecimalWrapper(amount))
+            .bind(&currency)
+            .bind(period)
+            .bind(&start_at)
+            .bind(&now)
+            .fetch_one(&mut *tx)
+            .await ?;
+
+        tx.commit().await ?;
+        Ok(standing_order)
+    }
+
+    async fn get_client_standing_orders(&self, client_id: ClientId)
+        -> Result<Vec<StandingOrder>, AccountProcessError> {
+
+        info!("### Loading STANDING ORDERS of client [{client_id}] from database");
+
+        let standing_orders = sqlx::query_as(
             "select \
-                 ID, IBAN, CLIENT_ID, NAME, \
-                 AMOUNT, CUR, \
-                 CREATED_AT, UPDATED_AT \
-                 from ACCOUNTS \
-                 where CLIENT_ID = $1 and IBAN = $2 ")
-            .bind(client_id)
-            .bind(&IbanRefWrapper(iban))
-            .fetch_one(&mut **tx) // &*self.database_connection)
-            .await
-            // .map_err(Self::Error::Sqlx)?)
-            .map_err(From::<sqlx::Error>::from);
-        res
+                 ID, CLIENT_ID, FROM_ACCOUNT_ID, TO_IBAN, BENEFICIARY_NAME, AMOUNT, CUR, \
+                 PERIOD, START_AT, NEXT_EXECUTION_NO, NEXT_EXECUTION_AT, FAILED_ATTEMPTS, RETRY_AT, \
+                 LAST_EXECUTED_AT, LAST_TRANSFER_ID, LAST_ERROR, CREATED_AT, UPDATED_AT \
+                 from STANDING_ORDERS \
+                 where CLIENT_ID = $1 \
+                 order by CREATED_AT ")
+            .bind(&client_id)
+            .fetch_all(&*self.database_connection)
+            .await ?;
+
+        Ok(standing_orders)
     }
 
-    async fn update_account_by_iban_impl(
-        &self, tx: &mut Transaction<'_, Postgres>,
-        client_id: &ClientId, iban: &iban::Iban, amount: Amount,
-    ) -> Result<(), AccountProcessError> {
+    async fn get_client_standing_order(&self, client_id: ClientId, order_id: StandingOrderId)
+        -> Result<StandingOrder, AccountProcessError> {
 
-        debug!("### Loading user ACCOUNT [{iban}] of client [{client_id}] from database");
+        info!("### Loading STANDING ORDER [{order_id}] of client [{client_id}] from database");
+
+        let standing_order: Option<StandingOrder> = sqlx::query_as(
+            "select \
+                 ID, CLIENT_ID, FROM_ACCOUNT_ID, TO_IBAN, BENEFICIARY_NAME, AMOUNT, CUR, \
+                 PERIOD, START_AT, NEXT_EXECUTION_NO, NEXT_EXECUTION_AT, FAILED_ATTEMPTS, RETRY_AT, \
+                 LAST_EXECUTED_AT, LAST_TRANSFER_ID, LAST_ERROR, CREATED_AT, UPDATED_AT \
+                 from STANDING_ORDERS \
+                 where CLIENT_ID = $1 and ID = $2 ")
+            .bind(&client_id)
+            .bind(&order_id)
+            .fetch_optional(&*self.database_connection)
+            .await ?;
+
+        standing_order.ok_or_else(|| AccountProcessError::StandingOrderNotFound(order_id, backtrace()))
+    }
+
+    async fn change_client_standing_order(&self, client_id: ClientId, order_id: StandingOrderId, change: StandingOrderChange)
+        -> Result<StandingOrder, AccountProcessError> {
+
+        info!("### Changing STANDING ORDER [{order_id}] of client [{client_id}]");
+
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
+
+        let standing_order: Option<StandingOrder> = sqlx::query_as(
+            "select \
+                 ID, CLIENT_ID, FROM_ACCOUNT_ID, TO_IBAN, BENEFICIARY_NAME, AMOUNT, CUR, \
+                 PERIOD, START_AT, NEXT_EXECUTION_NO, NEXT_EXECUTION_AT, FAILED_ATTEMPTS, RETRY_AT, \
+                 LAST_EXECUTED_AT, LAST_TRANSFER_ID, LAST_ERROR, CREATED_AT, UPDATED_AT \
+                 from STANDING_ORDERS \
+                 where CLIENT_ID = $1 and ID = $2 \
+                 for update ")
+            .bind(&client_id)
+            .bind(&order_id)
+            .fetch_optional(&mut *tx)
+            .await ?;
+        let standing_order = standing_order.ok_or_else(|| AccountProcessError::StandingOrderNotFound(order_id.clone(), backtrace())) ?;
+
+        // Changed order is validated again (new amount should be in 'from' account currency, new beneficiary should match).
+        self.validate_transfer_accounts_impl(
+            &mut tx, &client_id,
+            &AccountIdWrapper::Id(standing_order.from_account_id.clone()),
+            &AccountIdWrapper::Iban(standing_order.to_iban.clone()),
+            change.beneficiary_name.as_deref(), &change.amount).await ?;
+        // Orders are executed without approval.
+        self.validate_approval_not_required_impl(&mut tx, &change.amount).await ?;
 
-        // let now: chrono::DateTime::<Utc> = chrono::DateTime::<Utc>::default();
         let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        let StandingOrderChange { beneficiary_name, amount, period, start_at } = change;
         let AmountParts { value: amount, currency } = amount.into_parts();
 
-        let update_res = sqlx::query(
-            " update ACCOUNTS \
-                 set AMOUNT = $4, UPDATED_AT = $5 \
-                 where CLIENT_ID = $1 and IBAN = $2 and CUR = $3 ")
-            .bind(client_id)
-            .bind(&IbanRefWrapper(iban))
+        let standing_order: Option<StandingOrder> = sqlx::query_as(
+            " update STANDING_ORDERS \
+                 set BENEFICIARY_NAME = $3, AMOUNT = $4, CUR = $5, PERIOD = $6, START_AT = $7, \
+                     NEXT_EXECUTION_NO = 0, NEXT_EXECUTION_AT = $7, FAILED_ATTEMPTS = 0, RETRY_AT = null, \
+                     UPDATED_AT = $8 \
+                 where CLIENT_ID = $1 and ID = $2 \
+                 returning \
+                 ID, CLIENT_ID, FROM_ACCOUNT_ID, TO_IBAN, BENEFICIARY_NAME, AMOUNT, CUR, \
+                 PERIOD, START_AT, NEXT_EXECUTION_NO, NEXT_EXECUTION_AT, FAILED_ATTEMPTS, RETRY_AT, \
+                 LAST_EXECUTED_AT, LAST_TRANSFER_ID, LAST_ERROR, CREATED_AT, UPDATED_AT ")
+            .bind(&client_id)
+            .bind(&order_id)
+            .bind(beneficiary_name)
+            .bind(BigDecimalWrapper(amount))
             .bind(&currency)
-            .bind(BigDecimalWrapper(amount)) // or &BigDecimalRefWrapper(&amount.value)
+            .bind(period)
+            .bind(&start_at)
             .bind(&now)
-            .execute(&mut **tx) // &*self.database_connection)
-            .await
-            .map_err(|err|AccountProcessError::Sqlx(err, backtrace())) ?;
+            .fetch_optional(&mut *tx)
+            .await ?;
 
-        let updated_count = update_res.rows_affected();
-        if updated_count == 1 {
-            Ok(())
-        } else {
-            Err(AccountProcessError::Internal(
-                anyhow::anyhow!("Error of updating account [{iban}] (updated_count: {updated_count}).")))
+        let standing_order = standing_order.ok_or_else(|| AccountProcessError::StandingOrderNotFound(order_id, backtrace())) ?;
+        tx.commit().await ?;
+        Ok(standing_order)
+    }
+
+    async fn delete_client_standing_order(&self, client_id: ClientId, order_id: StandingOrderId)
+        -> Result<(), AccountProcessError> {
+
+        info!("### Deleting STANDING ORDER [{order_id}] of client [{client_id}]");
+
+        let delete_res = sqlx::query(
+            " delete from STANDING_ORDERS where CLIENT_ID = $1 and ID = $2 ")
+            .bind(&client_id)
+            .bind(&order_id)
+            .execute(&*self.database_connection)
+            .await ?;
+
+        if delete_res.rows_affected() == 0 {
+            return Err(AccountProcessError::StandingOrderNotFound(order_id, backtrace()));
         }
+        Ok(())
     }
 
-    async fn update_account_by_id_impl(
-        &self, tx: &mut Transaction<'_, Postgres>,
-        client_id: &ClientId, id: &AccountId, amount: Amount,
-    ) -> Result<(), AccountProcessError> {
+    async fn is_transfer_approval_required(&self, amount: &Amount) -> Result<bool, AccountProcessError> {
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
+        self.is_transfer_approval_required_impl(&mut tx, amount).await
+    }
 
-        debug!("### Loading user ACCOUNT [{id}] of client [{client_id}] from database");
+    async fn create_pending_transfer(&self, client_id: ClientId, created_by: String, transfer: NewPendingTransfer,
+                                     idempotency: Option<Idempotency>)
+        -> Result<PendingTransfer, AccountProcessError> {
+
+        info!("### Creating PENDING TRANSFER from ACCOUNT [{:?}] of client [{client_id}] by [{created_by}]",
+            transfer.from_account);
+
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
+
+        // Transfer is validated as much as possible to avoid approval of obviously invalid transfer.
+        let from_account = self.get_client_account_impl(&mut tx, &client_id, &transfer.from_account).await ?;
+        let to_account = match transfer.to_account {
+            AccountIdWrapper::Id(_) =>
+                self.get_client_account_impl(&mut tx, &client_id, &transfer.to_account).await ?,
+            AccountIdWrapper::Iban(ref iban) =>
+                self.get_account_by_iban_impl(&mut tx, iban).await ?,
+        };
+        if from_account.id == to_account.id {
+            return Err(AccountProcessError::SameAccountTransfer(transfer.from_account, backtrace()));
+        }
+        validate_account_is_active(&from_account) ?;
+        // It also validates currency.
+        let _ = (&from_account.amount - &transfer.amount) ?;
+        if to_account.client_id != client_id || transfer.beneficiary_name.is_some() {
+            self.validate_beneficiary_impl(&mut tx, &to_account, &transfer.to_account,
+                transfer.beneficiary_name.as_deref()).await ?;
+        }
 
         let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        let NewPendingTransfer { beneficiary_name, amount, .. } = transfer;
         let AmountParts { value: amount, currency } = amount.into_parts();
 
-        let update_res = sqlx::query(
-            " update ACCOUNTS \
-                 set AMOUNT = $4, UPDATED_AT = $5 \
-                 where CLIENT_ID = $1 and ID = $2 and CUR = $3 ")
-            .bind(client_id)
-            .bind(&id)
+        let pending_transfer: Option<PendingTransfer> = sqlx::query_as(
+            " insert into PENDING_TRANSFERS \
+                 (ID, CLIENT_ID, FROM_ACCOUNT_ID, TO_IBAN, BENEFICIARY_NAME, AMOUNT, CUR, STATUS, \
+                  CREATED_BY, IDEMPOTENCY_KEY, REQUEST_HASH, CREATED_AT, UPDATED_AT) \
+                 values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $12) \
+                 on conflict do nothing \
+                 returning \
+                 ID, CLIENT_ID, FROM_ACCOUNT_ID, TO_IBAN, BENEFICIARY_NAME, AMOUNT, CUR, STATUS, \
+                 CREATED_BY, DECIDED_BY, DECIDED_AT, TRANSFER_ID, REJECT_REASON, CREATED_AT, UPDATED_AT ")
+            .bind(PendingTransferId::generate())
+            .bind(&client_id)
+            .bind(&from_account.id)
+            .bind(&IbanRefWrapper(&to_account.iban))
+            .bind(beneficiary_name)
+            .bind(BigDecimalWrapper(amount))
             .bind(&currency)
-            .bind(BigDecimalWrapper(amount)) // or .bind(&BigDecimalRefWrapper(&amount.value))
+            .bind(PendingTransferStatus::Pending)
+            .bind(&created_by)
+            .bind(idempotency.as_ref().map(|idempotency| idempotency.key.as_str()))
+            .bind(idempotency.as_ref().map(|idempotency| idempotency.request_hash.as_str()))
             .bind(&now)
-            .execute(&mut **tx) // &*self.database_connection)
-            .await
-            .map_err(|err|AccountProcessError::Sqlx(err, backtrace())) ?;
+            .fetch_optional(&mut *tx)
+            .await ?;
 
-        let updated_count = update_res.rows_affected();
-        if updated_count == 1 {
-            Ok(())
-        } else {
-            Err(AccountProcessError::Internal(
-                anyhow::anyhow!("Error of updating account [{id}] (updated_count: {updated_count}).")))
-        }
+        let pending_transfer = match (pending_transfer, idempotency) {
+            (Some(pending_transfer), _) => pending_transfer,
+            (None, None) =>
+                return Err(AccountProcessError::Internal(anyhow::anyhow!("Error of inserting pending transfer."))),
+            (None, Some(idempotency)) => {
+                // The same key is already used.
+                let (request_hash, prev_pending_transfer_id): (String, PendingTransferId) = sqlx::query_as(
+                    "select REQUEST_HASH, ID \
+                         from PENDING_TRANSFERS \
+                         where CLIENT_ID = $1 and IDEMPOTENCY_KEY = $2 ")
+                    .bind(&client_id)
+                    .bind(idempotency.key.as_str())
+                    .fetch_one(&mut *tx)
+                    .await ?;
+                if request_hash.trim() != idempotency.request_hash {
+                    return Err(AccountProcessError::IdempotencyKeyConflict(idempotency.key, backtrace()));
+                }
+                info!("### Pending transfer with idempotency key [{}] already exists [{prev_pending_transfer_id}]",
+                    idempotency.key);
+                self.get_client_pending_transfer_impl(&mut tx, &client_id, &prev_pending_transfer_id, false).await ?
+            }
+        };
+
+        tx.commit().await ?;
+        Ok(pending_transfer)
     }
 
-}
+    async fn get_client_pending_transfers(&self, client_id: ClientId)
+        -> Result<Vec<PendingTransfer>, AccountProcessError> {
 
+        info!("### Loading PENDING TRANSFERS of client [{client_id}] from database");
 
-impl sqlx::FromRow<'_, sqlx_postgres::PgRow> for Account {
+        let pending_transfers = sqlx::query_as(
+            "select \
+                 ID, CLIENT_ID, FROM_ACCOUNT_ID, TO_IBAN, BENEFICIARY_NAME, AMOUNT, CUR, STATUS, \
+                 CREATED_BY, DECIDED_BY, DECIDED_AT, TRANSFER_ID, REJECT_REASON, CREATED_AT, UPDATED_AT \
+                 from PENDING_TRANSFERS \
+                 where CLIENT_ID = $1 \
+                 order by CREATED_AT desc ")
+            .bind(&client_id)
+            .fetch_all(&*self.database_connection)
+            .await ?;
+        Ok(pending_transfers)
+    }
+
+    async fn approve_pending_transfer(&self, client_id: ClientId, pending_transfer_id: PendingTransferId, approved_by: String)
+        -> Result<PendingTransfer, AccountProcessError> {
+
+        info!("### Approving PENDING TRANSFER [{pending_transfer_id}] of client [{client_id}] by [{approved_by}]");
+        retry_on_tx_conflict(|| self.approve_pending_transfer_impl(&client_id, &pending_transfer_id, &approved_by)).await
+    }
+
+    async fn reject_pending_transfer(&self, client_id: ClientId, pending_transfer_id: PendingTransferId, rejected_by: String,
+                                     reason: Option<String>)
+        -> Result<PendingTransfer, AccountProcessError> {
+
+        info!("### Rejecting PENDING TRANSFER [{pending_transfer_id}] of client [{client_id}] by [{rejected_by}]");
+
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
+
+        let pending_transfer = self.get_client_pending_transfer_impl(&mut tx, &client_id, &pending_transfer_id, true).await ?;
+        validate_pending_transfer_can_be_decided(&pending_transfer, &rejected_by) ?;
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        let pending_transfer = sqlx::query_as(
+            " update PENDING_TRANSFERS \
+                 set STATUS = $3, DECIDED_BY = $4, DECIDED_AT = $5, REJECT_REASON = $6, UPDATED_AT = $5 \
+                 where CLIENT_ID = $1 and ID = $2 \
+                 returning \
+                 ID, CLIENT_ID, FROM_ACCOUNT_ID, TO_IBAN, BENEFICIARY_NAME, AMOUNT, CUR, STATUS, \
+                 CREATED_BY, DECIDED_BY, DECIDED_AT, TRANSFER_ID, REJECT_REASON, CREATED_AT, UPDATED_AT ")
+            .bind(&client_id)
+            .bind(&pending_transfer_id)
+            .bind(PendingTransferStatus::Rejected)
+            .bind(&rejected_by)
+            .bind(&now)
+            .bind(reason)
+            .fetch_one(&mut *tx)
+            .await ?;
+
+        tx.commit().await ?;
+        Ok(pending_transfer)
+    }
+
+    async fn get_interest_bearing_account_ids(&self) -> Result<Vec<AccountId>, AccountProcessError> {
+        let account_ids = sqlx::query_scalar(
+            "select ID from ACCOUNTS \
+                 where INTEREST_RATE > 0 and STATUS <> $1 \
+                 order by ID ")
+            .bind(AccountStatus::Closed)
+            .fetch_all(&*self.database_connection)
+            .await ?;
+        Ok(account_ids)
+    }
+
+    async fn accrue_account_interest(&self, account_id: AccountId, date: chrono::NaiveDate)
+        -> Result<Option<InterestAccrual>, AccountProcessError> {
+        debug!("### Accruing interest of account [{account_id}] for [{date}]");
+        retry_on_tx_conflict(|| self.accrue_account_interest_impl(&account_id, date)).await
+    }
+
+    async fn create_webhook_subscription(&self, client_id: ClientId, subscription: NewWebhookSubscription)
+        -> Result<WebhookSubscription, AccountProcessError> {
+
+        info!("### Creating WEBHOOK SUBSCRIPTION of client [{client_id}] ({})", subscription.url);
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        let NewWebhookSubscription { url, secret, event_types } = subscription;
+
+        let subscription = sqlx::query_as(
+            " insert into WEBHOOK_SUBSCRIPTIONS \
+                 (ID, CLIENT_ID, URL, SECRET, EVENT_TYPES, ACTIVE, CREATED_AT, UPDATED_AT) \
+                 values ($1, $2, $3, $4, $5::WEBHOOK_EVENT_TYPE[], true, $6, $6) \
+                 returning \
+                 ID, CLIENT_ID, URL, SECRET, EVENT_TYPES::VARCHAR[] as EVENT_TYPES, ACTIVE, CREATED_AT, UPDATED_AT ")
+            .bind(WebhookSubscriptionId::generate())
+            .bind(&client_id)
+            .bind(url)
+            .bind(secret.as_str())
+            .bind(webhook_event_types_to_db(&event_types))
+            .bind(&now)
+            .fetch_one(&*self.database_connection)
+            .await ?;
+
+        Ok(subscription)
+    }
+
+    async fn get_client_webhook_subscriptions(&self, client_id: ClientId)
+        -> Result<Vec<WebhookSubscription>, AccountProcessError> {
+
+        info!("### Loading WEBHOOK SUBSCRIPTIONS of client [{client_id}] from database");
+
+        let subscriptions = sqlx::query_as(
+            "select \
+                 ID, CLIENT_ID, URL, SECRET, EVENT_TYPES::VARCHAR[] as EVENT_TYPES, ACTIVE, CREATED_AT, UPDATED_AT \
+                 from WEBHOOK_SUBSCRIPTIONS \
+                 where CLIENT_ID = $1 \
+                 order by CREATED_AT ")
+            .bind(&client_id)
+            .fetch_all(&*self.database_connection)
+            .await ?;
+
+        Ok(subscriptions)
+    }
+
+    async fn get_client_webhook_subscription(&self, client_id: ClientId, subscription_id: WebhookSubscriptionId)
+        -> Result<WebhookSubscription, AccountProcessError> {
+
+        info!("### Loading WEBHOOK SUBSCRIPTION [{subscription_id}] of client [{client_id}] from database");
+
+        let subscription: Option<WebhookSubscription> = sqlx::query_as(
+            "select \
+                 ID, CLIENT_ID, URL, SECRET, EVENT_TYPES::VARCHAR[] as EVENT_TYPES, ACTIVE, CREATED_AT, UPDATED_AT \
+                 from WEBHOOK_SUBSCRIPTIONS \
+                 where CLIENT_ID = $1 and ID = $2 ")
+            .bind(&client_id)
+            .bind(&subscription_id)
+            .fetch_optional(&*self.database_connection)
+            .await ?;
+
+        subscription.ok_or_else(|| AccountProcessError::WebhookSubscriptionNotFound(subscription_id, backtrace()))
+    }
+
+    async fn change_client_webhook_subscription(&self, client_id: ClientId, subscription_id: WebhookSubscriptionId,
+                                                change: WebhookSubscriptionChange)
+        -> Result<WebhookSubscription, AccountProcessError> {
+
+        info!("### Changing WEBHOOK SUBSCRIPTION [{subscription_id}] of client [{client_id}]");
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        let WebhookSubscriptionChange { url, secret, event_types, active } = change;
+
+        let subscription: Option<WebhookSubscription> = sqlx::query_as(
+            " update WEBHOOK_SUBSCRIPTIONS \
+                 set URL = $3, SECRET = coalesce($4, SECRET), EVENT_TYPES = $5::WEBHOOK_EVENT_TYPE[], \
+                     ACTIVE = $6, UPDATED_AT = $7 \
+                 where CLIENT_ID = $1 and ID = $2 \
+                 returning \
+                 ID, CLIENT_ID, URL, SECRET, EVENT_TYPES::VARCHAR[] as EVENT_TYPES, ACTIVE, CREATED_AT, UPDATED_AT ")
+            .bind(&client_id)
+            .bind(&subscription_id)
+            .bind(url)
+            .bind(secret.as_ref().map(|secret| secret.as_str()))
+            .bind(webhook_event_types_to_db(&event_types))
+            .bind(active)
+            .bind(&now)
+            .fetch_optional(&*self.database_connection)
+            .await ?;
+
+        subscription.ok_or_else(|| AccountProcessError::WebhookSubscriptionNotFound(subscription_id, backtrace()))
+    }
+
+    async fn delete_client_webhook_subscription(&self, client_id: ClientId, subscription_id: WebhookSubscriptionId)
+        -> Result<(), AccountProcessError> {
+
+        info!("### Deleting WEBHOOK SUBSCRIPTION [{subscription_id}] of client [{client_id}]");
+
+        let delete_res = sqlx::query(
+            " delete from WEBHOOK_SUBSCRIPTIONS where CLIENT_ID = $1 and ID = $2 ")
+            .bind(&client_id)
+            .bind(&subscription_id)
+            .execute(&*self.database_connection)
+            .await ?;
+
+        if delete_res.rows_affected() == 0 {
+            return Err(AccountProcessError::WebhookSubscriptionNotFound(subscription_id, backtrace()));
+        }
+        Ok(())
+    }
+
+    async fn get_client_webhook_deliveries(&self, client_id: ClientId, subscription_id: WebhookSubscriptionId)
+        -> Result<Vec<WebhookDelivery>, AccountProcessError> {
+
+        info!("### Loading WEBHOOK DELIVERIES of subscription [{subscription_id}] of client [{client_id}] from database");
+
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
+
+        let subscription_exists: bool = sqlx::query_scalar(
+            "select exists(select 1 from WEBHOOK_SUBSCRIPTIONS where CLIENT_ID = $1 and ID = $2) ")
+            .bind(&client_id)
+            .bind(&subscription_id)
+            .fetch_one(&mut *tx)
+            .await ?;
+        if !subscription_exists {
+            return Err(AccountProcessError::WebhookSubscriptionNotFound(subscription_id, backtrace()));
+        }
+
+        let deliveries: Vec<WebhookDelivery> = sqlx::query_as(
+            "select \
+                 ID, SUBSCRIPTION_ID, EVENT_ID, EVENT_TYPE, STATUS, ATTEMPTS, \
+                 case when STATUS = 'PENDING' then NEXT_ATTEMPT_AT end as NEXT_ATTEMPT_AT, \
+                 LAST_ERROR, DELIVERED_AT, CREATED_AT \
+                 from WEBHOOK_DELIVERIES \
+                 where SUBSCRIPTION_ID = $1 \
+                 order by CREATED_AT desc \
+                 limit $2 ")
+            .bind(&subscription_id)
+            .bind(MAX_WEBHOOK_DELIVERIES_PAGE_SIZE)
+            .fetch_all(&mut *tx)
+            .await ?;
+
+        let delivery_ids: Vec<uuid::Uuid> = deliveries.iter().map(|delivery| delivery.id).collect();
+        let attempts: Vec<(uuid::Uuid, i32, chrono::DateTime<Utc>, Option<i32>, Option<String>, i32)> = sqlx::query_as(
+            "select DELIVERY_ID, ATTEMPT_NO, ATTEMPTED_AT, RESPONSE_STATUS, ERROR, DURATION_MS \
+                 from WEBHOOK_DELIVERY_ATTEMPTS \
+                 where DELIVERY_ID = any($1) \
+                 order by DELIVERY_ID, ATTEMPT_NO ")
+            .bind(&delivery_ids)
+            .fetch_all(&mut *tx)
+            .await ?;
+
+        let deliveries = deliveries.into_iter()
+            .map(|delivery| {
+                let mut delivery = delivery.into_parts();
+                delivery.attempt_history = attempts.iter()
+                    .filter(|(delivery_id, ..)| *delivery_id == delivery.id)
+                    .map(|(_, attempt_no, attempted_at, response_status, error, duration_ms)| WebhookDeliveryAttempt {
+                        attempt_no: u32::try_from(*attempt_no).unwrap_or_default(),
+                        attempted_at: *attempted_at,
+                        response_status: response_status.and_then(|status| u16::try_from(status).ok()),
+                        error: error.clone(),
+                        duration_ms: u32::try_from(*duration_ms).unwrap_or_default(),
+                    })
+                    .collect();
+                WebhookDelivery::new(delivery)
+            })
+            .collect();
+
+        Ok(deliveries)
+    }
+}
+
+
+/// Repeats DB transaction if it failed due to serialization failure or deadlock
+/// (concurrent transaction changed the same rows).
+async fn retry_on_tx_conflict<T, F, Fut>(mut tx_fn: F) -> Result<T, AccountProcessError>
+    where
+        F: FnMut() -> Fut,
+        Fut: core::future::Future<Output = Result<T, AccountProcessError>>,
+{
+    let mut attempt = 1;
+    loop {
+        match tx_fn().await {
+            Err(ref err) if attempt < MAX_TX_ATTEMPTS && is_tx_conflict_error(err) => {
+                warn!("### DB transaction conflict (attempt {attempt}), it will be repeated ({err:?})");
+                attempt += 1;
+            }
+            res => return res,
+        }
+    }
+}
+
+fn is_tx_conflict_error(err: &AccountProcessError) -> bool {
+    // See https://www.postgresql.org/docs/current/errcodes-appendix.html
+    const SERIALIZATION_FAILURE: &str = "40001";
+    const DEADLOCK_DETECTED: &str = "40P01";
+
+    match err {
+        AccountProcessError::Sqlx(sqlx::Error::Database(ref db_err), _) =>
+            matches!(db_err.code().as_deref(), Some(SERIALIZATION_FAILURE) | Some(DEADLOCK_DETECTED)),
+        _ => false,
+    }
+}
+
+
+/// Name is compared case-insensitively, extra spaces and order of first/last names are ignored.
+fn is_beneficiary_name_matched(first_name: &str, last_name: &str, beneficiary_name: &str) -> bool {
+    let normalize = |name: &str| name.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+
+    let first_name = normalize(first_name);
+    let last_name = normalize(last_name);
+    let beneficiary_name = normalize(beneficiary_name);
+
+    let first_last = [first_name.as_slice(), last_name.as_slice()].concat();
+    let last_first = [last_name.as_slice(), first_name.as_slice()].concat();
+    !beneficiary_name.is_empty() && (beneficiary_name == first_last || beneficiary_name == last_first)
+}
+
+
+/// Returns true if error is caused by leg data (it is reported as leg status),
+/// other (DB, internal) errors break the whole batch.
+fn is_transfer_leg_error(err: &AccountProcessError) -> bool {
+    match err {
+        AccountProcessError::Sqlx(sqlx::Error::RowNotFound, _) => true,
+        AccountProcessError::Sqlx(..) | AccountProcessError::Internal(..) => false,
+        _ => true,
+    }
+}
+
+
+fn find_account<'a>(accounts: &'a [Account], account_id: &AccountId) -> Result<&'a Account, AccountProcessError> {
+    accounts.iter()
+        .find(|account| &account.id == account_id)
+        .ok_or_else(|| AccountProcessError::AccountNotFound(AccountIdWrapper::Id(account_id.clone()), backtrace()))
+}
+
+
+/// Balances of accounts changed by reversal (they are changed by every compensating entry).
+struct ReversalBalances {
+    balances: Vec<(AccountId, Amount)>,
+}
+
+impl ReversalBalances {
+    fn new(accounts: &[Account]) -> Self {
+        let balances = accounts.iter()
+            .map(|account| (account.id.clone(), account.amount.with_value(account.amount.value_ref().clone())))
+            .collect();
+        ReversalBalances { balances }
+    }
+
+    fn balance(&self, account_id: &AccountId) -> Result<&Amount, AccountProcessError> {
+        self.balances.iter()
+            .find(|(id, _)| id == account_id)
+            .map(|(_, balance)| balance)
+            .ok_or_else(|| AccountProcessError::AccountNotFound(AccountIdWrapper::Id(account_id.clone()), backtrace()))
+    }
+
+    /// Returns new balance.
+    fn apply(&mut self, account_id: &AccountId, amount: &Amount) -> Result<Amount, AccountProcessError> {
+        let (_, balance) = self.balances.iter_mut()
+            .find(|(id, _)| id == account_id)
+            .ok_or_else(|| AccountProcessError::AccountNotFound(AccountIdWrapper::Id(account_id.clone()), backtrace())) ?;
+        *balance = (&*balance + amount) ?;
+        Ok(balance.with_value(balance.value_ref().clone()))
+    }
+}
+
+
+fn validate_amount_is_positive(amount: &Amount) -> Result<(), AccountProcessError> {
+    if amount.value_ref() <= &BigDecimal::from(0i32) {
+        return Err(AccountProcessError::IllegalArgument(format!("Amount [{amount}] should be positive."), backtrace()));
+    }
+    Ok(())
+}
+
+fn validate_account_is_active(account: &Account) -> Result<(), AccountProcessError> {
+    if account.status != AccountStatus::Active {
+        return Err(AccountProcessError::AccountNotActive(
+            AccountIdWrapper::Id(account.id.clone()), account.status, backtrace()));
+    }
+    Ok(())
+}
+
+
+
+fn validate_pending_transfer_can_be_decided(pending_transfer: &PendingTransfer, user_name: &str)
+    -> Result<(), AccountProcessError> {
+    if pending_transfer.status != PendingTransferStatus::Pending {
+        return Err(AccountProcessError::PendingTransferIsDecided(
+            pending_transfer.id.clone(), pending_transfer.status, backtrace()));
+    }
+    if !pending_transfer.can_be_decided_by(user_name) {
+        return Err(AccountProcessError::SelfApprovalNotAllowed(pending_transfer.id.clone(), backtrace()));
+    }
+    Ok(())
+}
+
+fn webhook_event_types_to_db(event_types: &[WebhookEventType]) -> Vec<&'static str> {
+    event_types.iter().map(|event_type| event_type.as_str()).collect()
+}
+
+fn validate_hold_is_active(hold: &Hold) -> Result<(), AccountProcessError> {
+    if hold.status != HoldStatus::Active {
+        return Err(AccountProcessError::HoldNotActive(hold.id.clone(), hold.status, backtrace()));
+    }
+    Ok(())
+}
+
+
+fn generate_iban() -> Result<iban::Iban, AccountProcessError> {
+    use rand::Rng;
+
+    let mut rng = rand::thread_rng();
+    let account_number: String = (0..IBAN_ACCOUNT_NUMBER_LEN)
+        .map(|_| char::from(b'0' + rng.gen_range(0..10u8)))
+        .collect();
+
+    make_iban(IBAN_COUNTRY_CODE, &format!("{IBAN_BANK_CODE}{account_number}"))
+        .map_err(|err| AccountProcessError::Internal(anyhow::anyhow!("Error of IBAN generation ({err:?}).")))
+}
+
+
+impl<ExRateP: ExchangeRateProvider> AccountServiceImpl<ExRateP> {
+
+    async fn transfer_impl(
+        &self, client_id: &ClientId,
+        from_account_id: &AccountIdWrapper, to_account_id: &AccountIdWrapper, beneficiary_name: Option<&str>,
+        amount: &Amount, expected_rate: Option<&ExpectedRate>, idempotency: Option<&Idempotency>,
+    ) -> Result<TransferId, AccountProcessError> {
+
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
+
+        let transfer_id = TransferId::generate();
+        if let Some(idempotency) = idempotency {
+            let prev_transfer_id = self.reserve_idempotency_key_impl(&mut tx, client_id, idempotency, &transfer_id).await ?;
+            if let Some(prev_transfer_id) = prev_transfer_id {
+                return Ok(prev_transfer_id);
+            }
+        }
+
+        self.transfer_in_tx_impl(&mut tx, &transfer_id,
+            client_id, from_account_id, to_account_id, beneficiary_name, amount, expected_rate, false).await ?;
+
+        tx.commit().await ?;
+        Ok(transfer_id)
+    }
+
+    /// Transfer which is not 'approved' (by approval of pending transfer) is rejected
+    /// if amount is above approval threshold.
+    async fn transfer_in_tx_impl(
+        &self, tx: &mut Transaction<'_, Postgres>, transfer_id: &TransferId, client_id: &ClientId,
+        from_account_id: &AccountIdWrapper, to_account_id: &AccountIdWrapper, beneficiary_name: Option<&str>,
+        amount: &Amount, expected_rate: Option<&ExpectedRate>, approved: bool,
+    ) -> Result<(), AccountProcessError> {
+
+        // Negative amount would move money from 'to' account (even of another client).
+        validate_amount_is_positive(amount) ?;
+
+        if !approved {
+            self.validate_approval_not_required_impl(tx, amount).await ?;
+        }
+
+        let (from_account, to_account) = self.lock_client_accounts_impl(
+            tx, client_id, from_account_id, to_account_id).await ?;
+        validate_account_is_active(&from_account) ?;
+        validate_account_is_active(&to_account) ?;
+
+        if to_account.client_id != *client_id || beneficiary_name.is_some() {
+            self.validate_beneficiary_impl(tx, &to_account, to_account_id, beneficiary_name).await ?;
+        }
+
+        let fee = self.transfer_fee_impl(tx, &from_account, &to_account, amount).await ?;
+        let total_debit_amount = match fee {
+            None => amount.with_value(amount.value_ref().clone()),
+            Some(ref fee) => (amount + fee) ?,
+        };
+
+        let new_from_account_amount = (&from_account.amount - amount) ?;
+        let available_funds = from_account.available_funds() ?;
+        if total_debit_amount.value_ref() > available_funds.value_ref() {
+            return Err(AccountProcessError::NotEnoughBalance(from_account_id.clone(), available_funds, backtrace()));
+        }
+
+        self.validate_transfer_limits_impl(tx, client_id, amount).await ?;
+
+        let (credit_amount, exchange_rate) = self.credit_amount_impl(
+            &from_account, &to_account, amount, expected_rate).await ?;
+        let new_to_account_amount = (&to_account.amount + &credit_amount) ?;
+
+        self.insert_transfer_transactions_impl(tx, transfer_id,
+            &from_account, &to_account, amount, &credit_amount, exchange_rate.as_ref(),
+            &new_from_account_amount, &new_to_account_amount).await ?;
+
+        // Fee account balance is changed after 'to' account update
+        // (it should not be overwritten if transfer is done to fee account itself).
+        let from_account_balance_after = new_from_account_amount.with_value(new_from_account_amount.value_ref().clone());
+        let to_account_balance_after = EventAmount::from(&new_to_account_amount);
+        self.update_account_by_id_impl(tx, &from_account.client_id, &from_account.id, new_from_account_amount).await ?;
+        self.update_account_by_id_impl(tx, &to_account.client_id, &to_account.id, new_to_account_amount).await ?;
+
+        let from_account_balance_after = match fee {
+            None => from_account_balance_after,
+            Some(ref fee) => {
+                self.charge_fee_impl(tx, transfer_id, &from_account, fee, &from_account_balance_after).await ?;
+                (&from_account_balance_after - fee) ?
+            }
+        };
+
+        self.insert_event_impl(tx, &AccountEvent::TransferCompleted {
+            transfer_id: transfer_id.clone().into_inner(),
+            from_account_id: from_account.id.clone().into_inner(),
+            from_client_id: from_account.client_id.clone().into_inner(),
+            to_account_id: to_account.id.clone().into_inner(),
+            to_client_id: to_account.client_id.clone().into_inner(),
+            amount: EventAmount::from(amount),
+            credit_amount: EventAmount::from(&credit_amount),
+            fee: fee.as_ref().map(EventAmount::from),
+            from_balance_after: EventAmount::from(&from_account_balance_after),
+            to_balance_after: to_account_balance_after,
+        }).await ?;
+
+        Ok(())
+    }
+
+    /// Transfer checks which can be done before locking of accounts
+    /// (they are used to reject invalid request as early as possible).
+    async fn validate_transfer_accounts_impl(
+        &self, tx: &mut Transaction<'_, Postgres>, client_id: &ClientId,
+        from_account_id: &AccountIdWrapper, to_account_id: &AccountIdWrapper, beneficiary_name: Option<&str>,
+        amount: &Amount,
+    ) -> Result<(Account, Account), AccountProcessError> {
+
+        validate_amount_is_positive(amount) ?;
+
+        let from_account = self.get_client_account_impl(tx, client_id, from_account_id).await ?;
+        let to_account = match to_account_id {
+            AccountIdWrapper::Id(_) =>
+                self.get_client_account_impl(tx, client_id, to_account_id).await ?,
+            AccountIdWrapper::Iban(iban) =>
+                self.get_account_by_iban_impl(tx, iban).await ?,
+        };
+        if from_account.id == to_account.id {
+            return Err(AccountProcessError::SameAccountTransfer(from_account_id.clone(), backtrace()));
+        }
+        validate_account_is_active(&from_account) ?;
+        validate_account_is_active(&to_account) ?;
+        // It also validates currency.
+        let _ = (&from_account.amount - amount) ?;
+
+        if to_account.client_id != *client_id || beneficiary_name.is_some() {
+            self.validate_beneficiary_impl(tx, &to_account, to_account_id, beneficiary_name).await ?;
+        }
+        Ok((from_account, to_account))
+    }
+
+    async fn is_transfer_approval_required_impl(&self, tx: &mut Transaction<'_, Postgres>, amount: &Amount)
+        -> Result<bool, AccountProcessError> {
+
+        let threshold: Option<BigDecimalWrapper> = sqlx::query_scalar(
+            "select THRESHOLD from TRANSFER_APPROVAL_THRESHOLDS where CUR = $1 ")
+            .bind(amount.currency())
+            .fetch_optional(&mut **tx)
+            .await ?;
+
+        Ok(threshold.is_some_and(|BigDecimalWrapper(threshold)| amount.value_ref() > &threshold))
+    }
+
+    async fn validate_approval_not_required_impl(&self, tx: &mut Transaction<'_, Postgres>, amount: &Amount)
+        -> Result<(), AccountProcessError> {
+        if self.is_transfer_approval_required_impl(tx, amount).await ? {
+            return Err(AccountProcessError::ApprovalRequired(
+                amount.with_value(amount.value_ref().clone()), backtrace()));
+        }
+        Ok(())
+    }
+
+    /// Returns None if there is no fee rule for this transfer (or fee is zero).
+    async fn transfer_fee_impl(
+        &self, tx: &mut Transaction<'_, Postgres>, from_account: &Account, to_account: &Account, amount: &Amount,
+    ) -> Result<Option<Amount>, AccountProcessError> {
+
+        let transfer_type = FeeTransferType {
+            inter_client: from_account.client_id != to_account.client_id,
+            cross_currency: from_account.amount.currency() != to_account.amount.currency(),
+        };
+
+        let fee_rule: Option<FeeRule> = sqlx::query_as(
+            "select FLAT_FEE, FEE_PERCENT, MIN_FEE, MAX_FEE \
+                 from FEE_RULES \
+                 where CUR = $1 and INTER_CLIENT = $2 and CROSS_CURRENCY = $3 ")
+            .bind(from_account.amount.currency())
+            .bind(transfer_type.inter_client)
+            .bind(transfer_type.cross_currency)
+            .fetch_optional(&mut **tx)
+            .await ?;
+
+        let fee = fee_rule.map(|fee_rule| fee_rule.calculate(amount))
+            .filter(|fee| fee.value_ref() > &BigDecimal::from(0i32));
+        debug!("### Transfer fee ({transfer_type:?}): {fee:?}");
+        Ok(fee)
+    }
+
+    /// Fee is a separate transfer (linked with main one by FEE_FOR_TRANSFER_ID)
+    /// from 'from' account to bank fee account of the same currency.
+    async fn charge_fee_impl(
+        &self, tx: &mut Transaction<'_, Postgres>, transfer_id: &TransferId,
+        from_account: &Account, fee: &Amount, from_account_balance: &Amount,
+    ) -> Result<TransferId, AccountProcessError> {
+
+        let fee_transfer_id = TransferId::generate();
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+
+        // Fee account is already locked (in ID order together with transfer accounts, see lock_client_accounts_impl()),
+        // it is changed by relative update because it can be also the 'to' account of this transfer.
+        let fee_account: Option<(AccountId, BigDecimalWrapper)> = sqlx::query_as(
+            " update ACCOUNTS \
+                 set AMOUNT = AMOUNT + $2, UPDATED_AT = $3 \
+                 where ID = (select ACCOUNT_ID from BANK_FEE_ACCOUNTS where CUR = $1) and CUR = $1 \
+                 returning ID, AMOUNT ")
+            .bind(fee.currency())
+            .bind(BigDecimalRefWrapper(fee.value_ref()))
+            .bind(&now)
+            .fetch_optional(&mut **tx)
+            .await ?;
+        let Some((fee_account_id, BigDecimalWrapper(fee_account_balance))) = fee_account
+            else {
+                return Err(AccountProcessError::Internal(
+                    anyhow::anyhow!("There is no bank fee account for currency [{}].", fee.currency())));
+            };
+
+        let new_from_account_amount = (from_account_balance - fee) ?;
+
+        let debit = fee.with_value(-fee.value_ref());
+        self.insert_transaction_impl(tx, &fee_transfer_id,
+            &from_account.id, Some(&fee_account_id), &debit, &new_from_account_amount,
+            None, None, Some(transfer_id)).await ?;
+        self.insert_transaction_impl(tx, &fee_transfer_id,
+            &fee_account_id, Some(&from_account.id), fee, &fee.with_value(fee_account_balance),
+            None, None, Some(transfer_id)).await ?;
+
+        self.update_account_by_id_impl(tx, &from_account.client_id, &from_account.id, new_from_account_amount).await ?;
+
+        debug!("### Fee {fee} of transfer [{transfer_id}] is charged (transfer [{fee_transfer_id}])");
+        Ok(fee_transfer_id)
+    }
+
+    /// Validates per-transfer and daily limits of client tier (for 'amount' currency).
+    /// If there are no limits for tier/currency, transfer is not limited.
+    ///
+    /// Client row is locked to serialize concurrent transfers from different accounts of the same client
+    /// (otherwise both of them could pass daily limit check).
+    async fn validate_transfer_limits_impl(
+        &self, tx: &mut Transaction<'_, Postgres>, client_id: &ClientId, amount: &Amount,
+    ) -> Result<(), AccountProcessError> {
+        use sqlx::Row;
+        use mvv_common::pg_column_name as col_name;
+
+        let client_row = sqlx::query(
+            "select BUSINESS_USER, SUPER_BUSINESS_USER \
+                 from CLIENTS \
+                 where CLIENT_ID = $1 \
+                 for no key update ")
+            .bind(client_id)
+            .fetch_one(&mut **tx)
+            .await ?;
+        let client_tier = ClientTier::from_features(
+            client_row.try_get(col_name!("BUSINESS_USER")) ?,
+            client_row.try_get(col_name!("SUPER_BUSINESS_USER")) ?,
+        );
+
+        let limits: Option<(Option<BigDecimalWrapper>, Option<BigDecimalWrapper>)> = sqlx::query_as(
+            "select MAX_TRANSFER_AMOUNT, MAX_DAILY_AMOUNT \
+                 from TRANSFER_LIMITS \
+                 where CLIENT_TIER = $1 and CUR = $2 ")
+            .bind(client_tier)
+            .bind(amount.currency())
+            .fetch_optional(&mut **tx)
+            .await ?;
+        let Some((max_transfer_amount, max_daily_amount)) = limits
+            else { return Ok(()) };
+
+        if let Some(BigDecimalWrapper(max_transfer_amount)) = max_transfer_amount {
+            if amount.value_ref() > &max_transfer_amount {
+                return Err(AccountProcessError::LimitExceeded(
+                    TransferLimitKind::PerTransfer, client_tier, amount.with_value(max_transfer_amount), backtrace()));
+            }
+        }
+
+        if let Some(BigDecimalWrapper(max_daily_amount)) = max_daily_amount {
+            // Rolling window (last 24 hours), reversal (compensating) and fee entries are not counted.
+            let BigDecimalWrapper(daily_amount): BigDecimalWrapper = sqlx::query_scalar(
+                "select coalesce(-sum(T.AMOUNT), 0) \
+                     from TRANSACTIONS T \
+                     join ACCOUNTS A on A.ID = T.ACCOUNT_ID \
+                     where A.CLIENT_ID = $1 and T.CUR = $2 and T.AMOUNT < 0 \
+                       and T.REVERSED_TRANSFER_ID is null and T.FEE_FOR_TRANSFER_ID is null \
+                       and T.CREATED_AT > CURRENT_TIMESTAMP - interval '1 day' ")
+                .bind(client_id)
+                .bind(amount.currency())
+                .fetch_one(&mut **tx)
+                .await ?;
+
+            if &daily_amount + amount.value_ref() > max_daily_amount {
+                return Err(AccountProcessError::LimitExceeded(
+                    TransferLimitKind::Daily, client_tier, amount.with_value(max_daily_amount), backtrace()));
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn authorize_hold_impl(
+        &self, client_id: &ClientId, account_id: &AccountIdWrapper, amount: &Amount, ttl: chrono::Duration,
+    ) -> Result<Hold, AccountProcessError> {
+
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
+
+        // Otherwise hold could not be captured, and it would block funds until expiration.
+        self.validate_approval_not_required_impl(&mut tx, amount).await ?;
+
+        let account = self.get_client_account_impl(&mut tx, client_id, account_id).await ?;
+        let account = self.lock_client_account_impl(&mut tx, client_id, &account.id).await ?;
+        validate_account_is_active(&account) ?;
+
+        // It also validates currency.
+        let _ = (&account.amount - amount) ?;
+        let available_funds = account.available_funds() ?;
+        if amount.value_ref() > available_funds.value_ref() {
+            return Err(AccountProcessError::NotEnoughBalance(account_id.clone(), available_funds, backtrace()));
+        }
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+
+        let hold = sqlx::query_as(
+            " insert into HOLDS \
+                 (ID, ACCOUNT_ID, AMOUNT, CUR, STATUS, EXPIRES_AT, CREATED_AT, UPDATED_AT) \
+                 values ($1, $2, $3, $4, $5, $6, $7, $7) \
+                 returning \
+                 ID, ACCOUNT_ID, AMOUNT, CUR, STATUS, \
+                 CAPTURED_AMOUNT, TRANSFER_ID, \
+                 EXPIRES_AT, CREATED_AT, UPDATED_AT ")
+            .bind(HoldId::generate())
+            .bind(&account.id)
+            .bind(&BigDecimalRefWrapper(amount.value_ref()))
+            .bind(amount.currency())
+            .bind(HoldStatus::Active)
+            .bind(now + ttl)
+            .bind(&now)
+            .fetch_one(&mut *tx)
+            .await ?;
+
+        tx.commit().await ?;
+        Ok(hold)
+    }
+
+    async fn capture_hold_impl(
+        &self, client_id: &ClientId, hold_id: &HoldId,
+        to_account_id: &AccountIdWrapper, beneficiary_name: Option<&str>, amount: Option<&Amount>,
+    ) -> Result<TransferId, AccountProcessError> {
+
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
+
+        let hold = self.lock_client_hold_impl(&mut tx, client_id, hold_id).await ?;
+        validate_hold_is_active(&hold) ?;
+
+        let amount = match amount {
+            None => hold.amount.with_value(hold.amount.value_ref().clone()),
+            Some(amount) => {
+                // It also validates currency.
+                let rest = (&hold.amount - amount) ?;
+                if rest.value_ref() < &BigDecimal::from(0i32) {
+                    return Err(AccountProcessError::HoldAmountExceeded(
+                        hold_id.clone(), hold.amount.with_value(hold.amount.value_ref().clone()), backtrace()));
+                }
+                amount.with_value(amount.value_ref().clone())
+            }
+        };
+
+        let transfer_id = TransferId::generate();
+
+        // Hold should be captured before transfer, otherwise it still reduces available funds.
+        self.update_hold_status_impl(&mut tx, hold_id, HoldStatus::Captured, Some(&amount), Some(&transfer_id)).await ?;
+
+        let from_account_id = AccountIdWrapper::Id(hold.account_id.clone());
+        self.transfer_in_tx_impl(&mut tx, &transfer_id,
+            client_id, &from_account_id, to_account_id, beneficiary_name, &amount, None, false).await ?;
+
+        tx.commit().await ?;
+        Ok(transfer_id)
+    }
+
+    /// Locks (SELECT FOR UPDATE) hold of client account.
+    async fn lock_client_hold_impl(
+        &self, tx: &mut Transaction<'_, Postgres>, client_id: &ClientId, hold_id: &HoldId,
+    ) -> Result<Hold, AccountProcessError> {
+
+        debug!("### Loading HOLD [{hold_id}] of client [{client_id}] from database");
+
+        let hold: Option<Hold> = sqlx::query_as(
+            "select \
+                 H.ID, H.ACCOUNT_ID, H.AMOUNT, H.CUR, \
+                 case when H.STATUS = 'ACTIVE' and H.EXPIRES_AT <= CURRENT_TIMESTAMP then 'EXPIRED' else H.STATUS end as STATUS, \
+                 H.CAPTURED_AMOUNT, H.TRANSFER_ID, \
+                 H.EXPIRES_AT, H.CREATED_AT, H.UPDATED_AT \
+                 from HOLDS H \
+                 join ACCOUNTS A on A.ID = H.ACCOUNT_ID \
+                 where A.CLIENT_ID = $1 and H.ID = $2 \
+                 for update of H ")
+            .bind(client_id)
+            .bind(hold_id)
+            .fetch_optional(&mut **tx)
+            .await ?;
+
+        hold.ok_or_else(|| AccountProcessError::HoldNotFound(hold_id.clone(), backtrace()))
+    }
+
+    async fn update_hold_status_impl(
+        &self, tx: &mut Transaction<'_, Postgres>, hold_id: &HoldId, status: HoldStatus,
+        captured_amount: Option<&Amount>, transfer_id: Option<&TransferId>,
+    ) -> Result<Hold, AccountProcessError> {
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+
+        let hold = sqlx::query_as(
+            " update HOLDS \
+                 set STATUS = $2, CAPTURED_AMOUNT = $3, TRANSFER_ID = $4, UPDATED_AT = $5 \
+                 where ID = $1 \
+                 returning \
+                 ID, ACCOUNT_ID, AMOUNT, CUR, STATUS, \
+                 CAPTURED_AMOUNT, TRANSFER_ID, \
+                 EXPIRES_AT, CREATED_AT, UPDATED_AT ")
+            .bind(hold_id)
+            .bind(status)
+            .bind(captured_amount.map(|amount| BigDecimalRefWrapper(amount.value_ref())))
+            .bind(transfer_id)
+            .bind(&now)
+            .fetch_one(&mut **tx)
+            .await ?;
+        Ok(hold)
+    }
+
+    /// Locks (SELECT FOR UPDATE) both accounts and returns their actual state.
+    /// 'from' account should belong to client, 'to' account given by IBAN can belong to any client.
+    ///
+    /// Rows are always locked in the same order (by ID) independently of transfer direction,
+    /// so concurrent opposite transfers do not deadlock.
+    /// Bank fee account (if fee is charged for this transfer) is locked together with them (in the same order).
+    async fn lock_client_accounts_impl(
+        &self, tx: &mut Transaction<'_, Postgres>, client_id: &ClientId,
+        from_account_id: &AccountIdWrapper, to_account_id: &AccountIdWrapper,
+    ) -> Result<(Account, Account), AccountProcessError> {
+
+        let (from_id, to_id) = self.resolve_transfer_accounts_impl(
+            tx, client_id, from_account_id, to_account_id).await ?;
+        let fee_account_id = self.transfer_fee_account_id_impl(tx, &from_id, &to_id).await ?;
+
+        let mut account_ids: Vec<uuid::Uuid> = [Some(&from_id), Some(&to_id), fee_account_id.as_ref()]
+            .into_iter()
+            .flatten()
+            .map(|account_id| account_id.clone().into_inner())
+            .collect();
+        account_ids.sort();
+        account_ids.dedup();
+
+        let accounts: Vec<Account> = sqlx::query_as(
+            "select \
+                 ID, IBAN, CLIENT_ID, NAME, \
+                 AMOUNT, CUR, OVERDRAFT_LIMIT, STATUS, ACCOUNT_HELD_AMOUNT(ID) as HELD_AMOUNT, \
+                 CREATED_AT, UPDATED_AT \
+                 from ACCOUNTS \
+                 where ID = any($1) \
+                 order by ID \
+                 for update ")
+            .bind(&account_ids)
+            .fetch_all(&mut **tx)
+            .await ?;
+
+        let mut from_account: Option<Account> = None;
+        let mut to_account: Option<Account> = None;
+        for account in accounts {
+            if account.id == from_id { from_account = Some(account) }
+            else if account.id == to_id { to_account = Some(account) }
+        }
+
+        match (from_account, to_account) {
+            (Some(from_account), Some(to_account)) => Ok((from_account, to_account)),
+            (None, _) => Err(AccountProcessError::AccountNotFound(from_account_id.clone(), backtrace())),
+            (_, None) => Err(AccountProcessError::AccountNotFound(to_account_id.clone(), backtrace())),
+        }
+    }
+
+    async fn approve_pending_transfer_impl(
+        &self, client_id: &ClientId, pending_transfer_id: &PendingTransferId, approved_by: &str,
+    ) -> Result<PendingTransfer, AccountProcessError> {
+
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
+
+        // Row lock prevents concurrent approval/rejection (transfer is executed only once).
+        let pending_transfer = self.get_client_pending_transfer_impl(&mut tx, client_id, pending_transfer_id, true).await ?;
+        validate_pending_transfer_can_be_decided(&pending_transfer, approved_by) ?;
+
+        // Transfer is executed by the same rules as usual one (limits, fee, beneficiary validation),
+        // but at current exchange rate.
+        let transfer_id = TransferId::generate();
+        self.transfer_in_tx_impl(&mut tx, &transfer_id, client_id,
+            &AccountIdWrapper::Id(pending_transfer.from_account_id.clone()),
+            &AccountIdWrapper::Iban(pending_transfer.to_iban.clone()),
+            pending_transfer.beneficiary_name.as_deref(),
+            &pending_transfer.amount,
+            None,
+            true,
+        ).await ?;
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        let pending_transfer = sqlx::query_as(
+            " update PENDING_TRANSFERS \
+                 set STATUS = $3, DECIDED_BY = $4, DECIDED_AT = $5, TRANSFER_ID = $6, UPDATED_AT = $5 \
+                 where CLIENT_ID = $1 and ID = $2 \
+                 returning \
+                 ID, CLIENT_ID, FROM_ACCOUNT_ID, TO_IBAN, BENEFICIARY_NAME, AMOUNT, CUR, STATUS, \
+                 CREATED_BY, DECIDED_BY, DECIDED_AT, TRANSFER_ID, REJECT_REASON, CREATED_AT, UPDATED_AT ")
+            .bind(client_id)
+            .bind(pending_transfer_id)
+            .bind(PendingTransferStatus::Approved)
+            .bind(approved_by)
+            .bind(&now)
+            .bind(&transfer_id)
+            .fetch_one(&mut *tx)
+            .await ?;
+
+        tx.commit().await ?;
+        info!("### Pending transfer [{pending_transfer_id}] is approved and executed (transfer [{transfer_id}])");
+        Ok(pending_transfer)
+    }
+
+    async fn accrue_account_interest_impl(&self, account_id: &AccountId, date: chrono::NaiveDate)
+        -> Result<Option<InterestAccrual>, AccountProcessError> {
+
+        use bigdecimal::{ RoundingMode, Zero };
+
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
+
+        // Interest is credited only on the last day of month, then bank interest account
+        // is locked together with account (in ID order, as accounts of usual transfer).
+        // Currency of account is never changed, so bank account can be found before locking.
+        let interest_account_id: Option<AccountId> = if is_last_day_of_month(date) {
+            sqlx::query_scalar(
+                "select I.ACCOUNT_ID \
+                     from ACCOUNTS A \
+                     join BANK_INTEREST_ACCOUNTS I on I.CUR = A.CUR \
+                     where A.ID = $1 ")
+                .bind(account_id)
+                .fetch_optional(&mut *tx)
+                .await ?
+        } else {
+            None
+        };
+        let mut locked_account_ids: Vec<uuid::Uuid> = [Some(account_id), interest_account_id.as_ref()]
+            .into_iter()
+            .flatten()
+            .map(|id| id.clone().into_inner())
+            .collect();
+        locked_account_ids.sort();
+        locked_account_ids.dedup();
+        sqlx::query(
+            "select ID from ACCOUNTS \
+                 where ID = any($1) \
+                 order by ID \
+                 for update ")
+            .bind(&locked_account_ids)
+            .execute(&mut *tx)
+            .await ?;
+
+        // Account row lock serializes accrual of the same account/day (and crediting).
+        let (currency, interest_rate, day_count_convention, accrued_interest): (Currency, BigDecimalWrapper, DayCountConvention, BigDecimalWrapper) =
+            sqlx::query_as(
+                "select CUR, INTEREST_RATE, DAY_COUNT_CONVENTION, ACCRUED_INTEREST \
+                     from ACCOUNTS \
+                     where ID = $1 \
+                     for update ")
+                .bind(account_id)
+                .fetch_one(&mut *tx)
+                .await ?;
+        let (BigDecimalWrapper(interest_rate), BigDecimalWrapper(accrued_interest)) = (interest_rate, accrued_interest);
+
+        let already_accrued: bool = sqlx::query_scalar(
+            "select exists(select 1 from INTEREST_ACCRUALS where ACCOUNT_ID = $1 and ACCRUAL_DATE = $2) ")
+            .bind(account_id)
+            .bind(date)
+            .fetch_one(&mut *tx)
+            .await ?;
+        if already_accrued {
+            return Ok(None);
+        }
+
+        // End of day balance is calculated by ledger (current balance without later entries),
+        // so past days can be processed (back-filled) too.
+        let day_end = date.succ_opt()
+            .ok_or_else(|| AccountProcessError::Internal(anyhow::anyhow!("Date [{date}] is out of range.")))?
+            .and_time(chrono::NaiveTime::MIN)
+            .and_utc();
+        let BigDecimalWrapper(balance): BigDecimalWrapper = sqlx::query_scalar(
+            "select A.AMOUNT - coalesce( \
+                 (select sum(T.AMOUNT) from TRANSACTIONS T where T.ACCOUNT_ID = A.ID and T.CREATED_AT >= $2), 0) \
+                 from ACCOUNTS A \
+                 where A.ID = $1 ")
+            .bind(account_id)
+            .bind(&day_end)
+            .fetch_one(&mut *tx)
+            .await ?;
+
+        // Interest is accrued only for positive balance (overdraft is not a deposit).
+        let interest = if balance > BigDecimal::zero() {
+            day_count_convention.daily_interest(&balance, &interest_rate, date)
+        } else {
+            BigDecimal::zero().with_scale(interest::INTEREST_ACCRUAL_SCALE)
+        };
+
+        sqlx::query(
+            " insert into INTEREST_ACCRUALS \
+                 (ACCOUNT_ID, ACCRUAL_DATE, BALANCE, INTEREST_RATE, DAY_COUNT_CONVENTION, AMOUNT, CUR) \
+                 values ($1, $2, $3, $4, $5, $6, $7) ")
+            .bind(account_id)
+            .bind(date)
+            .bind(BigDecimalRefWrapper(&balance))
+            .bind(BigDecimalRefWrapper(&interest_rate))
+            .bind(day_count_convention)
+            .bind(BigDecimalRefWrapper(&interest))
+            .bind(&currency)
+            .execute(&mut *tx)
+            .await ?;
+
+        let accrued_interest = Amount::new(&accrued_interest + &interest, currency);
+
+        // The rest (less than minor unit) stays accrued till the next month.
+        let credited_amount = accrued_interest.round_to_minor_units(RoundingMode::Down);
+        let credit = if is_last_day_of_month(date) && credited_amount.value_ref() > &BigDecimal::zero() {
+            Some(credited_amount)
+        } else {
+            None
+        };
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        let accrued_after = match credit {
+            None => accrued_interest.value_ref().clone(),
+            Some(ref credit) => accrued_interest.value_ref() - credit.value_ref(),
+        };
+        let balance_after: BigDecimalWrapper = sqlx::query_scalar(
+            " update ACCOUNTS \
+                 set AMOUNT = AMOUNT + $2, ACCRUED_INTEREST = $3, UPDATED_AT = $4 \
+                 where ID = $1 \
+                 returning AMOUNT ")
+            .bind(account_id)
+            .bind(BigDecimalWrapper(credit.as_ref().map(|credit| credit.value_ref().clone()).unwrap_or_default()))
+            .bind(BigDecimalRefWrapper(&accrued_after))
+            .bind(&now)
+            .fetch_one(&mut *tx)
+            .await ?;
+
+        let credit_transfer_id = match credit {
+            None => None,
+            Some(ref credit) => {
+                let interest_account_id = interest_account_id.ok_or_else(|| AccountProcessError::Internal(
+                    anyhow::anyhow!("There is no bank interest account of currency [{currency}]."))) ?;
+
+                // Interest belongs to accrual date (not to processing time).
+                let booked_at = day_end - chrono::Duration::microseconds(1);
+
+                let interest_account_balance_after: BigDecimalWrapper = sqlx::query_scalar(
+                    " update ACCOUNTS \
+                         set AMOUNT = AMOUNT - $2, UPDATED_AT = $3 \
+                         where ID = $1 \
+                         returning AMOUNT ")
+                    .bind(&interest_account_id)
+                    .bind(BigDecimalRefWrapper(credit.value_ref()))
+                    .bind(&now)
+                    .fetch_one(&mut *tx)
+                    .await ?;
+
+                let transfer_id = TransferId::generate();
+                let debit = credit.with_value(-credit.value_ref());
+                let interest_account_balance_after = credit.with_value(interest_account_balance_after.0);
+                let balance_after = credit.with_value(balance_after.0);
+                self.insert_transaction_at_impl(&mut tx, &transfer_id, &interest_account_id, Some(account_id),
+                    &debit, &interest_account_balance_after, None, None, None, &booked_at).await ?;
+                self.insert_transaction_at_impl(&mut tx, &transfer_id, account_id, Some(&interest_account_id),
+                    credit, &balance_after, None, None, None, &booked_at).await ?;
+                self.notify_account_changed_impl(&mut tx, &interest_account_id).await ?;
+                self.notify_account_changed_impl(&mut tx, account_id).await ?;
+
+                self.insert_event_impl(&mut tx, &AccountEvent::InterestCredited {
+                    transfer_id: transfer_id.clone().into_inner(),
+                    account_id: account_id.clone().into_inner(),
+                    amount: EventAmount::from(credit),
+                    balance_after: EventAmount::from(&balance_after),
+                }).await ?;
+
+                sqlx::query(
+                    " update INTEREST_ACCRUALS \
+                         set CREDIT_TRANSFER_ID = $3, CREDITED_AMOUNT = $4 \
+                         where ACCOUNT_ID = $1 and ACCRUAL_DATE = $2 ")
+                    .bind(account_id)
+                    .bind(date)
+                    .bind(&transfer_id)
+                    .bind(BigDecimalRefWrapper(credit.value_ref()))
+                    .execute(&mut *tx)
+                    .await ?;
+
+                info!("### Interest [{credit}] is credited to account [{account_id}] (transfer [{transfer_id}])");
+                Some(transfer_id)
+            }
+        };
+
+        tx.commit().await ?;
+
+        Ok(Some(InterestAccrual::new(interest::new::Args {
+            account_id: account_id.clone(),
+            accrual_date: date,
+            balance: Amount::new(balance, currency),
+            interest_rate,
+            day_count_convention,
+            amount: Amount::new(interest, currency),
+            credit_transfer_id,
+            credited_amount: credit,
+        })))
+    }
+
+    async fn get_client_pending_transfer_impl(
+        &self, tx: &mut Transaction<'_, Postgres>,
+        client_id: &ClientId, pending_transfer_id: &PendingTransferId, for_update: bool,
+    ) -> Result<PendingTransfer, AccountProcessError> {
+
+        let sql = format!(
+            "select \
+                 ID, CLIENT_ID, FROM_ACCOUNT_ID, TO_IBAN, BENEFICIARY_NAME, AMOUNT, CUR, STATUS, \
+                 CREATED_BY, DECIDED_BY, DECIDED_AT, TRANSFER_ID, REJECT_REASON, CREATED_AT, UPDATED_AT \
+                 from PENDING_TRANSFERS \
+                 where CLIENT_ID = $1 and ID = $2 {}",
+            if for_update { "for update" } else { "" });
+
+        let pending_transfer: Option<PendingTransfer> = sqlx::query_as(&sql)
+            .bind(client_id)
+            .bind(pending_transfer_id)
+            .fetch_optional(&mut **tx)
+            .await ?;
+        pending_transfer.ok_or_else(|| AccountProcessError::PendingTransferNotFound(pending_transfer_id.clone(), backtrace()))
+    }
+
+    async fn reverse_transfer_impl(&self, client_id: &ClientId, transfer_id: &TransferId)
+        -> Result<TransferId, AccountProcessError> {
+
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
+
+        let entries: Vec<AccountTransaction> = sqlx::query_as(
+            "select \
+                 ID, TRANSFER_ID, ACCOUNT_ID, COUNTERPARTY_ACCOUNT_ID, \
+                 AMOUNT, CUR, BALANCE_AFTER, \
+                 EXCHANGE_RATE, EXCHANGE_RATE_AT, REVERSED_TRANSFER_ID, FEE_FOR_TRANSFER_ID, \
+                 CREATED_AT \
+                 from TRANSACTIONS \
+                 where TRANSFER_ID = $1 ")
+            .bind(transfer_id)
+            .fetch_all(&mut *tx)
+            .await ?;
+
+        let zero = BigDecimal::from(0i32);
+        let debit = entries.iter().find(|entry| entry.amount.value_ref() < &zero);
+        let credit = entries.iter().find(|entry| entry.amount.value_ref() > &zero);
+        let (debit, credit) = match (debit, credit) {
+            (Some(debit), Some(credit)) if entries.len() == 2 => (debit, credit),
+            _ => return Err(AccountProcessError::TransferNotFound(transfer_id.clone(), backtrace())),
+        };
+
+        // Reversal (and fee) entries are not reversed separately
+        // (fee is refunded together with reversal of its transfer).
+        if debit.reversed_transfer_id.is_some() || debit.fee_for_transfer_id.is_some() {
+            return Err(AccountProcessError::TransferNotReversible(transfer_id.clone(), backtrace()));
+        }
+
+        let fee_entries: Vec<AccountTransaction> = sqlx::query_as(
+            "select \
+                 ID, TRANSFER_ID, ACCOUNT_ID, COUNTERPARTY_ACCOUNT_ID, \
+                 AMOUNT, CUR, BALANCE_AFTER, \
+                 EXCHANGE_RATE, EXCHANGE_RATE_AT, REVERSED_TRANSFER_ID, FEE_FOR_TRANSFER_ID, \
+                 CREATED_AT \
+                 from TRANSACTIONS \
+                 where FEE_FOR_TRANSFER_ID = $1 \
+                 order by ID ")
+            .bind(transfer_id)
+            .fetch_all(&mut *tx)
+            .await ?;
+        let fee_debit = fee_entries.iter().find(|entry| entry.amount.value_ref() < &zero);
+        let fee_credit = fee_entries.iter().find(|entry| entry.amount.value_ref() > &zero);
+        let fee = match (fee_debit, fee_credit) {
+            (None, None) => None,
+            (Some(fee_debit), Some(fee_credit)) if fee_entries.len() == 2 => Some((fee_debit, fee_credit)),
+            _ => return Err(AccountProcessError::Internal(anyhow::anyhow!(
+                "Unexpected fee entries of transfer [{transfer_id}]."))),
+        };
+
+        // Original 'from' account gets money back (with fee), original 'to' (beneficiary) account
+        // and bank fee account are debited. All of them are locked in ID order.
+        let mut account_ids = vec![&debit.account_id, &credit.account_id];
+        if let Some((_, fee_credit)) = fee {
+            account_ids.push(&fee_credit.account_id);
+        }
+        let accounts = self.lock_accounts_by_ids_impl(&mut tx, &account_ids).await ?;
+        let from_account = find_account(&accounts, &debit.account_id) ?;
+        let to_account = find_account(&accounts, &credit.account_id) ?;
+        if &from_account.client_id != client_id {
+            return Err(AccountProcessError::TransferNotFound(transfer_id.clone(), backtrace()));
+        }
+
+        // Accounts are locked, so concurrent reversal of the same transfer waits for us
+        // (and then it sees our compensating entries).
+        let already_reversed: bool = sqlx::query_scalar(
+            "select exists (select 1 from TRANSACTIONS where REVERSED_TRANSFER_ID = $1) ")
+            .bind(transfer_id)
+            .fetch_one(&mut *tx)
+            .await ?;
+        if already_reversed {
+            return Err(AccountProcessError::TransferAlreadyReversed(transfer_id.clone(), backtrace()));
+        }
+
+        validate_account_is_active(from_account) ?;
+        validate_account_is_active(to_account) ?;
+
+        let refund_amount = debit.amount.with_value(-debit.amount.value_ref());
+        let chargeback_amount = credit.amount.with_value(credit.amount.value_ref().clone());
+
+        let beneficiary_available = to_account.available_funds() ?;
+        if chargeback_amount.value_ref() > beneficiary_available.value_ref() {
+            return Err(AccountProcessError::ReversalNotEnoughBalance(
+                transfer_id.clone(), to_account.id.clone(), beneficiary_available, backtrace()));
+        }
+
+        // Fee account can be also 'to' account of original transfer,
+        // so balances are changed one by one (by every compensating entry).
+        let mut balances = ReversalBalances::new(&accounts);
+
+        let reversal_transfer_id = TransferId::generate();
+        let chargeback = chargeback_amount.with_value(-chargeback_amount.value_ref());
+        let new_to_amount = balances.apply(&to_account.id, &chargeback) ?;
+        let new_from_amount = balances.apply(&from_account.id, &refund_amount) ?;
+        self.insert_transaction_impl(&mut tx, &reversal_transfer_id,
+            &to_account.id, Some(&from_account.id), &chargeback, &new_to_amount,
+            None, Some(transfer_id), None).await ?;
+        self.insert_transaction_impl(&mut tx, &reversal_transfer_id,
+            &from_account.id, Some(&to_account.id), &refund_amount, &new_from_amount,
+            None, Some(transfer_id), None).await ?;
+
+        // Fee refund is a separate transfer which reverses fee transfer
+        // (and it is linked with reversal by FEE_FOR_TRANSFER_ID as fee is linked with original transfer).
+        let fee_refund_amount = match fee {
+            None => None,
+            Some((fee_debit, fee_credit)) => {
+                let fee_refund_transfer_id = TransferId::generate();
+                let fee_refund = fee_debit.amount.with_value(-fee_debit.amount.value_ref());
+                let fee_chargeback = fee_credit.amount.with_value(-fee_credit.amount.value_ref());
+
+                let new_fee_account_amount = balances.apply(&fee_credit.account_id, &fee_chargeback) ?;
+                let new_from_amount = balances.apply(&from_account.id, &fee_refund) ?;
+                self.insert_transaction_impl(&mut tx, &fee_refund_transfer_id,
+                    &fee_credit.account_id, Some(&from_account.id), &fee_chargeback, &new_fee_account_amount,
+                    None, Some(&fee_debit.transfer_id), Some(&reversal_transfer_id)).await ?;
+                self.insert_transaction_impl(&mut tx, &fee_refund_transfer_id,
+                    &from_account.id, Some(&fee_credit.account_id), &fee_refund, &new_from_amount,
+                    None, Some(&fee_debit.transfer_id), Some(&reversal_transfer_id)).await ?;
+                Some(fee_refund)
+            }
+        };
+
+        let to_balance_after = EventAmount::from(balances.balance(&to_account.id) ?);
+        let from_balance_after = EventAmount::from(balances.balance(&from_account.id) ?);
+        for account in accounts.iter() {
+            let balance = balances.balance(&account.id) ?;
+            let balance = balance.with_value(balance.value_ref().clone());
+            self.update_account_by_id_impl(&mut tx, &account.client_id, &account.id, balance).await ?;
+        }
+
+        self.insert_event_impl(&mut tx, &AccountEvent::TransferReversed {
+            reversal_transfer_id: reversal_transfer_id.clone().into_inner(),
+            reversed_transfer_id: transfer_id.clone().into_inner(),
+            from_account_id: from_account.id.clone().into_inner(),
+            from_client_id: from_account.client_id.clone().into_inner(),
+            to_account_id: to_account.id.clone().into_inner(),
+            to_client_id: to_account.client_id.clone().into_inner(),
+            refund_amount: EventAmount::from(&refund_amount),
+            chargeback_amount: EventAmount::from(&chargeback_amount),
+            fee_refund: fee_refund_amount.as_ref().map(EventAmount::from),
+            from_balance_after,
+            to_balance_after,
+        }).await ?;
+
+        tx.commit().await ?;
+        info!("### Transfer [{transfer_id}] is reversed by [{reversal_transfer_id}]");
+        Ok(reversal_transfer_id)
+    }
+
+    /// Locks (SELECT FOR UPDATE) accounts (of any client) in ID order.
+    /// Accounts are returned in ID order (every account once, even if ID is repeated).
+    async fn lock_accounts_by_ids_impl(
+        &self, tx: &mut Transaction<'_, Postgres>, account_ids: &[&AccountId],
+    ) -> Result<Vec<Account>, AccountProcessError> {
+
+        let mut ids: Vec<uuid::Uuid> = account_ids.iter()
+            .map(|account_id| (*account_id).clone().into_inner())
+            .collect();
+        ids.sort();
+        ids.dedup();
+
+        let accounts: Vec<Account> = sqlx::query_as(
+            "select \
+                 ID, IBAN, CLIENT_ID, NAME, \
+                 AMOUNT, CUR, OVERDRAFT_LIMIT, STATUS, ACCOUNT_HELD_AMOUNT(ID) as HELD_AMOUNT, \
+                 CREATED_AT, UPDATED_AT \
+                 from ACCOUNTS \
+                 where ID = any($1) \
+                 order by ID \
+                 for update ")
+            .bind(&ids)
+            .fetch_all(&mut **tx)
+            .await ?;
+
+        for account_id in account_ids {
+            find_account(&accounts, account_id) ?;
+        }
+        Ok(accounts)
+    }
+
+    /// Bank fee account of 'from' account currency if there is fee rule for this transfer
+    /// (the same rule as in transfer_fee_impl(), but accounts are not locked yet).
+    /// Currency and owner of account are never changed, so the result is stable.
+    async fn transfer_fee_account_id_impl(
+        &self, tx: &mut Transaction<'_, Postgres>, from_account_id: &AccountId, to_account_id: &AccountId,
+    ) -> Result<Option<AccountId>, AccountProcessError> {
+
+        let fee_account_id: Option<AccountId> = sqlx::query_scalar(
+            "select F.ACCOUNT_ID \
+                 from ACCOUNTS A_FROM \
+                 join ACCOUNTS A_TO on A_TO.ID = $2 \
+                 join FEE_RULES R on R.CUR = A_FROM.CUR \
+                     and R.INTER_CLIENT = (A_FROM.CLIENT_ID <> A_TO.CLIENT_ID) \
+                     and R.CROSS_CURRENCY = (A_FROM.CUR <> A_TO.CUR) \
+                 join BANK_FEE_ACCOUNTS F on F.CUR = A_FROM.CUR \
+                 where A_FROM.ID = $1 ")
+            .bind(from_account_id)
+            .bind(to_account_id)
+            .fetch_optional(&mut **tx)
+            .await ?;
+        Ok(fee_account_id)
+    }
+
+    /// Resolves IBAN to ID (and checks that 'from' account belongs to client).
+    async fn resolve_transfer_accounts_impl(
+        &self, tx: &mut Transaction<'_, Postgres>, client_id: &ClientId,
+        from_account_id: &AccountIdWrapper, to_account_id: &AccountIdWrapper,
+    ) -> Result<(AccountId, AccountId), AccountProcessError> {
+
+        let from_id = self.get_client_account_impl(tx, client_id, from_account_id).await ?.id;
+        let to_id = match to_account_id {
+            AccountIdWrapper::Id(_) =>
+                self.get_client_account_impl(tx, client_id, to_account_id).await ?.id,
+            AccountIdWrapper::Iban(ref iban) =>
+                self.get_account_by_iban_impl(tx, iban).await ?.id,
+        };
+
+        if from_id == to_id {
+            return Err(AccountProcessError::SameAccountTransfer(from_account_id.clone(), backtrace()));
+        }
+        Ok((from_id, to_id))
+    }
+
+    async fn transfer_batch_impl(&self, client_id: &ClientId, legs: &[TransferLeg], idempotency: Option<&Idempotency>)
+        -> Result<BatchTransferResult, AccountProcessError> {
+
+        let mut tx: Transaction<Postgres> = self.database_connection.begin().await ?;
+
+        let transfer_ids = legs.iter().map(|_| TransferId::generate()).collect::<Vec<_>>();
+        if let Some(idempotency) = idempotency {
+            let prev_transfer_ids = self.reserve_batch_idempotency_key_impl(
+                &mut tx, client_id, idempotency, &transfer_ids).await ?;
+            if let Some(prev_transfer_ids) = prev_transfer_ids {
+                let legs = prev_transfer_ids.into_iter().map(TransferLegStatus::Ok).collect();
+                return Ok(BatchTransferResult { committed: true, legs });
+            }
+        }
+
+        // All accounts of all legs (including bank fee accounts) are locked in the same order (by ID)
+        // before any change, so concurrent batches/transfers do not deadlock.
+        // Legs with unknown accounts are just skipped there (they are reported as failed below).
+        let mut account_ids: Vec<uuid::Uuid> = Vec::with_capacity(legs.len() * 3);
+        for leg in legs {
+            let accounts = self.resolve_transfer_accounts_impl(
+                &mut tx, client_id, &leg.from_account, &leg.to_account).await;
+            if let Ok((from_id, to_id)) = accounts {
+                if let Some(fee_account_id) = self.transfer_fee_account_id_impl(&mut tx, &from_id, &to_id).await ? {
+                    account_ids.push(fee_account_id.into_inner());
+                }
+                account_ids.push(from_id.into_inner());
+                account_ids.push(to_id.into_inner());
+            }
+        }
+        account_ids.sort();
+        account_ids.dedup();
+
+        sqlx::query(
+            "select ID from ACCOUNTS \
+                 where ID = any($1) \
+                 order by ID \
+                 for update ")
+            .bind(&account_ids)
+            .execute(&mut *tx)
+            .await ?;
+
+        // Every leg is executed in its own savepoint to find out status of every leg
+        // (leg sees changes of previous successful legs).
+        let mut leg_statuses: Vec<TransferLegStatus> = Vec::with_capacity(legs.len());
+        for (leg, transfer_id) in legs.iter().zip(transfer_ids) {
+            let mut leg_tx = tx.begin().await ?;
+
+            let leg_res = self.transfer_in_tx_impl(&mut leg_tx, &transfer_id, client_id,
+                &leg.from_account, &leg.to_account, leg.beneficiary_name.as_deref(),
+                &leg.amount, leg.expected_rate.as_ref(), false).await;
+
+            match leg_res {
+                Ok(()) => {
+                    leg_tx.commit().await ?;
+                    leg_statuses.push(TransferLegStatus::Ok(transfer_id));
+                }
+                Err(err) if is_transfer_leg_error(&err) => {
+                    leg_tx.rollback().await ?;
+                    leg_statuses.push(TransferLegStatus::Failed(err));
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        let committed = leg_statuses.iter().all(|status| matches!(status, TransferLegStatus::Ok(_)));
+        if committed {
+            tx.commit().await ?;
+        } else {
+            tx.rollback().await ?;
+        }
+
+        Ok(BatchTransferResult { committed, legs: leg_statuses })
+    }
+
+    /// Locks (SELECT FOR UPDATE) account and returns its actual state.
+    async fn lock_client_account_impl(
+        &self, tx: &mut Transaction<'_, Postgres>, client_id: &ClientId, account_id: &AccountId,
+    ) -> Result<Account, AccountProcessError> {
+        let account = sqlx::query_as(
+            "select \
+                 ID, IBAN, CLIENT_ID, NAME, \
+                 AMOUNT, CUR, OVERDRAFT_LIMIT, STATUS, ACCOUNT_HELD_AMOUNT(ID) as HELD_AMOUNT, \
+                 CREATED_AT, UPDATED_AT \
+                 from ACCOUNTS \
+                 where CLIENT_ID = $1 and ID = $2 \
+                 for update ")
+            .bind(client_id)
+            .bind(account_id)
+            .fetch_one(&mut **tx)
+            .await ?;
+        Ok(account)
+    }
+
+    async fn get_client_account_impl(
+        &self, tx: &mut Transaction<'_, Postgres>,
+        client_id: &ClientId, account_id: &AccountIdWrapper,
+    ) -> Result<Account, AccountProcessError> {
+        match account_id {
+            AccountIdWrapper::Id(ref account_id) =>
+                self.get_client_account_by_id_impl(tx, client_id, account_id).await,
+            AccountIdWrapper::Iban(ref iban) =>
+                self.get_client_account_by_iban_impl(tx, client_id, iban).await,
+        }
+    }
+
+    async fn get_client_account_by_id_impl(
+        &self, tx: &mut Transaction<'_, Postgres>,
+        client_id: &ClientId, account_id: &AccountId,
+    ) -> Result<Account, AccountProcessError> {
+
+        debug!("### Loading user ACCOUNT [{account_id}] of client [{client_id}] from database");
+
+        let res = sqlx::query_as(
+            "select \
+                 ID, IBAN, CLIENT_ID, NAME, \
+                 AMOUNT, CUR, OVERDRAFT_LIMIT, STATUS, ACCOUNT_HELD_AMOUNT(ID) as HELD_AMOUNT, \
+                 CREATED_AT, UPDATED_AT \
+                 from ACCOUNTS \
+                 where CLIENT_ID = $1 and ID = $2 ")
+            .bind(client_id.inner_ref())
+            .bind(account_id.inner_ref())
+            .fetch_one(&mut **tx)
+            .await
+            // .map_err(Self::Error::Sqlx)?)
+            .map_err(From::<sqlx::Error>::from);
+        res
+    }
+
+    async fn get_client_account_by_iban_impl(
+        &self, tx: &mut Transaction<'_, Postgres>,
+        client_id: &ClientId, iban: &iban::Iban,
+    ) -> Result<Account, AccountProcessError> {
+
+        debug!("### Loading user ACCOUNT [{iban}] of client [{client_id}] from database");
+
+        let res = sqlx::query_as(
+            "select \
+                 ID, IBAN, CLIENT_ID, NAME, \
+                 AMOUNT, CUR, OVERDRAFT_LIMIT, STATUS, ACCOUNT_HELD_AMOUNT(ID) as HELD_AMOUNT, \
+                 CREATED_AT, UPDATED_AT \
+                 from ACCOUNTS \
+                 where CLIENT_ID = $1 and IBAN = $2 ")
+            .bind(client_id)
+            .bind(&IbanRefWrapper(iban))
+            .fetch_one(&mut **tx) // &*self.database_connection)
+            .await
+            // .map_err(Self::Error::Sqlx)?)
+            .map_err(From::<sqlx::Error>::from);
+        res
+    }
+
+    /// Looks up account of any client (for example, beneficiary one).
+    async fn get_account_by_iban_impl(
+        &self, tx: &mut Transaction<'_, Postgres>, iban: &iban::Iban,
+    ) -> Result<Account, AccountProcessError> {
+
+        debug!("### Loading ACCOUNT [{iban}] from database");
+
+        let account: Option<Account> = sqlx::query_as(
+            "select \
+                 ID, IBAN, CLIENT_ID, NAME, \
+                 AMOUNT, CUR, OVERDRAFT_LIMIT, STATUS, ACCOUNT_HELD_AMOUNT(ID) as HELD_AMOUNT, \
+                 CREATED_AT, UPDATED_AT \
+                 from ACCOUNTS \
+                 where IBAN = $1 ")
+            .bind(&IbanRefWrapper(iban))
+            .fetch_optional(&mut **tx)
+            .await ?;
+
+        account.ok_or_else(|| AccountProcessError::AccountNotFound(
+            AccountIdWrapper::Iban(iban.clone()), backtrace()))
+    }
+
+    /// Checks that beneficiary name matches first/last name of account owner.
+    async fn validate_beneficiary_impl(
+        &self, tx: &mut Transaction<'_, Postgres>,
+        account: &Account, account_id: &AccountIdWrapper, beneficiary_name: Option<&str>,
+    ) -> Result<(), AccountProcessError> {
+
+        let Some(beneficiary_name) = beneficiary_name
+            else { return Err(AccountProcessError::BeneficiaryMismatch(account_id.clone(), backtrace())) };
+
+        debug!("### Loading name of CLIENT [{}] from database", account.client_id);
+
+        let (first_name, last_name): (String, String) = sqlx::query_as(
+            "select FIRST_NAME, LAST_NAME from CLIENTS where CLIENT_ID = $1 ")
+            .bind(&account.client_id)
+            .fetch_one(&mut **tx)
+            .await ?;
+
+        if is_beneficiary_name_matched(&first_name, &last_name, beneficiary_name) {
+            Ok(())
+        } else {
+            Err(AccountProcessError::BeneficiaryMismatch(account_id.clone(), backtrace()))
+        }
+    }
+
+    async fn update_account_by_id_impl(
+        &self, tx: &mut Transaction<'_, Postgres>,
+        client_id: &ClientId, id: &AccountId, amount: Amount,
+    ) -> Result<(), AccountProcessError> {
+
+        debug!("### Loading user ACCOUNT [{id}] of client [{client_id}] from database");
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        let AmountParts { value: amount, currency } = amount.into_parts();
+
+        let update_res = sqlx::query(
+            " update ACCOUNTS \
+                 set AMOUNT = $4, UPDATED_AT = $5 \
+                 where CLIENT_ID = $1 and ID = $2 and CUR = $3 ")
+            .bind(client_id)
+            .bind(&id)
+            .bind(&currency)
+            .bind(BigDecimalWrapper(amount)) // or .bind(&BigDecimalRefWrapper(&amount.value))
+            .bind(&now)
+            .execute(&mut **tx) // &*self.database_connection)
+            .await
+            .map_err(|err|AccountProcessError::Sqlx(err, backtrace())) ?;
+
+        let updated_count = update_res.rows_affected();
+        if updated_count != 1 {
+            return Err(AccountProcessError::Internal(
+                anyhow::anyhow!("Error of updating account [{id}] (updated_count: {updated_count}).")));
+        }
+
+        self.notify_account_changed_impl(tx, id).await
+    }
+
+    /// Notification (with client ID) is delivered to listeners only after commit
+    /// (and the same notifications of one transaction are delivered once).
+    async fn notify_account_changed_impl(&self, tx: &mut Transaction<'_, Postgres>, id: &AccountId)
+        -> Result<(), AccountProcessError> {
+
+        sqlx::query("select pg_notify($1, CLIENT_ID::text) from ACCOUNTS where ID = $2 ")
+            .bind(ACCOUNT_CHANGES_CHANNEL)
+            .bind(id)
+            .execute(&mut **tx)
+            .await ?;
+        Ok(())
+    }
+
+    /// Returns amount which should be credited to 'to' account
+    /// and exchange rate if conversion is needed (accounts have different currencies).
+    async fn credit_amount_impl(
+        &self, from_account: &Account, to_account: &Account, amount: &Amount,
+        expected_rate: Option<&ExpectedRate>,
+    ) -> Result<(Amount, Option<ExchangeRate>), AccountProcessError> {
+
+        let from_currency = from_account.amount.currency();
+        let to_currency = to_account.amount.currency();
+
+        if from_currency == to_currency {
+            return Ok((amount.with_value(amount.value_ref().clone()), None));
+        }
+
+        let exchange_rate = self.exchange_rate_provider.get_exchange_rate(from_currency, to_currency).await ?;
+        debug!("### Exchange rate [{from_currency} => {to_currency}]: {} (at {})",
+            exchange_rate.rate, exchange_rate.updated_at);
+
+        if let Some(expected_rate) = expected_rate {
+            if !exchange_rate.is_within_slippage(&expected_rate.rate, &expected_rate.max_slippage) {
+                return Err(AccountProcessError::ExchangeRateChanged(
+                    exchange_rate.rate, expected_rate.rate.clone(), backtrace()));
+            }
+        }
+
+        let credit_amount = exchange_rate.convert(amount) ?;
+        Ok((credit_amount, Some(exchange_rate)))
+    }
+
+    async fn insert_transfer_transactions_impl(
+        &self, tx: &mut Transaction<'_, Postgres>, transfer_id: &TransferId,
+        from_account: &Account, to_account: &Account,
+        debit_amount: &Amount, credit_amount: &Amount, exchange_rate: Option<&ExchangeRate>,
+        from_account_balance_after: &Amount, to_account_balance_after: &Amount,
+    ) -> Result<(), AccountProcessError> {
+
+        debug!("### Recording transfer [{transfer_id}] from ACCOUNT [{}] to [{}]", from_account.id, to_account.id);
+
+        let debit = debit_amount.with_value(-debit_amount.value_ref());
+        self.insert_transaction_impl(tx, transfer_id,
+            &from_account.id, Some(&to_account.id), &debit, from_account_balance_after, exchange_rate, None, None).await ?;
+        self.insert_transaction_impl(tx, transfer_id,
+            &to_account.id, Some(&from_account.id), credit_amount, to_account_balance_after, exchange_rate, None, None).await ?;
+
+        Ok(())
+    }
+
+    /// Returns ID of previous transfer if this key was already used (by committed transfer).
+    ///
+    /// Key row is inserted before transfer itself, so concurrent request with the same key
+    /// is blocked (by primary key) until the first transaction is completed.
+    async fn reserve_idempotency_key_impl(
+        &self, tx: &mut Transaction<'_, Postgres>,
+        client_id: &ClientId, idempotency: &Idempotency, transfer_id: &TransferId,
+    ) -> Result<Option<TransferId>, AccountProcessError> {
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+
+        let insert_res = sqlx::query(
+            " insert into TRANSFER_IDEMPOTENCY_KEYS \
+                 (CLIENT_ID, IDEMPOTENCY_KEY, REQUEST_HASH, TRANSFER_ID, CREATED_AT) \
+                 values ($1, $2, $3, $4, $5) \
+                 on conflict do nothing ")
+            .bind(client_id)
+            .bind(idempotency.key.as_str())
+            .bind(idempotency.request_hash.as_str())
+            .bind(transfer_id)
+            .bind(&now)
+            .execute(&mut **tx)
+            .await
+            .map_err(|err|AccountProcessError::Sqlx(err, backtrace())) ?;
+
+        if insert_res.rows_affected() == 1 {
+            return Ok(None);
+        }
+
+        let (request_hash, prev_transfer_id): (String, TransferId) = sqlx::query_as(
+            "select REQUEST_HASH, TRANSFER_ID \
+                 from TRANSFER_IDEMPOTENCY_KEYS \
+                 where CLIENT_ID = $1 and IDEMPOTENCY_KEY = $2 ")
+            .bind(client_id)
+            .bind(idempotency.key.as_str())
+            .fetch_one(&mut **tx)
+            .await ?;
+
+        if request_hash.trim() != idempotency.request_hash {
+            return Err(AccountProcessError::IdempotencyKeyConflict(idempotency.key.clone(), backtrace()));
+        }
+
+        info!("### Transfer with idempotency key [{}] is already done [{prev_transfer_id}]", idempotency.key);
+        Ok(Some(prev_transfer_id))
+    }
+
+    /// The same as reserve_idempotency_key_impl() but for batch
+    /// (returns transfer IDs of legs of previous committed batch).
+    async fn reserve_batch_idempotency_key_impl(
+        &self, tx: &mut Transaction<'_, Postgres>,
+        client_id: &ClientId, idempotency: &Idempotency, transfer_ids: &[TransferId],
+    ) -> Result<Option<Vec<TransferId>>, AccountProcessError> {
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        let transfer_ids = transfer_ids.iter()
+            .map(|transfer_id| transfer_id.clone().into_inner())
+            .collect::<Vec<uuid::Uuid>>();
+
+        let insert_res = sqlx::query(
+            " insert into TRANSFER_BATCH_IDEMPOTENCY_KEYS \
+                 (CLIENT_ID, IDEMPOTENCY_KEY, REQUEST_HASH, TRANSFER_IDS, CREATED_AT) \
+                 values ($1, $2, $3, $4, $5) \
+                 on conflict do nothing ")
+            .bind(client_id)
+            .bind(idempotency.key.as_str())
+            .bind(idempotency.request_hash.as_str())
+            .bind(&transfer_ids)
+            .bind(&now)
+            .execute(&mut **tx)
+            .await
+            .map_err(|err|AccountProcessError::Sqlx(err, backtrace())) ?;
+
+        if insert_res.rows_affected() == 1 {
+            return Ok(None);
+        }
+
+        let request_hash: String = sqlx::query_scalar(
+            "select REQUEST_HASH \
+                 from TRANSFER_BATCH_IDEMPOTENCY_KEYS \
+                 where CLIENT_ID = $1 and IDEMPOTENCY_KEY = $2 ")
+            .bind(client_id)
+            .bind(idempotency.key.as_str())
+            .fetch_one(&mut **tx)
+            .await ?;
+
+        if request_hash.trim() != idempotency.request_hash {
+            return Err(AccountProcessError::IdempotencyKeyConflict(idempotency.key.clone(), backtrace()));
+        }
+
+        let prev_transfer_ids: Vec<TransferId> = sqlx::query_scalar(
+            "select LEG.TRANSFER_ID \
+                 from TRANSFER_BATCH_IDEMPOTENCY_KEYS K \
+                 cross join unnest(K.TRANSFER_IDS) with ordinality as LEG(TRANSFER_ID, LEG_INDEX) \
+                 where K.CLIENT_ID = $1 and K.IDEMPOTENCY_KEY = $2 \
+                 order by LEG.LEG_INDEX ")
+            .bind(client_id)
+            .bind(idempotency.key.as_str())
+            .fetch_all(&mut **tx)
+            .await ?;
+
+        info!("### Batch with idempotency key [{}] is already done ({} legs)", idempotency.key, prev_transfer_ids.len());
+        Ok(Some(prev_transfer_ids))
+    }
+
+    async fn insert_transaction_impl(
+        &self, tx: &mut Transaction<'_, Postgres>,
+        transfer_id: &TransferId, account_id: &AccountId, counterparty_account_id: Option<&AccountId>,
+        amount: &Amount, balance_after: &Amount, exchange_rate: Option<&ExchangeRate>,
+        reversed_transfer_id: Option<&TransferId>, fee_for_transfer_id: Option<&TransferId>,
+    ) -> Result<(), AccountProcessError> {
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        self.insert_transaction_at_impl(tx, transfer_id, account_id, counterparty_account_id,
+            amount, balance_after, exchange_rate, reversed_transfer_id, fee_for_transfer_id, &now).await
+    }
+
+    /// Entry is booked at 'created_at' (it can be in the past), but it still follows
+    /// all existing entries of account (BALANCE_AFTER chain is ordered by ID).
+    async fn insert_transaction_at_impl(
+        &self, tx: &mut Transaction<'_, Postgres>,
+        transfer_id: &TransferId, account_id: &AccountId, counterparty_account_id: Option<&AccountId>,
+        amount: &Amount, balance_after: &Amount, exchange_rate: Option<&ExchangeRate>,
+        reversed_transfer_id: Option<&TransferId>, fee_for_transfer_id: Option<&TransferId>,
+        created_at: &chrono::DateTime<Utc>,
+    ) -> Result<(), AccountProcessError> {
+
+        sqlx::query(
+            " insert into TRANSACTIONS \
+                 (TRANSFER_ID, ACCOUNT_ID, COUNTERPARTY_ACCOUNT_ID, AMOUNT, CUR, BALANCE_AFTER, \
+                  EXCHANGE_RATE, EXCHANGE_RATE_AT, REVERSED_TRANSFER_ID, FEE_FOR_TRANSFER_ID, CREATED_AT) \
+                 values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) ")
+            .bind(transfer_id)
+            .bind(account_id)
+            .bind(counterparty_account_id)
+            .bind(BigDecimalRefWrapper(amount.value_ref()))
+            .bind(amount.currency())
+            .bind(BigDecimalRefWrapper(balance_after.value_ref()))
+            .bind(exchange_rate.map(|rate| BigDecimalRefWrapper(&rate.rate)))
+            .bind(exchange_rate.map(|rate| rate.updated_at))
+            .bind(reversed_transfer_id)
+            .bind(fee_for_transfer_id)
+            .bind(created_at)
+            .execute(&mut **tx)
+            .await
+            .map_err(|err|AccountProcessError::Sqlx(err, backtrace())) ?;
+
+        Ok(())
+    }
+
+    /// Event is written to outbox in the same transaction as account change,
+    /// so it is published (by OutboxDispatcher) only if this change is committed.
+    /// Webhook deliveries (for subscriptions of involved clients) are created in the same way.
+    async fn insert_event_impl(&self, tx: &mut Transaction<'_, Postgres>, event: &AccountEvent)
+        -> Result<(), AccountProcessError> {
+
+        let payload = serde_json::to_string(event)
+            .map_err(|err| AccountProcessError::Internal(anyhow::anyhow!(err))) ?;
+
+        sqlx::query(
+            " insert into OUTBOX_EVENTS (EVENT_ID, EVENT_TYPE, PAYLOAD) \
+                 values ($1, $2, $3::jsonb) ")
+            .bind(uuid::Uuid::new_v4())
+            .bind(event.event_type())
+            .bind(payload)
+            .execute(&mut **tx)
+            .await ?;
+
+        debug!("### Event [{}] is written to outbox", event.event_type());
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        for ClientNotification { client_id, event_type, notification } in transfer_notifications(event, now) {
+            let payload = serde_json::to_string(&notification)
+                .map_err(|err| AccountProcessError::Internal(anyhow::anyhow!(err))) ?;
+
+            sqlx::query(
+                " insert into WEBHOOK_DELIVERIES (ID, SUBSCRIPTION_ID, EVENT_ID, EVENT_TYPE, PAYLOAD, CREATED_AT, UPDATED_AT) \
+                     select gen_random_uuid(), S.ID, $2, $3, $4::jsonb, $5, $5 \
+                     from WEBHOOK_SUBSCRIPTIONS S \
+                     where S.CLIENT_ID = $1 and S.ACTIVE and $3 = any(S.EVENT_TYPES::VARCHAR[]) ")
+                .bind(client_id)
+                .bind(notification.event_id)
+                .bind(event_type)
+                .bind(payload)
+                .bind(&now)
+                .execute(&mut **tx)
+                .await ?;
+        }
+
+        Ok(())
+    }
+}
+
+
+impl sqlx::FromRow<'_, sqlx_postgres::PgRow> for Account {
     fn from_row(row: &sqlx_postgres::PgRow) -> sqlx::Result<Self> {
         use sqlx::Row;
         use mvv_common::pg_column_name as col_name;
 
+        let currency = row.try_get(col_name!("CUR")) ?;
         let account = Account::new(account::new::Args {
             id: row.try_get(col_name!("ID")) ?,
             iban: row.try_get::<IbanWrapper,_>(col_name!("IBAN")) ?.0,
@@ -292,8 +2902,17 @@ impl sqlx::FromRow<'_, sqlx_postgres::PgRow> for Account {
             name: row.try_get(col_name!("NAME")) ?,
             amount: Amount::new(
                 row.try_get::<BigDecimalWrapper,_>(col_name!("AMOUNT")) ?.0,
-                row.try_get(col_name!("CUR")) ?,
+                currency,
             ),
+            overdraft_limit: Amount::new(
+                row.try_get::<BigDecimalWrapper,_>(col_name!("OVERDRAFT_LIMIT")) ?.0,
+                currency,
+            ),
+            held_amount: Amount::new(
+                row.try_get::<BigDecimalWrapper,_>(col_name!("HELD_AMOUNT")) ?.0,
+                currency,
+            ),
+            status: row.try_get(col_name!("STATUS")) ?,
             created_at: row.try_get(col_name!("CREATED_AT")) ?,
             updated_at: row.try_get(col_name!("UPDATED_AT")) ?,
         });
@@ -301,3 +2920,221 @@ impl sqlx::FromRow<'_, sqlx_postgres::PgRow> for Account {
         Ok(account)
     }
 }
+
+
+impl sqlx::FromRow<'_, sqlx_postgres::PgRow> for Hold {
+    fn from_row(row: &sqlx_postgres::PgRow) -> sqlx::Result<Self> {
+        use sqlx::Row;
+        use mvv_common::pg_column_name as col_name;
+
+        let currency = row.try_get(col_name!("CUR")) ?;
+        let hold = Hold::new(hold::new::Args {
+            id: row.try_get(col_name!("ID")) ?,
+            account_id: row.try_get(col_name!("ACCOUNT_ID")) ?,
+            amount: Amount::new(
+                row.try_get::<BigDecimalWrapper,_>(col_name!("AMOUNT")) ?.0,
+                currency,
+            ),
+            status: row.try_get(col_name!("STATUS")) ?,
+            captured_amount: row.try_get::<Option<BigDecimalWrapper>,_>(col_name!("CAPTURED_AMOUNT")) ?
+                .map(|amount| Amount::new(amount.0, currency)),
+            transfer_id: row.try_get(col_name!("TRANSFER_ID")) ?,
+            expires_at: row.try_get(col_name!("EXPIRES_AT")) ?,
+            created_at: row.try_get(col_name!("CREATED_AT")) ?,
+            updated_at: row.try_get(col_name!("UPDATED_AT")) ?,
+        });
+
+        Ok(hold)
+    }
+}
+
+
+impl sqlx::FromRow<'_, sqlx_postgres::PgRow> for StandingOrder {
+    fn from_row(row: &sqlx_postgres::PgRow) -> sqlx::Result<Self> {
+        use sqlx::Row;
+        use mvv_common::pg_column_name as col_name;
+
+        let to_u32 = |value: i32| u32::try_from(value)
+            .map_err(|err| sqlx::Error::Decode(Box::new(err)));
+
+        let currency = row.try_get(col_name!("CUR")) ?;
+        let standing_order = StandingOrder::new(standing_order::new::Args {
+            id: row.try_get(col_name!("ID")) ?,
+            client_id: row.try_get(col_name!("CLIENT_ID")) ?,
+            from_account_id: row.try_get(col_name!("FROM_ACCOUNT_ID")) ?,
+            to_iban: row.try_get::<IbanWrapper,_>(col_name!("TO_IBAN")) ?.0,
+            beneficiary_name: row.try_get(col_name!("BENEFICIARY_NAME")) ?,
+            amount: Amount::new(
+                row.try_get::<BigDecimalWrapper,_>(col_name!("AMOUNT")) ?.0,
+                currency,
+            ),
+            period: row.try_get(col_name!("PERIOD")) ?,
+            start_at: row.try_get(col_name!("START_AT")) ?,
+            next_execution_no: to_u32(row.try_get(col_name!("NEXT_EXECUTION_NO")) ?) ?,
+            next_execution_at: row.try_get(col_name!("NEXT_EXECUTION_AT")) ?,
+            failed_attempts: to_u32(row.try_get(col_name!("FAILED_ATTEMPTS")) ?) ?,
+            retry_at: row.try_get(col_name!("RETRY_AT")) ?,
+            last_executed_at: row.try_get(col_name!("LAST_EXECUTED_AT")) ?,
+            last_transfer_id: row.try_get(col_name!("LAST_TRANSFER_ID")) ?,
+            last_error: row.try_get(col_name!("LAST_ERROR")) ?,
+            created_at: row.try_get(col_name!("CREATED_AT")) ?,
+            updated_at: row.try_get(col_name!("UPDATED_AT")) ?,
+        });
+
+        Ok(standing_order)
+    }
+}
+
+
+impl sqlx::FromRow<'_, sqlx_postgres::PgRow> for AccountTransaction {
+    fn from_row(row: &sqlx_postgres::PgRow) -> sqlx::Result<Self> {
+        use sqlx::Row;
+        use mvv_common::pg_column_name as col_name;
+
+        let currency = row.try_get(col_name!("CUR")) ?;
+        let transaction = AccountTransaction::new(transaction::new::Args {
+            id: row.try_get(col_name!("ID")) ?,
+            transfer_id: row.try_get(col_name!("TRANSFER_ID")) ?,
+            account_id: row.try_get(col_name!("ACCOUNT_ID")) ?,
+            counterparty_account_id: row.try_get(col_name!("COUNTERPARTY_ACCOUNT_ID")) ?,
+            amount: Amount::new(
+                row.try_get::<BigDecimalWrapper,_>(col_name!("AMOUNT")) ?.0,
+                currency,
+            ),
+            balance_after: Amount::new(
+                row.try_get::<BigDecimalWrapper,_>(col_name!("BALANCE_AFTER")) ?.0,
+                currency,
+            ),
+            exchange_rate: row.try_get::<Option<BigDecimalWrapper>,_>(col_name!("EXCHANGE_RATE")) ?
+                .map(|rate| rate.0),
+            exchange_rate_at: row.try_get(col_name!("EXCHANGE_RATE_AT")) ?,
+            reversed_transfer_id: row.try_get(col_name!("REVERSED_TRANSFER_ID")) ?,
+            fee_for_transfer_id: row.try_get(col_name!("FEE_FOR_TRANSFER_ID")) ?,
+            created_at: row.try_get(col_name!("CREATED_AT")) ?,
+        });
+
+        Ok(transaction)
+    }
+}
+
+
+impl sqlx::FromRow<'_, sqlx_postgres::PgRow> for PendingTransfer {
+    fn from_row(row: &sqlx_postgres::PgRow) -> sqlx::Result<Self> {
+        use sqlx::Row;
+        use mvv_common::pg_column_name as col_name;
+
+        let currency = row.try_get(col_name!("CUR")) ?;
+        let pending_transfer = PendingTransfer::new(pending_transfer::new::Args {
+            id: row.try_get(col_name!("ID")) ?,
+            client_id: row.try_get(col_name!("CLIENT_ID")) ?,
+            from_account_id: row.try_get(col_name!("FROM_ACCOUNT_ID")) ?,
+            to_iban: row.try_get::<IbanWrapper,_>(col_name!("TO_IBAN")) ?.0,
+            beneficiary_name: row.try_get(col_name!("BENEFICIARY_NAME")) ?,
+            amount: Amount::new(
+                row.try_get::<BigDecimalWrapper,_>(col_name!("AMOUNT")) ?.0,
+                currency,
+            ),
+            status: row.try_get(col_name!("STATUS")) ?,
+            created_by: row.try_get(col_name!("CREATED_BY")) ?,
+            decided_by: row.try_get(col_name!("DECIDED_BY")) ?,
+            decided_at: row.try_get(col_name!("DECIDED_AT")) ?,
+            transfer_id: row.try_get(col_name!("TRANSFER_ID")) ?,
+            reject_reason: row.try_get(col_name!("REJECT_REASON")) ?,
+            created_at: row.try_get(col_name!("CREATED_AT")) ?,
+            updated_at: row.try_get(col_name!("UPDATED_AT")) ?,
+        });
+
+        Ok(pending_transfer)
+    }
+}
+
+
+impl sqlx::FromRow<'_, sqlx_postgres::PgRow> for WebhookSubscription {
+    fn from_row(row: &sqlx_postgres::PgRow) -> sqlx::Result<Self> {
+        use core::str::FromStr;
+        use sqlx::Row;
+        use mvv_common::pg_column_name as col_name;
+
+        let event_types = row.try_get::<Vec<String>,_>(col_name!("EVENT_TYPES")) ?
+            .iter()
+            .map(|event_type| WebhookEventType::from_str(event_type))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| sqlx::Error::Decode(Box::new(err))) ?;
+
+        let subscription = WebhookSubscription::new(webhook::new::Args {
+            id: row.try_get(col_name!("ID")) ?,
+            client_id: row.try_get(col_name!("CLIENT_ID")) ?,
+            url: row.try_get(col_name!("URL")) ?,
+            secret: SecureString::from_string(row.try_get(col_name!("SECRET")) ?),
+            event_types,
+            active: row.try_get(col_name!("ACTIVE")) ?,
+            created_at: row.try_get(col_name!("CREATED_AT")) ?,
+            updated_at: row.try_get(col_name!("UPDATED_AT")) ?,
+        });
+
+        Ok(subscription)
+    }
+}
+
+
+/// History of attempts is loaded separately.
+impl sqlx::FromRow<'_, sqlx_postgres::PgRow> for WebhookDelivery {
+    fn from_row(row: &sqlx_postgres::PgRow) -> sqlx::Result<Self> {
+        use sqlx::Row;
+        use mvv_common::pg_column_name as col_name;
+
+        let to_u32 = |value: i32| u32::try_from(value)
+            .map_err(|err| sqlx::Error::Decode(Box::new(err)));
+
+        let delivery = WebhookDelivery::new(webhook::WebhookDeliveryParts {
+            id: row.try_get(col_name!("ID")) ?,
+            subscription_id: row.try_get(col_name!("SUBSCRIPTION_ID")) ?,
+            event_id: row.try_get(col_name!("EVENT_ID")) ?,
+            event_type: row.try_get(col_name!("EVENT_TYPE")) ?,
+            status: row.try_get(col_name!("STATUS")) ?,
+            attempts: to_u32(row.try_get(col_name!("ATTEMPTS")) ?) ?,
+            next_attempt_at: row.try_get(col_name!("NEXT_ATTEMPT_AT")) ?,
+            last_error: row.try_get(col_name!("LAST_ERROR")) ?,
+            delivered_at: row.try_get(col_name!("DELIVERED_AT")) ?,
+            created_at: row.try_get(col_name!("CREATED_AT")) ?,
+            attempt_history: Vec::new(),
+        });
+
+        Ok(delivery)
+    }
+}
+
+
+impl sqlx::FromRow<'_, sqlx_postgres::PgRow> for FeeRule {
+    fn from_row(row: &sqlx_postgres::PgRow) -> sqlx::Result<Self> {
+        use sqlx::Row;
+        use mvv_common::pg_column_name as col_name;
+
+        Ok(FeeRule {
+            flat_fee: row.try_get::<BigDecimalWrapper,_>(col_name!("FLAT_FEE")) ?.0,
+            percent: row.try_get::<BigDecimalWrapper,_>(col_name!("FEE_PERCENT")) ?.0,
+            min_fee: row.try_get::<Option<BigDecimalWrapper>,_>(col_name!("MIN_FEE")) ?
+                .map(|fee| fee.0),
+            max_fee: row.try_get::<Option<BigDecimalWrapper>,_>(col_name!("MAX_FEE")) ?
+                .map(|fee| fee.0),
+        })
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::is_beneficiary_name_matched;
+
+    #[test]
+    fn beneficiary_name_matching() {
+        assert!(is_beneficiary_name_matched("Tigra", "Bohdan", "Tigra Bohdan"));
+        assert!(is_beneficiary_name_matched("Tigra", "Bohdan", "  bohdan   TIGRA "));
+        assert!(is_beneficiary_name_matched("Anna Maria", "Bohdan", "Anna Maria Bohdan"));
+
+        assert!(!is_beneficiary_name_matched("Tigra", "Bohdan", "Tigra"));
+        assert!(!is_beneficiary_name_matched("Tigra", "Bohdan", "Tigra Bohdanenko"));
+        assert!(!is_beneficiary_name_matched("Tigra", "Bohdan", ""));
+    }
+}
@@ -0,0 +1,104 @@
+use core::future::Future;
+use std::time::Duration;
+use log::info;
+//--------------------------------------------------------------------------------------------------
+
+
+
+/// Max length of error text stored in LAST_ERROR columns.
+pub const LAST_ERROR_MAX_LEN: usize = 1000;
+
+
+/// Exponential backoff: base delay * 2^(attempt-1), but not more than max delay.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryBackoff {
+    /// Delay before the first retry, it is doubled for every next one.
+    pub base_delay: chrono::Duration,
+    pub max_delay: chrono::Duration,
+}
+
+impl RetryBackoff {
+    /// Delay after 'attempts' failed attempts (0 is treated as 1).
+    pub fn delay(&self, attempts: u32) -> chrono::Duration {
+        let factor = 1i32.checked_shl(attempts.saturating_sub(1)).unwrap_or(i32::MAX);
+        self.base_delay.checked_mul(factor)
+            .map(|delay| delay.min(self.max_delay))
+            .unwrap_or(self.max_delay)
+    }
+}
+
+
+/// Cuts error text to LAST_ERROR_MAX_LEN chars.
+pub fn truncate_error(err: String) -> String {
+    if err.chars().count() <= LAST_ERROR_MAX_LEN { err }
+    else { err.chars().take(LAST_ERROR_MAX_LEN).collect() }
+}
+
+
+/// Runs 'task' every 'interval' until the process is stopped
+/// (if run takes longer than interval, next run is delayed, runs are not overlapped).
+/// Interval should not be zero.
+pub fn spawn_periodic_task <
+    Task: FnMut() -> TaskFut + Send + 'static,
+    TaskFut: Future<Output = ()> + Send,
+> (task_name: &'static str, interval: Duration, mut task: Task) -> tokio::task::JoinHandle<()> {
+
+    info!("### Starting {task_name} (interval: {interval:?})");
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            task().await;
+        }
+    })
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{ AtomicUsize, Ordering };
+    use std::time::Duration;
+    use super::{ spawn_periodic_task, truncate_error, RetryBackoff, LAST_ERROR_MAX_LEN };
+
+    const BACKOFF: RetryBackoff = RetryBackoff {
+        base_delay: chrono::Duration::seconds(5),
+        max_delay: chrono::Duration::minutes(30),
+    };
+
+    #[test]
+    fn retry_delay_is_exponential_and_limited() {
+        assert_eq!(BACKOFF.delay(0), BACKOFF.base_delay);
+        assert_eq!(BACKOFF.delay(1), BACKOFF.base_delay);
+        assert_eq!(BACKOFF.delay(2), BACKOFF.base_delay * 2);
+        assert_eq!(BACKOFF.delay(3), BACKOFF.base_delay * 4);
+        assert_eq!(BACKOFF.delay(20), BACKOFF.max_delay);
+        assert_eq!(BACKOFF.delay(u32::MAX), BACKOFF.max_delay);
+    }
+
+    #[test]
+    fn error_is_truncated_by_chars() {
+        assert_eq!(truncate_error("short".to_owned()), "short");
+
+        let long_err = "є".repeat(LAST_ERROR_MAX_LEN + 1);
+        assert_eq!(truncate_error(long_err).chars().count(), LAST_ERROR_MAX_LEN);
+    }
+
+    #[tokio::test]
+    async fn periodic_task_is_repeated() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let task_runs = Arc::clone(&runs);
+
+        let handle = spawn_periodic_task("test task", Duration::from_millis(10), move || {
+            let task_runs = Arc::clone(&task_runs);
+            async move { task_runs.fetch_add(1, Ordering::SeqCst); }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        handle.abort();
+
+        assert!(runs.load(Ordering::SeqCst) >= 2);
+    }
+}
@@ -0,0 +1,159 @@
+use std::sync::Arc;
+use bigdecimal::{ BigDecimal, RoundingMode };
+use chrono::Utc;
+use log::debug;
+use mvv_common::{
+    backtrace::{backtrace, BacktraceCell},
+};
+use mvv_common_bank_entities::{
+    amount::Amount, bd::BigDecimalWrapper,
+};
+use crate::entity::prelude::Currency;
+//--------------------------------------------------------------------------------------------------
+
+
+
+#[derive(
+    Debug,
+    thiserror::Error,
+    mvv_error_macro::ThisErrorFromWithBacktrace,
+    mvv_error_macro::ThisErrorBacktraceSource,
+)]
+pub enum ExchangeRateError {
+    #[error("ExchangeRateNotFound ({0} => {1})")]
+    ExchangeRateNotFound(Currency, Currency, BacktraceCell),
+    #[error("Sqlx error")]
+    Sqlx(#[from_with_bt] sqlx::Error, BacktraceCell),
+}
+
+
+/// Rate of converting 'from' currency to 'to' currency (1 'from' = 'rate' 'to').
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExchangeRate {
+    pub from: Currency,
+    pub to: Currency,
+    pub rate: BigDecimal,
+    /// Time when rate was fixed by provider.
+    pub updated_at: chrono::DateTime<Utc>,
+}
+
+impl ExchangeRate {
+    /// Converts amount of 'from' currency to 'to' currency
+    /// (result is rounded to minor units of 'to' currency).
+    pub fn convert(&self, amount: &Amount) -> Result<Amount, ExchangeRateError> {
+        if amount.currency() != self.from {
+            return Err(ExchangeRateError::ExchangeRateNotFound(amount.currency(), self.to, backtrace()));
+        }
+        let converted = Amount::new(amount.value_ref() * &self.rate, self.to);
+        Ok(converted.round_to_minor_units(RoundingMode::HalfEven))
+    }
+
+    /// Returns true if rate differs from expected one
+    /// by no more than 'max_slippage' (relative value, for example 0.01 is 1%).
+    pub fn is_within_slippage(&self, expected_rate: &BigDecimal, max_slippage: &BigDecimal) -> bool {
+        let deviation = (&self.rate - expected_rate).abs();
+        deviation <= expected_rate.abs() * max_slippage
+    }
+}
+
+
+/// Client expectation of exchange rate (usually it is rate shown to client before transfer).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectedRate {
+    pub rate: BigDecimal,
+    /// Relative value, for example 0.01 is 1%.
+    pub max_slippage: BigDecimal,
+}
+
+
+#[trait_variant::make(Send)]
+pub trait ExchangeRateProvider: Send + Sync {
+    async fn get_exchange_rate(&self, from: Currency, to: Currency) -> Result<ExchangeRate, ExchangeRateError>;
+}
+
+
+/// Rates are taken from EXCHANGE_RATES table (it is filled/updated by external process).
+pub struct SqlExchangeRateProvider {
+    pub database_connection: Arc<sqlx_postgres::PgPool>,
+}
+
+impl ExchangeRateProvider for SqlExchangeRateProvider {
+
+    async fn get_exchange_rate(&self, from: Currency, to: Currency) -> Result<ExchangeRate, ExchangeRateError> {
+
+        debug!("### Loading EXCHANGE_RATE [{from} => {to}] from database");
+
+        let rate: Option<(BigDecimalWrapper, chrono::DateTime<Utc>)> = sqlx::query_as(
+            "select RATE, UPDATED_AT \
+                 from EXCHANGE_RATES \
+                 where FROM_CUR = $1 and TO_CUR = $2 ")
+            .bind(&from)
+            .bind(&to)
+            .fetch_optional(&*self.database_connection)
+            .await ?;
+
+        match rate {
+            None => Err(ExchangeRateError::ExchangeRateNotFound(from, to, backtrace())),
+            Some((BigDecimalWrapper(rate), updated_at)) =>
+                Ok(ExchangeRate { from, to, rate, updated_at }),
+        }
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+    use bigdecimal::BigDecimal;
+    use mvv_auth::util::test_unwrap::TestResultUnwrap;
+    use mvv_common_bank_entities::{ amount::Amount, currency::Currency };
+    use super::ExchangeRate;
+
+    fn usd_to_uah() -> ExchangeRate {
+        ExchangeRate {
+            from: Currency::from_str("USD").test_unwrap(),
+            to: Currency::from_str("UAH").test_unwrap(),
+            rate: BigDecimal::from_str("41.2345").test_unwrap(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn convert() {
+        let rate = usd_to_uah();
+
+        let converted = rate.convert(&Amount::from_str("10.05 USD").test_unwrap()).test_unwrap();
+        assert_eq!(converted.currency(), Currency::from_str("UAH").test_unwrap());
+        // 10.05 * 41.2345 = 414.406725
+        assert_eq!(converted.value_ref(), &BigDecimal::from_str("414.41").test_unwrap());
+
+        assert!(rate.convert(&Amount::from_str("10.05 EUR").test_unwrap()).is_err());
+    }
+
+    #[test]
+    fn convert_to_currency_without_minor_units() {
+        let rate = ExchangeRate {
+            from: Currency::from_str("USD").test_unwrap(),
+            to: Currency::from_str("JPY").test_unwrap(),
+            rate: BigDecimal::from_str("149.837").test_unwrap(),
+            updated_at: chrono::Utc::now(),
+        };
+
+        let converted = rate.convert(&Amount::from_str("10.05 USD").test_unwrap()).test_unwrap();
+        // 10.05 * 149.837 = 1505.86185
+        assert_eq!(converted.value_ref(), &BigDecimal::from_str("1506").test_unwrap());
+    }
+
+    #[test]
+    fn is_within_slippage() {
+        let rate = usd_to_uah();
+        let max_slippage = BigDecimal::from_str("0.01").test_unwrap();
+
+        assert!(rate.is_within_slippage(&BigDecimal::from_str("41.2345").test_unwrap(), &max_slippage));
+        assert!(rate.is_within_slippage(&BigDecimal::from_str("41.0").test_unwrap(), &max_slippage));
+        assert!(rate.is_within_slippage(&BigDecimal::from_str("41.6").test_unwrap(), &max_slippage));
+        assert!(!rate.is_within_slippage(&BigDecimal::from_str("40.8").test_unwrap(), &max_slippage));
+        assert!(!rate.is_within_slippage(&BigDecimal::from_str("41.7").test_unwrap(), &max_slippage));
+    }
+}
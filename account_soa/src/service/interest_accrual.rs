@@ -0,0 +1,62 @@
+use std::sync::Arc;
+use chrono::NaiveDate;
+use log::info;
+use crate::service::account_service::{ AccountProcessError, AccountService };
+//--------------------------------------------------------------------------------------------------
+
+
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct InterestAccrualSummary {
+    pub accounts: usize,
+    pub accrued_days: usize,
+    /// Days which are already processed by previous runs.
+    pub skipped_days: usize,
+    /// Count of monthly credit entries.
+    pub credits: usize,
+}
+
+
+/// Accrues interest of all interest-bearing accounts for date range
+/// (it is run by 'accrue-interest' sub-command).
+///
+/// Every account/day is processed in separate DB transaction and already processed days are skipped,
+/// so the same range can be run again (for example, after failure) and past days can be back-filled.
+/// Days are processed in chronological order since monthly crediting depends on previous days.
+pub struct InterestAccrualJob <AccountS: AccountService + Send + Sync + 'static> {
+    pub account_service: Arc<AccountS>,
+}
+
+impl <AccountS: AccountService + Send + Sync + 'static> InterestAccrualJob<AccountS> {
+
+    /// Both dates are inclusive.
+    pub async fn run(&self, from: NaiveDate, to: NaiveDate) -> Result<InterestAccrualSummary, AccountProcessError> {
+        if from > to {
+            return Err(AccountProcessError::Internal(anyhow::anyhow!(
+                "Start date [{from}] of interest accrual is after end date [{to}].")));
+        }
+
+        info!("### Accruing interest from [{from}] to [{to}]");
+
+        let account_ids = self.account_service.get_interest_bearing_account_ids().await ?;
+        let mut summary = InterestAccrualSummary { accounts: account_ids.len(), ..Default::default() };
+
+        for account_id in account_ids {
+            for date in from.iter_days().take_while(|date| date <= &to) {
+                let accrual = self.account_service.accrue_account_interest(account_id.clone(), date).await ?;
+                match accrual {
+                    None => summary.skipped_days += 1,
+                    Some(accrual) => {
+                        summary.accrued_days += 1;
+                        if accrual.credit_transfer_id.is_some() {
+                            summary.credits += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        info!("### Interest accrual is finished: {summary:?}");
+        Ok(summary)
+    }
+}
@@ -0,0 +1,302 @@
+use std::sync::{ Arc, Mutex };
+use std::time::Duration;
+use chrono::Utc;
+use log::{ debug, error, info, warn };
+use mvv_common::backtrace::{ backtrace, BacktraceCell };
+use crate::service::{
+    account_service::AccountProcessError,
+    background::{ spawn_periodic_task, truncate_error, RetryBackoff },
+};
+//--------------------------------------------------------------------------------------------------
+
+
+
+pub const DEFAULT_DISPATCHER_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Max count of events published by one dispatcher run (the rest is published by next runs).
+const MAX_EVENTS_PER_RUN: i64 = 100;
+
+const RETRY_BACKOFF: RetryBackoff = RetryBackoff {
+    base_delay: chrono::Duration::seconds(5),
+    max_delay: chrono::Duration::minutes(30),
+};
+
+/// Dispatcher lease is prolonged before publishing of every event,
+/// so it should be longer than one EventSink::publish() call (including HTTP timeout).
+const DISPATCHER_LEASE: chrono::Duration = chrono::Duration::minutes(1);
+
+
+/// Row of OUTBOX_EVENTS table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutboxEvent {
+    pub id: i64,
+    /// Consumers should use it to skip duplicates (delivery is at-least-once).
+    pub event_id: uuid::Uuid,
+    pub event_type: String,
+    /// JSON of AccountEvent.
+    pub payload: String,
+    pub created_at: chrono::DateTime<Utc>,
+    pub attempts: i32,
+}
+
+
+#[derive(Debug, thiserror::Error)]
+pub enum EventSinkError {
+    #[error("EventSinkError: {0}")]
+    PublishError(String, BacktraceCell),
+}
+
+
+/// Destination of outbox events (message broker, webhook, and so on).
+/// It is called (with the same event) again if previous call failed,
+/// or if server was stopped after publishing but before marking event as published.
+#[async_trait::async_trait]
+pub trait EventSink: Send + Sync {
+    async fn publish(&self, event: &OutboxEvent) -> Result<(), EventSinkError>;
+}
+
+
+/// Just writes events to log (default sink).
+pub struct LogEventSink;
+
+#[async_trait::async_trait]
+impl EventSink for LogEventSink {
+    async fn publish(&self, event: &OutboxEvent) -> Result<(), EventSinkError> {
+        info!("### Event [{}] {} {}", event.event_id, event.event_type, event.payload);
+        Ok(())
+    }
+}
+
+
+/// Sends event JSON by HTTP POST, any non-2xx response is treated as failure.
+pub struct WebhookEventSink {
+    pub client: reqwest::Client,
+    pub url: String,
+}
+
+#[async_trait::async_trait]
+impl EventSink for WebhookEventSink {
+    async fn publish(&self, event: &OutboxEvent) -> Result<(), EventSinkError> {
+        let response = self.client.post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header("X-Event-Id", event.event_id.to_string())
+            .header("X-Event-Type", event.event_type.as_str())
+            .body(event.payload.clone())
+            .send()
+            .await
+            .map_err(|err| EventSinkError::PublishError(err.to_string(), backtrace())) ?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(EventSinkError::PublishError(
+                format!("Webhook [{}] responded with status {status}.", self.url), backtrace()));
+        }
+        Ok(())
+    }
+}
+
+
+/// Keeps published events in memory (for tests).
+/// Next 'failures' publish calls fail (to test retries).
+#[derive(Default)]
+pub struct InMemoryEventSink {
+    events: Mutex<Vec<OutboxEvent>>,
+    failures: Mutex<usize>,
+}
+
+impl InMemoryEventSink {
+    pub fn fail_next(&self, failures: usize) {
+        *self.failures.lock().expect("InMemoryEventSink lock") = failures;
+    }
+    pub fn events(&self) -> Vec<OutboxEvent> {
+        self.events.lock().expect("InMemoryEventSink lock").clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl EventSink for InMemoryEventSink {
+    async fn publish(&self, event: &OutboxEvent) -> Result<(), EventSinkError> {
+        {
+            let mut failures = self.failures.lock().expect("InMemoryEventSink lock");
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(EventSinkError::PublishError("Test failure.".to_owned(), backtrace()));
+            }
+        }
+        self.events.lock().expect("InMemoryEventSink lock").push(event.clone());
+        Ok(())
+    }
+}
+
+
+/// Publishes outbox events (written by AccountServiceImpl) to EventSink.
+///
+/// Event is marked as published only after successful EventSink::publish(),
+/// so delivery is at-least-once (the same event can be published twice if server is stopped
+/// between them). Events are published in insertion order, if one of them fails
+/// the rest of events waits for its retry.
+///
+/// Several replicas can run dispatcher, but only the owner of lease (OUTBOX_DISPATCHER_LEASE)
+/// publishes events. Lease is taken by short DB statement and events are published
+/// without open DB transaction (slow sink does not hold DB locks).
+pub struct OutboxDispatcher {
+    pub database_connection: Arc<sqlx_postgres::PgPool>,
+    pub sink: Arc<dyn EventSink>,
+    /// Unique ID of dispatcher (replica), it is used as lease owner.
+    pub dispatcher_id: uuid::Uuid,
+}
+
+impl OutboxDispatcher {
+
+    pub fn start(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        spawn_periodic_task("outbox dispatcher", interval, move || {
+            let dispatcher = Arc::clone(&self);
+            async move {
+                match dispatcher.dispatch_pending().await {
+                    Ok(0) => {}
+                    Ok(published) => info!("### {published} outbox events are published"),
+                    Err(err) => error!("### Error of publishing outbox events: {err:?}"),
+                }
+            }
+        })
+    }
+
+    /// Returns count of published events.
+    pub async fn dispatch_pending(&self) -> Result<usize, AccountProcessError> {
+
+        if !self.acquire_lease().await ? {
+            debug!("### Outbox events are published by another dispatcher");
+            return Ok(0);
+        }
+
+        let events = self.load_ready_events().await ?;
+
+        let mut published = 0;
+        for event in events {
+            // Lease could be expired (and taken by another replica) during previous publishing.
+            if !self.acquire_lease().await ? {
+                warn!("### Outbox dispatcher lease is lost");
+                break;
+            }
+
+            match self.sink.publish(&event).await {
+                Ok(()) => {
+                    sqlx::query("update OUTBOX_EVENTS set PUBLISHED_AT = $2, LAST_ERROR = null where ID = $1 ")
+                        .bind(event.id)
+                        .bind(&chrono::Local::now().to_utc())
+                        .execute(&*self.database_connection)
+                        .await ?;
+                    published += 1;
+                }
+                Err(err) => {
+                    let attempts = event.attempts.saturating_add(1);
+                    let next_attempt_at = chrono::Local::now().to_utc()
+                        + RETRY_BACKOFF.delay(u32::try_from(attempts).unwrap_or(u32::MAX));
+                    warn!("### Event [{}] ({}) is not published ({err}), it will be repeated at {next_attempt_at}",
+                        event.event_id, event.event_type);
+
+                    sqlx::query(
+                        " update OUTBOX_EVENTS \
+                             set ATTEMPTS = $2, NEXT_ATTEMPT_AT = $3, LAST_ERROR = $4 \
+                             where ID = $1 ")
+                        .bind(event.id)
+                        .bind(attempts)
+                        .bind(&next_attempt_at)
+                        .bind(truncate_error(err.to_string()))
+                        .execute(&*self.database_connection)
+                        .await ?;
+                    // Next events are not published before this one (to keep order).
+                    break;
+                }
+            }
+        }
+
+        Ok(published)
+    }
+
+    /// Takes (or prolongs own) lease if it is free or expired.
+    async fn acquire_lease(&self) -> Result<bool, AccountProcessError> {
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+
+        let res = sqlx::query(
+            " update OUTBOX_DISPATCHER_LEASE \
+                 set OWNER_ID = $1, LEASED_UNTIL = $3 \
+                 where ID = 1 and (OWNER_ID = $1 or LEASED_UNTIL <= $2) ")
+            .bind(self.dispatcher_id)
+            .bind(&now)
+            .bind(&(now + DISPATCHER_LEASE))
+            .execute(&*self.database_connection)
+            .await ?;
+        Ok(res.rows_affected() == 1)
+    }
+
+    /// Unpublished events in ID order up to the first one which waits for retry
+    /// (events after it are not published before it).
+    async fn load_ready_events(&self) -> Result<Vec<OutboxEvent>, AccountProcessError> {
+        use sqlx::Row;
+        use mvv_common::pg_column_name as col_name;
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+
+        let rows = sqlx::query(
+            "select ID, EVENT_ID, EVENT_TYPE, PAYLOAD::text as PAYLOAD, CREATED_AT, ATTEMPTS, NEXT_ATTEMPT_AT \
+                 from OUTBOX_EVENTS \
+                 where PUBLISHED_AT is null \
+                 order by ID \
+                 limit $1 ")
+            .bind(MAX_EVENTS_PER_RUN)
+            .fetch_all(&*self.database_connection)
+            .await ?;
+
+        let mut events = Vec::with_capacity(rows.len());
+        for row in rows.iter() {
+            let next_attempt_at: chrono::DateTime<Utc> = row.try_get(col_name!("NEXT_ATTEMPT_AT")) ?;
+            if next_attempt_at > now {
+                break;
+            }
+            events.push(OutboxEvent {
+                id: row.try_get(col_name!("ID")) ?,
+                event_id: row.try_get(col_name!("EVENT_ID")) ?,
+                event_type: row.try_get(col_name!("EVENT_TYPE")) ?,
+                payload: row.try_get(col_name!("PAYLOAD")) ?,
+                created_at: row.try_get(col_name!("CREATED_AT")) ?,
+                attempts: row.try_get(col_name!("ATTEMPTS")) ?,
+            });
+        }
+        Ok(events)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use mvv_auth::util::test_unwrap::TestResultUnwrap;
+    use super::{ EventSink, InMemoryEventSink, OutboxEvent };
+
+    fn event(id: i64) -> OutboxEvent {
+        OutboxEvent {
+            id,
+            event_id: uuid::Uuid::new_v4(),
+            event_type: "AccountOpened".to_owned(),
+            payload: r#"{"type":"AccountOpened"}"#.to_owned(),
+            created_at: chrono::Local::now().to_utc(),
+            attempts: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn in_memory_sink_keeps_published_events() {
+        let sink = InMemoryEventSink::default();
+        sink.fail_next(1);
+
+        assert!(sink.publish(&event(1)).await.is_err());
+        assert!(sink.events().is_empty());
+
+        sink.publish(&event(1)).await.test_unwrap();
+        sink.publish(&event(2)).await.test_unwrap();
+
+        let ids: Vec<i64> = sink.events().iter().map(|event| event.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+}
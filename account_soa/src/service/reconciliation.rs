@@ -0,0 +1,435 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use bigdecimal::{ BigDecimal, Zero };
+use chrono::Utc;
+use log::{ error, info };
+use serde::Serialize;
+use mvv_common_bank_entities::{ bd::BigDecimalWrapper, Currency };
+use crate::service::{
+    account_service::AccountProcessError,
+    background::spawn_periodic_task,
+};
+//--------------------------------------------------------------------------------------------------
+
+
+
+pub const DEFAULT_RECONCILIATION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+
+/// Balance of account recomputed from its ledger entries (TRANSACTIONS).
+///
+/// Accounts can be opened with initial AMOUNT without ledger entry,
+/// so opening balance is taken from the first entry (BALANCE_AFTER - AMOUNT).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBalanceCheck {
+    pub account_id: uuid::Uuid,
+    pub client_id: uuid::Uuid,
+    pub currency: Currency,
+    /// ACCOUNTS.AMOUNT
+    #[serde(with = "mvv_common::json::serde_json_bd::bd_with")]
+    pub stored_balance: BigDecimal,
+    /// Opening balance + sum of ledger entries.
+    #[serde(with = "mvv_common::json::serde_json_bd::bd_with")]
+    pub ledger_balance: BigDecimal,
+    pub ledger_entries: i64,
+    /// Entries whose BALANCE_AFTER is not equal to previous BALANCE_AFTER + AMOUNT.
+    pub broken_entries: i64,
+    /// Entries in currency other than account currency.
+    pub foreign_currency_entries: i64,
+}
+
+impl AccountBalanceCheck {
+    /// stored_balance - ledger_balance
+    pub fn drift(&self) -> BigDecimal {
+        &self.stored_balance - &self.ledger_balance
+    }
+    pub fn is_ok(&self) -> bool {
+        self.stored_balance == self.ledger_balance && self.broken_entries == 0 && self.foreign_currency_entries == 0
+    }
+}
+
+
+/// Ledger entry sums of one currency grouped by transfer kind.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CurrencyLedgerFlows {
+    /// Transfers between accounts of the same currency (including fees and reversals),
+    /// debit and credit entries should compensate each other, so it should be zero.
+    pub internal: BigDecimal,
+    /// Single-entry transfers (money which comes from outside of the bank).
+    pub external: BigDecimal,
+    /// Cross-currency transfers (money goes to/from other currencies by exchange rate).
+    pub exchange: BigDecimal,
+}
+
+
+/// Per-currency system total: sum of all account balances should be changed
+/// only by external and exchange flows.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyTotal {
+    pub currency: Currency,
+    pub accounts: usize,
+    /// Sum of ACCOUNTS.AMOUNT
+    #[serde(with = "mvv_common::json::serde_json_bd::bd_with")]
+    pub stored_total: BigDecimal,
+    /// Sum of ledger balances
+    #[serde(with = "mvv_common::json::serde_json_bd::bd_with")]
+    pub ledger_total: BigDecimal,
+    #[serde(with = "mvv_common::json::serde_json_bd::bd_with")]
+    pub drift: BigDecimal,
+    /// Should be zero.
+    #[serde(with = "mvv_common::json::serde_json_bd::bd_with")]
+    pub internal_flow: BigDecimal,
+    #[serde(with = "mvv_common::json::serde_json_bd::bd_with")]
+    pub external_flow: BigDecimal,
+    #[serde(with = "mvv_common::json::serde_json_bd::bd_with")]
+    pub exchange_flow: BigDecimal,
+    pub conserved: bool,
+}
+
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationReport {
+    pub checked_at: chrono::DateTime<Utc>,
+    pub ok: bool,
+    pub accounts_checked: usize,
+    /// Only accounts with problems.
+    pub mismatched_accounts: Vec<AccountMismatch>,
+    pub currency_totals: Vec<CurrencyTotal>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountMismatch {
+    #[serde(flatten)]
+    pub check: AccountBalanceCheck,
+    #[serde(with = "mvv_common::json::serde_json_bd::bd_with")]
+    pub drift: BigDecimal,
+}
+
+impl ReconciliationReport {
+    pub fn new(checked_at: chrono::DateTime<Utc>,
+               accounts: Vec<AccountBalanceCheck>, flows: BTreeMap<Currency, CurrencyLedgerFlows>) -> Self {
+
+        let currency_totals = currency_totals(&accounts, flows);
+        let accounts_checked = accounts.len();
+        let mismatched_accounts = accounts.into_iter()
+            .filter(|check| !check.is_ok())
+            .map(|check| AccountMismatch { drift: check.drift(), check })
+            .collect::<Vec<_>>();
+        let ok = mismatched_accounts.is_empty() && currency_totals.iter().all(|total| total.conserved);
+
+        ReconciliationReport { checked_at, ok, accounts_checked, mismatched_accounts, currency_totals }
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+
+fn currency_totals(accounts: &[AccountBalanceCheck], mut flows: BTreeMap<Currency, CurrencyLedgerFlows>)
+    -> Vec<CurrencyTotal> {
+
+    let mut totals: BTreeMap<Currency, (usize, BigDecimal, BigDecimal)> = BTreeMap::new();
+    for account in accounts {
+        let (count, stored_total, ledger_total) = totals.entry(account.currency).or_default();
+        *count += 1;
+        *stored_total += &account.stored_balance;
+        *ledger_total += &account.ledger_balance;
+    }
+
+    totals.into_iter()
+        .map(|(currency, (accounts, stored_total, ledger_total))| {
+            let CurrencyLedgerFlows { internal, external, exchange } = flows.remove(&currency).unwrap_or_default();
+            let drift = &stored_total - &ledger_total;
+            let conserved = drift.is_zero() && internal.is_zero();
+            CurrencyTotal {
+                currency, accounts, stored_total, ledger_total, drift,
+                internal_flow: internal, external_flow: external, exchange_flow: exchange,
+                conserved,
+            }
+        })
+        .collect()
+}
+
+
+/// Checks stored account balances against ledger (TRANSACTIONS).
+///
+/// It only reads data (nothing is fixed automatically), drift is reported
+/// and should be investigated manually.
+/// Checks are not done in one snapshot with transfers in progress,
+/// so it uses 'repeatable read' transaction to see consistent data.
+pub struct ReconciliationJob {
+    pub database_connection: Arc<sqlx_postgres::PgPool>,
+}
+
+impl ReconciliationJob {
+
+    pub fn start(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        spawn_periodic_task("reconciliation job", interval, move || {
+            let job = Arc::clone(&self);
+            async move {
+                match job.run().await {
+                    Ok(report) => log_report(&report),
+                    Err(err) => error!("### Error of balance reconciliation: {err:?}"),
+                }
+            }
+        })
+    }
+
+    pub async fn run(&self) -> Result<ReconciliationReport, AccountProcessError> {
+        let mut tx = self.database_connection.begin().await ?;
+        sqlx::query("set transaction isolation level repeatable read, read only ")
+            .execute(&mut *tx)
+            .await ?;
+
+        let checked_at: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        let accounts = self.load_account_checks(&mut tx).await ?;
+        let flows = self.load_currency_flows(&mut tx).await ?;
+        tx.commit().await ?;
+
+        Ok(ReconciliationReport::new(checked_at, accounts, flows))
+    }
+
+    async fn load_account_checks(&self, tx: &mut sqlx::Transaction<'_, sqlx_postgres::Postgres>)
+        -> Result<Vec<AccountBalanceCheck>, AccountProcessError> {
+
+        use sqlx::Row;
+        use mvv_common::pg_column_name as col_name;
+
+        let rows = sqlx::query(
+            " with ENTRIES as ( \
+                  select ACCOUNT_ID, ID, AMOUNT, CUR, BALANCE_AFTER, \
+                      lag(BALANCE_AFTER) over (partition by ACCOUNT_ID order by ID) as PREV_BALANCE_AFTER, \
+                      first_value(BALANCE_AFTER - AMOUNT) over (partition by ACCOUNT_ID order by ID) as OPENING_BALANCE \
+                  from TRANSACTIONS ) \
+              select A.ID, A.CLIENT_ID, A.CUR, A.AMOUNT, \
+                  min(E.OPENING_BALANCE) + coalesce(sum(E.AMOUNT), 0) as LEDGER_BALANCE, \
+                  count(E.ID) as LEDGER_ENTRIES, \
+                  count(E.ID) filter (where E.PREV_BALANCE_AFTER + E.AMOUNT <> E.BALANCE_AFTER) as BROKEN_ENTRIES, \
+                  count(E.ID) filter (where E.CUR <> A.CUR) as FOREIGN_CURRENCY_ENTRIES \
+              from ACCOUNTS A \
+              left join ENTRIES E on E.ACCOUNT_ID = A.ID \
+              group by A.ID, A.CLIENT_ID, A.CUR, A.AMOUNT \
+              order by A.ID ")
+            .fetch_all(&mut **tx)
+            .await ?;
+
+        rows.iter()
+            .map(|row| {
+                let stored_balance = row.try_get::<BigDecimalWrapper,_>(col_name!("AMOUNT")) ?.0;
+                // Without ledger entries there is nothing to compare with.
+                let ledger_balance = row.try_get::<Option<BigDecimalWrapper>,_>(col_name!("LEDGER_BALANCE")) ?
+                    .map(|balance| balance.0)
+                    .unwrap_or_else(|| stored_balance.clone());
+
+                Ok(AccountBalanceCheck {
+                    account_id: row.try_get(col_name!("ID")) ?,
+                    client_id: row.try_get(col_name!("CLIENT_ID")) ?,
+                    currency: row.try_get(col_name!("CUR")) ?,
+                    stored_balance,
+                    ledger_balance,
+                    ledger_entries: row.try_get(col_name!("LEDGER_ENTRIES")) ?,
+                    broken_entries: row.try_get(col_name!("BROKEN_ENTRIES")) ?,
+                    foreign_currency_entries: row.try_get(col_name!("FOREIGN_CURRENCY_ENTRIES")) ?,
+                })
+            })
+            .collect::<Result<Vec<_>, sqlx::Error>>()
+            .map_err(From::from)
+    }
+
+    async fn load_currency_flows(&self, tx: &mut sqlx::Transaction<'_, sqlx_postgres::Postgres>)
+        -> Result<BTreeMap<Currency, CurrencyLedgerFlows>, AccountProcessError> {
+
+        use sqlx::Row;
+        use mvv_common::pg_column_name as col_name;
+
+        let rows = sqlx::query(
+            " with TRANSFERS as ( \
+                  select TRANSFER_ID, count(*) as ENTRIES, count(distinct CUR) as CURRENCIES \
+                  from TRANSACTIONS \
+                  group by TRANSFER_ID ) \
+              select T.CUR, \
+                  coalesce(sum(T.AMOUNT) filter (where X.ENTRIES > 1 and X.CURRENCIES = 1), 0) as INTERNAL_FLOW, \
+                  coalesce(sum(T.AMOUNT) filter (where X.ENTRIES = 1), 0) as EXTERNAL_FLOW, \
+                  coalesce(sum(T.AMOUNT) filter (where X.CURRENCIES > 1), 0) as EXCHANGE_FLOW \
+              from TRANSACTIONS T \
+              join TRANSFERS X on X.TRANSFER_ID = T.TRANSFER_ID \
+              group by T.CUR ")
+            .fetch_all(&mut **tx)
+            .await ?;
+
+        rows.iter()
+            .map(|row| Ok((
+                row.try_get::<Currency,_>(col_name!("CUR")) ?,
+                CurrencyLedgerFlows {
+                    internal: row.try_get::<BigDecimalWrapper,_>(col_name!("INTERNAL_FLOW")) ?.0,
+                    external: row.try_get::<BigDecimalWrapper,_>(col_name!("EXTERNAL_FLOW")) ?.0,
+                    exchange: row.try_get::<BigDecimalWrapper,_>(col_name!("EXCHANGE_FLOW")) ?.0,
+                },
+            )))
+            .collect::<Result<BTreeMap<_, _>, sqlx::Error>>()
+            .map_err(From::from)
+    }
+}
+
+
+/// Any drift is logged as error (alarm), it should be monitored by log alerting.
+pub fn log_report(report: &ReconciliationReport) {
+    if report.ok {
+        info!("### Balance reconciliation is OK ({} accounts)", report.accounts_checked);
+        return;
+    }
+
+    let report_json = report.to_json().unwrap_or_else(|err| format!("{err:?}"));
+    error!("### BALANCE RECONCILIATION ALARM: {} of {} accounts are mismatched, not conserved currencies: [{}]\n{report_json}",
+        report.mismatched_accounts.len(),
+        report.accounts_checked,
+        report.currency_totals.iter()
+            .filter(|total| !total.conserved)
+            .map(|total| total.currency.to_string())
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+    use std::collections::BTreeMap;
+    use bigdecimal::BigDecimal;
+    use mvv_auth::util::test_unwrap::TestResultUnwrap;
+    use mvv_common_bank_entities::make_currency;
+    use super::{ AccountBalanceCheck, CurrencyLedgerFlows, ReconciliationReport };
+
+    fn bd(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).test_unwrap()
+    }
+    fn uuid(s: &str) -> uuid::Uuid {
+        uuid::Uuid::from_str(s).test_unwrap()
+    }
+    fn usd_account(id: &str, stored_balance: &str, ledger_balance: &str) -> AccountBalanceCheck {
+        AccountBalanceCheck {
+            account_id: uuid(id),
+            client_id: uuid("00000000-0000-0000-0000-000000000001"),
+            currency: make_currency!("USD"),
+            stored_balance: bd(stored_balance),
+            ledger_balance: bd(ledger_balance),
+            ledger_entries: 2,
+            broken_entries: 0,
+            foreign_currency_entries: 0,
+        }
+    }
+    fn usd_flows(internal: &str) -> BTreeMap<mvv_common_bank_entities::Currency, CurrencyLedgerFlows> {
+        BTreeMap::from([(make_currency!("USD"), CurrencyLedgerFlows {
+            internal: bd(internal),
+            external: bd("1.25"),
+            exchange: bd("0"),
+        })])
+    }
+
+    #[test]
+    fn consistent_balances() {
+        let report = ReconciliationReport::new(
+            chrono::Local::now().to_utc(),
+            vec![
+                usd_account("00000000-0000-0000-0000-000000000101", "150", "150"),
+                usd_account("00000000-0000-0000-0000-000000000102", "0.5", "0.50"),
+            ],
+            usd_flows("0"),
+        );
+
+        assert!(report.ok);
+        assert_eq!(report.accounts_checked, 2);
+        assert!(report.mismatched_accounts.is_empty());
+        assert_eq!(report.currency_totals.len(), 1);
+        assert!(report.currency_totals[0].conserved);
+        assert_eq!(report.currency_totals[0].stored_total, bd("150.5"));
+    }
+
+    #[test]
+    fn account_drift() {
+        let report = ReconciliationReport::new(
+            chrono::Local::now().to_utc(),
+            vec![
+                usd_account("00000000-0000-0000-0000-000000000101", "150", "140"),
+                usd_account("00000000-0000-0000-0000-000000000102", "10", "10"),
+            ],
+            usd_flows("0"),
+        );
+
+        assert!(!report.ok);
+        assert_eq!(report.mismatched_accounts.len(), 1);
+        assert_eq!(report.mismatched_accounts[0].drift, bd("10"));
+        assert_eq!(report.currency_totals[0].drift, bd("10"));
+        assert!(!report.currency_totals[0].conserved);
+    }
+
+    #[test]
+    fn broken_ledger_chain() {
+        let mut account = usd_account("00000000-0000-0000-0000-000000000101", "150", "150");
+        account.broken_entries = 1;
+
+        let report = ReconciliationReport::new(chrono::Local::now().to_utc(), vec![account], usd_flows("0"));
+        assert!(!report.ok);
+        assert_eq!(report.mismatched_accounts.len(), 1);
+        // Total itself is still conserved.
+        assert!(report.currency_totals[0].conserved);
+    }
+
+    #[test]
+    fn not_compensated_internal_transfers() {
+        let report = ReconciliationReport::new(
+            chrono::Local::now().to_utc(),
+            vec![usd_account("00000000-0000-0000-0000-000000000101", "150", "150")],
+            usd_flows("-0.01"),
+        );
+        assert!(!report.ok);
+        assert!(report.mismatched_accounts.is_empty());
+        assert!(!report.currency_totals[0].conserved);
+    }
+
+    #[test]
+    fn report_to_json() {
+        let report = ReconciliationReport::new(
+            chrono::DateTime::<chrono::FixedOffset>::from_str("2024-05-30 22:29:57 +02:00").test_unwrap().to_utc(),
+            vec![usd_account("00000000-0000-0000-0000-000000000101", "150", "140")],
+            usd_flows("0"),
+        );
+
+        let json = report.to_json().test_unwrap();
+        assert_eq!(serde_json::Value::from_str(&json).test_unwrap(), serde_json::json!({
+            "checkedAt": "2024-05-30T20:29:57Z",
+            "ok": false,
+            "accountsChecked": 1,
+            "mismatchedAccounts": [{
+                "accountId": "00000000-0000-0000-0000-000000000101",
+                "clientId": "00000000-0000-0000-0000-000000000001",
+                "currency": "USD",
+                "storedBalance": 150,
+                "ledgerBalance": 140,
+                "ledgerEntries": 2,
+                "brokenEntries": 0,
+                "foreignCurrencyEntries": 0,
+                "drift": 10,
+            }],
+            "currencyTotals": [{
+                "currency": "USD",
+                "accounts": 1,
+                "storedTotal": 150,
+                "ledgerTotal": 140,
+                "drift": 10,
+                "internalFlow": 0,
+                "externalFlow": 1.25,
+                "exchangeFlow": 0,
+                "conserved": false,
+            }],
+        }));
+    }
+}
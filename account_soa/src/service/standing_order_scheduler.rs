@@ -0,0 +1,252 @@
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::Utc;
+use log::{ debug, error, info, warn };
+use crate::entity::{
+    StandingOrder, TransferId,
+    idempotency::{ Idempotency, IdempotencyKey },
+};
+use crate::service::{
+    account_service::{ AccountIdWrapper, AccountProcessError, AccountService },
+    background::{ spawn_periodic_task, truncate_error, RetryBackoff },
+};
+//--------------------------------------------------------------------------------------------------
+
+
+
+/// Key of DB (session) advisory lock, only one replica executes standing orders at a time.
+/// It is "STORDERS" as ASCII.
+pub const STANDING_ORDERS_ADVISORY_LOCK_KEY: i64 = 0x5354_4F52_4445_5253;
+
+pub const DEFAULT_SCHEDULER_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Max count of orders executed by one scheduler run (the rest is executed by next runs).
+const MAX_ORDERS_PER_RUN: i64 = 100;
+
+/// Max count of attempts of one execution (if there is not enough balance),
+/// after that this execution is skipped and order waits for the next one.
+pub const MAX_EXECUTION_ATTEMPTS: u32 = 5;
+
+const RETRY_BACKOFF: RetryBackoff = RetryBackoff {
+    base_delay: chrono::Duration::minutes(10),
+    max_delay: chrono::Duration::hours(6),
+};
+
+
+/// Executes due standing orders by AccountService::transfer().
+///
+/// Every execution uses idempotency key based on order ID and execution time,
+/// so if server is stopped after transfer, but before saving order state,
+/// the same execution is not repeated after restart.
+pub struct StandingOrderScheduler <AccountS: AccountService + Send + Sync + 'static> {
+    pub database_connection: Arc<sqlx_postgres::PgPool>,
+    pub account_service: Arc<AccountS>,
+}
+
+/// Outcome of one order execution.
+enum ExecutionResult {
+    Done(TransferId),
+    /// Not enough balance, it will be repeated later.
+    Retry(AccountProcessError),
+    /// Execution is skipped, order waits for the next one.
+    Skipped(AccountProcessError),
+}
+
+
+impl <AccountS: AccountService + Send + Sync + 'static> StandingOrderScheduler<AccountS> {
+
+    pub fn start(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        spawn_periodic_task("standing orders scheduler", interval, move || {
+            let scheduler = Arc::clone(&self);
+            async move {
+                match scheduler.run_due_orders().await {
+                    Ok(0) => {}
+                    Ok(executed) => info!("### {executed} standing orders are processed"),
+                    Err(err) => error!("### Error of processing standing orders: {err:?}"),
+                }
+            }
+        })
+    }
+
+    /// Returns count of processed orders (0 if other replica holds the lock).
+    pub async fn run_due_orders(&self) -> Result<usize, AccountProcessError> {
+
+        // Session advisory lock is bound to connection, so the same connection should be used to unlock.
+        // If the process is killed, connection is closed and lock is released by DB.
+        let mut lock_con = self.database_connection.acquire().await ?;
+        let locked: bool = sqlx::query_scalar("select pg_try_advisory_lock($1)")
+            .bind(STANDING_ORDERS_ADVISORY_LOCK_KEY)
+            .fetch_one(&mut *lock_con)
+            .await ?;
+        if !locked {
+            debug!("### Standing orders are processed by another replica");
+            return Ok(0);
+        }
+
+        let run_res = self.run_due_orders_impl().await;
+
+        let unlock_res: Result<bool, sqlx::Error> = sqlx::query_scalar("select pg_advisory_unlock($1)")
+            .bind(STANDING_ORDERS_ADVISORY_LOCK_KEY)
+            .fetch_one(&mut *lock_con)
+            .await;
+        if let Err(err) = unlock_res {
+            warn!("### Error of releasing standing orders lock ({err:?}), connection is closed");
+            // Closing connection releases the lock.
+            drop(lock_con.detach());
+        }
+
+        run_res
+    }
+
+    async fn run_due_orders_impl(&self) -> Result<usize, AccountProcessError> {
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+
+        let orders: Vec<StandingOrder> = sqlx::query_as(
+            "select \
+                 ID, CLIENT_ID, FROM_ACCOUNT_ID, TO_IBAN, BENEFICIARY_NAME, AMOUNT, CUR, \
+                 PERIOD, START_AT, NEXT_EXECUTION_NO, NEXT_EXECUTION_AT, FAILED_ATTEMPTS, RETRY_AT, \
+                 LAST_EXECUTED_AT, LAST_TRANSFER_ID, LAST_ERROR, CREATED_AT, UPDATED_AT \
+                 from STANDING_ORDERS \
+                 where coalesce(RETRY_AT, NEXT_EXECUTION_AT) <= $1 \
+                 order by coalesce(RETRY_AT, NEXT_EXECUTION_AT) \
+                 limit $2 ")
+            .bind(&now)
+            .bind(MAX_ORDERS_PER_RUN)
+            .fetch_all(&*self.database_connection)
+            .await ?;
+
+        let orders_count = orders.len();
+        for order in orders {
+            let order_id = order.id.clone();
+            // One broken order should not block others.
+            if let Err(err) = self.execute_order(order).await {
+                error!("### Error of executing standing order [{order_id}]: {err:?}");
+            }
+        }
+        Ok(orders_count)
+    }
+
+    async fn execute_order(&self, order: StandingOrder) -> Result<(), AccountProcessError> {
+        use core::str::FromStr;
+
+        debug!("### Executing standing order [{}] (execution {})", order.id, order.next_execution_no);
+
+        let idempotency_key = format!("standing-order-{}-{}", order.id, order.next_execution_at.timestamp());
+        let idempotency = Idempotency {
+            key: IdempotencyKey::from_str(&idempotency_key)
+                .map_err(|err| AccountProcessError::Internal(anyhow::anyhow!(err))) ?,
+            request_hash: order_request_hash(&order),
+        };
+
+        let transfer_res = self.account_service.transfer(
+            order.client_id.clone(),
+            AccountIdWrapper::Id(order.from_account_id.clone()),
+            AccountIdWrapper::Iban(order.to_iban.clone()),
+            order.beneficiary_name.clone(),
+            order.amount.with_value(order.amount.value_ref().clone()),
+            None,
+            Some(idempotency),
+        ).await;
+
+        let execution_res = match transfer_res {
+            Ok(transfer_id) =>
+                ExecutionResult::Done(transfer_id),
+            Err(err @ AccountProcessError::NotEnoughBalance(..))
+                if order.failed_attempts + 1 < MAX_EXECUTION_ATTEMPTS =>
+                ExecutionResult::Retry(err),
+            Err(err @ (AccountProcessError::Sqlx(..) | AccountProcessError::Internal(..)
+                     | AccountProcessError::ExchangeRateError(..))) =>
+                // Infrastructure problem, order state is not changed, and it is repeated by next run.
+                return Err(err),
+            Err(err) =>
+                ExecutionResult::Skipped(err),
+        };
+
+        self.save_execution_result(&order, execution_res).await
+    }
+
+    async fn save_execution_result(&self, order: &StandingOrder, execution_res: ExecutionResult)
+        -> Result<(), AccountProcessError> {
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+
+        let (failed_attempts, retry_at, next_execution_no, transfer_id, last_error) = match execution_res {
+            ExecutionResult::Done(transfer_id) => {
+                info!("### Standing order [{}] is executed (transfer [{transfer_id}])", order.id);
+                (0, None, order.next_execution_no + 1, Some(transfer_id), None)
+            }
+            ExecutionResult::Retry(err) => {
+                let failed_attempts = order.failed_attempts + 1;
+                let retry_at = now + RETRY_BACKOFF.delay(failed_attempts);
+                info!("### Standing order [{}] is failed ({err}), it will be repeated at {retry_at}", order.id);
+                (failed_attempts, Some(retry_at), order.next_execution_no, None, Some(err.to_string()))
+            }
+            ExecutionResult::Skipped(err) => {
+                warn!("### Standing order [{}] execution {} is skipped ({err})", order.id, order.next_execution_no);
+                (0, None, order.next_execution_no + 1, None, Some(err.to_string()))
+            }
+        };
+
+        let next_execution_at = order.period.execution_at(order.start_at, next_execution_no)
+            .ok_or_else(|| AccountProcessError::Internal(anyhow::anyhow!(
+                "Error of calculating next execution time of standing order [{}].", order.id))) ?;
+        let executed = transfer_id.is_some();
+        let last_error = last_error.map(truncate_error);
+
+        // Order could be changed/deleted by client during execution, then state is not updated
+        // (changed order is started from its new schedule).
+        let update_res = sqlx::query(
+            " update STANDING_ORDERS \
+                 set NEXT_EXECUTION_NO = $3, NEXT_EXECUTION_AT = $4, FAILED_ATTEMPTS = $5, RETRY_AT = $6, \
+                     LAST_EXECUTED_AT = case when $7 then $9 else LAST_EXECUTED_AT end, \
+                     LAST_TRANSFER_ID = case when $7 then $8 else LAST_TRANSFER_ID end, \
+                     LAST_ERROR = $10, UPDATED_AT = $9 \
+                 where ID = $1 and NEXT_EXECUTION_NO = $2 and START_AT = $11 ")
+            .bind(&order.id)
+            .bind(i64::from(order.next_execution_no))
+            .bind(i64::from(next_execution_no))
+            .bind(&next_execution_at)
+            .bind(i64::from(failed_attempts))
+            .bind(retry_at)
+            .bind(executed)
+            .bind(transfer_id)
+            .bind(&now)
+            .bind(last_error)
+            .bind(&order.start_at)
+            .execute(&*self.database_connection)
+            .await ?;
+
+        if update_res.rows_affected() == 0 {
+            warn!("### Standing order [{}] was changed during execution", order.id);
+        }
+        Ok(())
+    }
+}
+
+
+/// SHA-256 (hex) of transfer parameters of order (used for idempotency key validation).
+fn order_request_hash(order: &StandingOrder) -> String {
+    use sha2::{ Digest, Sha256 };
+
+    let normalized = format!("{}\n{}\n{}\n{}",
+        order.from_account_id, order.to_iban, order.amount,
+        order.beneficiary_name.as_deref().unwrap_or_default(),
+    );
+    hex::encode(Sha256::digest(normalized.as_bytes()))
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::RETRY_BACKOFF;
+
+    #[test]
+    fn retry_delay_is_exponential_and_limited() {
+        assert_eq!(RETRY_BACKOFF.delay(1), chrono::Duration::minutes(10));
+        assert_eq!(RETRY_BACKOFF.delay(2), chrono::Duration::minutes(20));
+        assert_eq!(RETRY_BACKOFF.delay(3), chrono::Duration::minutes(40));
+        assert_eq!(RETRY_BACKOFF.delay(10), RETRY_BACKOFF.max_delay);
+        assert_eq!(RETRY_BACKOFF.delay(100), RETRY_BACKOFF.max_delay);
+    }
+}
@@ -0,0 +1,528 @@
+use std::net::{ IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr };
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::Utc;
+use log::{ debug, error, info, warn };
+use mvv_common::{
+    cfg::{ load_optional_path_from_env_vars, SslConfValue, client::to_reqwest_tls_cert },
+    env::env_var_static,
+};
+use crate::entity::webhook::WebhookDeliveryStatus;
+use crate::service::{
+    account_service::AccountProcessError,
+    background::{ spawn_periodic_task, truncate_error, RetryBackoff },
+};
+//--------------------------------------------------------------------------------------------------
+
+
+
+pub const DEFAULT_DELIVERY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Max count of deliveries sent by one worker run (the rest is sent by next runs).
+const MAX_DELIVERIES_PER_RUN: i64 = 50;
+
+/// After this count of failed attempts delivery becomes DEAD (dead letter).
+pub const MAX_DELIVERY_ATTEMPTS: u32 = 10;
+
+const RETRY_BACKOFF: RetryBackoff = RetryBackoff {
+    base_delay: chrono::Duration::seconds(30),
+    max_delay: chrono::Duration::hours(6),
+};
+
+/// Claimed delivery is not taken by other worker (replica) during this time.
+/// If worker is stopped during sending, delivery is sent again after it.
+const DELIVERY_LEASE: chrono::Duration = chrono::Duration::minutes(5);
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+pub const EVENT_ID_HEADER: &str = "X-Webhook-Event-Id";
+pub const EVENT_TYPE_HEADER: &str = "X-Webhook-Event-Type";
+
+
+/// HTTP client of webhook event sink.
+/// Additional CA certificate (for example, of test environment) can be set by
+/// ACCOUNT_SOA_WEBHOOK_CA_SSL_CERT_PATH.
+pub fn create_webhook_http_client() -> anyhow::Result<reqwest::Client> {
+    Ok(webhook_http_client_builder() ?.build() ?)
+}
+
+/// HTTP client of client webhook subscriptions. Domain of subscription URL is resolved only to public addresses
+/// (see PublicAddressResolver), if it is not allowed by ACCOUNT_SOA_WEBHOOK_ALLOW_LOCAL_URLS=true.
+pub fn create_webhook_delivery_http_client() -> anyhow::Result<reqwest::Client> {
+    let mut client = webhook_http_client_builder() ?;
+    if !is_local_webhook_urls_allowed() ? {
+        client = client.dns_resolver(Arc::new(PublicAddressResolver));
+    }
+    Ok(client.build() ?)
+}
+
+fn webhook_http_client_builder() -> anyhow::Result<reqwest::ClientBuilder> {
+    let mut client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        // Redirect (even from public host) could lead to internal address, 3xx response is failed delivery.
+        .redirect(reqwest::redirect::Policy::none());
+
+    let ca_cert = load_optional_path_from_env_vars(["ACCOUNT_SOA_WEBHOOK_CA_SSL_CERT_PATH"]) ?
+        .map(SslConfValue::Path);
+    if let Some(cert) = to_reqwest_tls_cert(ca_cert.as_ref()) ? {
+        client = client.add_root_certificate(cert);
+    }
+    Ok(client)
+}
+
+
+/// Webhook URL of client subscription should be HTTPS URL of public host
+/// (loopback, private, link-local and other special addresses are rejected to prevent SSRF).
+///
+/// ACCOUNT_SOA_WEBHOOK_ALLOW_LOCAL_URLS=true allows HTTP and any host (only for tests/local development).
+pub fn validate_webhook_url(url: &str) -> anyhow::Result<()> {
+    validate_webhook_url_impl(url, is_local_webhook_urls_allowed() ?)
+}
+
+fn is_local_webhook_urls_allowed() -> anyhow::Result<bool> {
+    Ok(env_var_static("ACCOUNT_SOA_WEBHOOK_ALLOW_LOCAL_URLS") ?
+        .map(|value| value.parse::<bool>())
+        .transpose() ?
+        .unwrap_or(false))
+}
+
+fn validate_webhook_url_impl(url: &str, allow_local: bool) -> anyhow::Result<()> {
+    use url::Host;
+
+    let url = url::Url::parse(url)
+        .map_err(|err| anyhow::anyhow!("Incorrect webhook URL ({err})."))?;
+
+    if allow_local {
+        if !matches!(url.scheme(), "http" | "https") || !url.has_host() {
+            anyhow::bail!("Webhook URL should be HTTP(S) URL.");
+        }
+        return Ok(());
+    }
+
+    if url.scheme() != "https" {
+        anyhow::bail!("Webhook URL should be HTTPS URL.");
+    }
+    let is_public_host = match url.host() {
+        None => false,
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            domain != "localhost" && !domain.ends_with(".localhost")
+        }
+        Some(Host::Ipv4(ip)) => is_public_ip(IpAddr::V4(ip)),
+        Some(Host::Ipv6(ip)) => is_public_ip(IpAddr::V6(ip)),
+    };
+    if !is_public_host {
+        anyhow::bail!("Webhook URL should have public host.");
+    }
+    Ok(())
+}
+
+/// IP address of subscription URL is checked just before sending (for URL registered before ACCOUNT_SOA_WEBHOOK_ALLOW_LOCAL_URLS
+/// is switched off). Domain name is checked by PublicAddressResolver during connection.
+fn validate_webhook_host_address(url: &str) -> anyhow::Result<()> {
+    use url::Host;
+
+    if is_local_webhook_urls_allowed() ? {
+        return Ok(());
+    }
+
+    let url = url::Url::parse(url) ?;
+    let ip = match url.host() {
+        None => anyhow::bail!("Webhook URL does not have host."),
+        Some(Host::Domain(_)) => return Ok(()),
+        Some(Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(Host::Ipv6(ip)) => IpAddr::V6(ip),
+    };
+    if !is_public_ip(ip) {
+        anyhow::bail!("Webhook host is not public address [{ip}].");
+    }
+    Ok(())
+}
+
+/// Domain name of subscription URL can be resolved to internal address (it is changed by DNS owner at any time),
+/// so addresses are checked by resolver of HTTP client. Connection is done to the checked addresses
+/// (separate lookup before sending would not prevent DNS rebinding).
+struct PublicAddressResolver;
+
+impl reqwest::dns::Resolve for PublicAddressResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(resolve_public_addresses(name.as_str().to_owned()))
+    }
+}
+
+async fn resolve_public_addresses(host: String)
+    -> Result<reqwest::dns::Addrs, Box<dyn std::error::Error + Send + Sync>> {
+
+    // Port is set by HTTP client.
+    let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0)).await ?.collect();
+
+    if let Some(address) = addresses.iter().find(|address| !is_public_ip(address.ip())) {
+        return Err(anyhow::anyhow!("Webhook host [{host}] is resolved to not public address [{}].", address.ip()).into());
+    }
+    Ok(Box::new(addresses.into_iter()))
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ipv4) => is_public_ipv4(ipv4),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // 0.0.0.0/8 ('this' network)
+        || a == 0
+        // 100.64.0.0/10 (shared address space, carrier-grade NAT)
+        || (a == 100 && (b & 0b1100_0000) == 64)
+        // 240.0.0.0/4 (reserved)
+        || a >= 240)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first_segment = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // fc00::/7 (unique local)
+        || (first_segment & 0xfe00) == 0xfc00
+        // fe80::/10 (link-local)
+        || (first_segment & 0xffc0) == 0xfe80
+        // 2001:db8::/32 (documentation)
+        || (first_segment == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+
+/// Signature header value: 'v1=' + hex(HMAC-SHA256(secret, '{timestamp}.{payload}')).
+/// Receiver should verify it and reject old timestamps (to prevent replay).
+pub fn webhook_signature(secret: &str, timestamp: i64, payload: &str) -> String {
+    format!("v1={}", hmac_sha256_hex(secret.as_bytes(), format!("{timestamp}.{payload}").as_bytes()))
+}
+
+fn hmac_sha256_hex(key: &[u8], message: &[u8]) -> String {
+    use hmac::{ Hmac, Mac };
+    // HMAC accepts a key of any length, so it cannot fail.
+    let mut mac = <Hmac<sha2::Sha256> as Mac>::new_from_slice(key)
+        .expect("HMAC key of any length");
+    mac.update(message);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+
+/// Delivery claimed by worker.
+struct PendingDelivery {
+    id: uuid::Uuid,
+    event_id: uuid::Uuid,
+    event_type: String,
+    payload: String,
+    attempts: u32,
+    url: String,
+    secret: mvv_common::secure::SecureString,
+}
+
+struct AttemptResult {
+    response_status: Option<u16>,
+    error: Option<String>,
+    duration_ms: u32,
+}
+
+
+/// Sends webhook deliveries (created by AccountServiceImpl in transaction of transfer).
+///
+/// Deliveries are claimed (leased) by one short DB transaction and sent without open transaction,
+/// so several replicas can run worker. Delivery is at-least-once, receivers should skip duplicates
+/// by event ID. Order of deliveries is not guaranteed (failed delivery does not block others).
+pub struct WebhookDeliveryWorker {
+    pub database_connection: Arc<sqlx_postgres::PgPool>,
+    pub client: reqwest::Client,
+}
+
+impl WebhookDeliveryWorker {
+
+    pub fn start(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        spawn_periodic_task("webhook delivery worker", interval, move || {
+            let worker = Arc::clone(&self);
+            async move {
+                match worker.deliver_pending().await {
+                    Ok(0) => {}
+                    Ok(processed) => info!("### {processed} webhook deliveries are processed"),
+                    Err(err) => error!("### Error of sending webhook deliveries: {err:?}"),
+                }
+            }
+        })
+    }
+
+    /// Returns count of processed (successful or failed) deliveries.
+    pub async fn deliver_pending(&self) -> Result<usize, AccountProcessError> {
+        let deliveries = self.claim_deliveries().await ?;
+
+        let deliveries_count = deliveries.len();
+        for delivery in deliveries {
+            let delivery_id = delivery.id;
+            // One broken delivery should not block others.
+            if let Err(err) = self.deliver(delivery).await {
+                error!("### Error of processing webhook delivery [{delivery_id}]: {err:?}");
+            }
+        }
+        Ok(deliveries_count)
+    }
+
+    async fn claim_deliveries(&self) -> Result<Vec<PendingDelivery>, AccountProcessError> {
+        use sqlx::Row;
+        use mvv_common::pg_column_name as col_name;
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+
+        let rows = sqlx::query(
+            " update WEBHOOK_DELIVERIES D \
+                 set NEXT_ATTEMPT_AT = $2 \
+                 from WEBHOOK_SUBSCRIPTIONS S \
+                 where S.ID = D.SUBSCRIPTION_ID and D.ID in ( \
+                     select ID from WEBHOOK_DELIVERIES \
+                     where STATUS = 'PENDING' and NEXT_ATTEMPT_AT <= $1 \
+                     order by NEXT_ATTEMPT_AT \
+                     limit $3 \
+                     for update skip locked) \
+                 returning D.ID, D.EVENT_ID, D.EVENT_TYPE, D.PAYLOAD::text as PAYLOAD, D.ATTEMPTS, S.URL, S.SECRET ")
+            .bind(&now)
+            .bind(&(now + DELIVERY_LEASE))
+            .bind(MAX_DELIVERIES_PER_RUN)
+            .fetch_all(&*self.database_connection)
+            .await ?;
+
+        let deliveries = rows.iter()
+            .map(|row| Ok::<_, sqlx::Error>(PendingDelivery {
+                id: row.try_get(col_name!("ID")) ?,
+                event_id: row.try_get(col_name!("EVENT_ID")) ?,
+                event_type: row.try_get(col_name!("EVENT_TYPE")) ?,
+                payload: row.try_get(col_name!("PAYLOAD")) ?,
+                attempts: u32::try_from(row.try_get::<i32,_>(col_name!("ATTEMPTS")) ?)
+                    .map_err(|err| sqlx::Error::Decode(Box::new(err))) ?,
+                url: row.try_get(col_name!("URL")) ?,
+                secret: mvv_common::secure::SecureString::from_string(row.try_get(col_name!("SECRET")) ?),
+            }))
+            .collect::<Result<Vec<_>, _>>() ?;
+        Ok(deliveries)
+    }
+
+    async fn deliver(&self, delivery: PendingDelivery) -> Result<(), AccountProcessError> {
+        debug!("### Sending webhook delivery [{}] to [{}]", delivery.id, delivery.url);
+
+        let attempted_at: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        let attempt = self.send(&delivery, attempted_at).await;
+        let attempts = delivery.attempts + 1;
+
+        let now: chrono::DateTime<Utc> = chrono::Local::now().to_utc();
+        let (status, next_attempt_at) = match attempt.error {
+            None => (WebhookDeliveryStatus::Delivered, now),
+            Some(ref err) => {
+                let (status, next_attempt_at) = failed_delivery_state(attempts, now);
+                warn!("### Webhook delivery [{}] is failed ({err}), status: {status}, next attempt at {next_attempt_at}",
+                    delivery.id);
+                (status, next_attempt_at)
+            }
+        };
+        let delivered_at = (status == WebhookDeliveryStatus::Delivered).then_some(now);
+        let error = attempt.error.map(truncate_error);
+
+        let mut tx = self.database_connection.begin().await ?;
+
+        sqlx::query(
+            " insert into WEBHOOK_DELIVERY_ATTEMPTS \
+                 (DELIVERY_ID, ATTEMPT_NO, ATTEMPTED_AT, RESPONSE_STATUS, ERROR, DURATION_MS) \
+                 values ($1, $2, $3, $4, $5, $6) ")
+            .bind(delivery.id)
+            .bind(i64::from(attempts))
+            .bind(&attempted_at)
+            .bind(attempt.response_status.map(i32::from))
+            .bind(&error)
+            .bind(i64::from(attempt.duration_ms))
+            .execute(&mut *tx)
+            .await ?;
+
+        sqlx::query(
+            " update WEBHOOK_DELIVERIES \
+                 set STATUS = $2, ATTEMPTS = $3, NEXT_ATTEMPT_AT = $4, LAST_ERROR = $5, \
+                     DELIVERED_AT = $6, UPDATED_AT = $7 \
+                 where ID = $1 ")
+            .bind(delivery.id)
+            .bind(status)
+            .bind(i64::from(attempts))
+            .bind(&next_attempt_at)
+            .bind(&error)
+            .bind(delivered_at)
+            .bind(&now)
+            .execute(&mut *tx)
+            .await ?;
+
+        tx.commit().await ?;
+        Ok(())
+    }
+
+    async fn send(&self, delivery: &PendingDelivery, attempted_at: chrono::DateTime<Utc>) -> AttemptResult {
+        if let Err(err) = validate_webhook_host_address(&delivery.url) {
+            return AttemptResult { response_status: None, error: Some(err.to_string()), duration_ms: 0 };
+        }
+
+        let timestamp = attempted_at.timestamp();
+        let signature = webhook_signature(delivery.secret.as_str(), timestamp, &delivery.payload);
+
+        let started = std::time::Instant::now();
+        let response = self.client.post(&delivery.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_ID_HEADER, delivery.event_id.to_string())
+            .header(EVENT_TYPE_HEADER, delivery.event_type.as_str())
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, signature)
+            .body(delivery.payload.clone())
+            .send()
+            .await;
+        let duration_ms = u32::try_from(started.elapsed().as_millis()).unwrap_or(u32::MAX);
+
+        match response {
+            Ok(response) => {
+                let status = response.status();
+                AttemptResult {
+                    response_status: Some(status.as_u16()),
+                    error: (!status.is_success()).then(|| format!("Response status {status}.")),
+                    duration_ms,
+                }
+            }
+            Err(err) => AttemptResult { response_status: None, error: Some(err.to_string()), duration_ms },
+        }
+    }
+}
+
+
+/// State of delivery after failed attempt: DEAD after MAX_DELIVERY_ATTEMPTS,
+/// otherwise it is retried with exponential backoff.
+fn failed_delivery_state(attempts: u32, now: chrono::DateTime<Utc>)
+    -> (WebhookDeliveryStatus, chrono::DateTime<Utc>) {
+    if attempts >= MAX_DELIVERY_ATTEMPTS {
+        (WebhookDeliveryStatus::Dead, now)
+    } else {
+        (WebhookDeliveryStatus::Pending, now + RETRY_BACKOFF.delay(attempts))
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::webhook::WebhookDeliveryStatus;
+    use mvv_auth::util::test_unwrap::TestResultUnwrap;
+    use super::{
+        create_webhook_http_client, failed_delivery_state, hmac_sha256_hex, resolve_public_addresses,
+        validate_webhook_url_impl, webhook_signature, MAX_DELIVERY_ATTEMPTS, RETRY_BACKOFF,
+    };
+
+    #[test]
+    fn webhook_url_should_be_https_url_of_public_host() {
+        assert!(validate_webhook_url_impl("https://bank-client.com/webhook", false).is_ok());
+        assert!(validate_webhook_url_impl("https://8.8.8.8:8443/webhook", false).is_ok());
+        assert!(validate_webhook_url_impl("https://[2a00:1450:4001:81b::200e]/webhook", false).is_ok());
+
+        assert!(validate_webhook_url_impl("http://bank-client.com/webhook", false).is_err());
+        assert!(validate_webhook_url_impl("ftp://bank-client.com/webhook", false).is_err());
+        assert!(validate_webhook_url_impl("not-url", false).is_err());
+
+        for url in [
+            "https://localhost/webhook", "https://api.localhost./webhook",
+            "https://127.0.0.1/webhook", "https://0.0.0.0/webhook",
+            "https://10.1.2.3/webhook", "https://172.16.0.1/webhook", "https://192.168.1.1/webhook",
+            // cloud metadata
+            "https://169.254.169.254/latest/meta-data",
+            "https://100.64.0.1/webhook",
+            "https://[::1]/webhook", "https://[fd00::1]/webhook", "https://[fe80::1]/webhook",
+            "https://[::ffff:127.0.0.1]/webhook",
+        ] {
+            assert!(validate_webhook_url_impl(url, false).is_err(), "URL [{url}] should be rejected");
+        }
+    }
+
+    #[test]
+    fn local_webhook_url_can_be_allowed() {
+        assert!(validate_webhook_url_impl("http://localhost:8080/bank/webhook", true).is_ok());
+        assert!(validate_webhook_url_impl("https://127.0.0.1/webhook", true).is_ok());
+        assert!(validate_webhook_url_impl("ftp://localhost/webhook", true).is_err());
+    }
+
+    #[tokio::test]
+    async fn host_is_resolved_only_to_public_addresses() {
+        assert!(resolve_public_addresses("8.8.8.8".to_owned()).await.is_ok());
+
+        for host in ["localhost", "127.0.0.1", "169.254.169.254", "::1"] {
+            assert!(resolve_public_addresses(host.to_owned()).await.is_err(), "Host [{host}] should be rejected");
+        }
+    }
+
+    #[tokio::test]
+    async fn redirect_is_not_followed() {
+        use std::sync::Arc;
+        use std::sync::atomic::{ AtomicBool, Ordering };
+        use axum::{ http::StatusCode, response::Redirect, routing::post };
+
+        let redirected = Arc::new(AtomicBool::new(false));
+        let redirect_target_hit = Arc::clone(&redirected);
+        let app = axum::Router::new()
+            .route("/webhook", post(|| async { Redirect::temporary("/internal") }))
+            .route("/internal", post(move || async move {
+                redirect_target_hit.store(true, Ordering::SeqCst);
+                StatusCode::OK
+            }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.test_unwrap();
+        let port = listener.local_addr().test_unwrap().port();
+        let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let response = create_webhook_http_client().test_unwrap()
+            .post(format!("http://127.0.0.1:{port}/webhook"))
+            .send().await.test_unwrap();
+        server.abort();
+
+        assert_eq!(response.status().as_u16(), 307);
+        assert!(!response.status().is_success());
+        assert!(!redirected.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn hmac_sha256() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+        );
+    }
+
+    #[test]
+    fn signature_depends_on_secret_timestamp_and_payload() {
+        let signature = webhook_signature("secret", 1717000000, r#"{"type":"TRANSFER_INCOMING"}"#);
+        assert!(signature.starts_with("v1="));
+        assert_eq!(signature.len(), 3 + 64);
+        assert_eq!(signature, format!("v1={}",
+            hmac_sha256_hex(b"secret", br#"1717000000.{"type":"TRANSFER_INCOMING"}"#)));
+
+        assert_ne!(signature, webhook_signature("secret2", 1717000000, r#"{"type":"TRANSFER_INCOMING"}"#));
+        assert_ne!(signature, webhook_signature("secret", 1717000001, r#"{"type":"TRANSFER_INCOMING"}"#));
+        assert_ne!(signature, webhook_signature("secret", 1717000000, r#"{"type":"TRANSFER_OUTGOING"}"#));
+    }
+
+    #[test]
+    fn delivery_is_dead_after_max_attempts() {
+        let now = chrono::Local::now().to_utc();
+        assert_eq!(failed_delivery_state(1, now), (WebhookDeliveryStatus::Pending, now + RETRY_BACKOFF.base_delay));
+        assert_eq!(failed_delivery_state(MAX_DELIVERY_ATTEMPTS - 1, now).0, WebhookDeliveryStatus::Pending);
+        assert_eq!(failed_delivery_state(MAX_DELIVERY_ATTEMPTS, now), (WebhookDeliveryStatus::Dead, now));
+    }
+}
@@ -0,0 +1,31 @@
+#!/bin/bash
+set -e
+
+# Applies account_soa migrations (mounted to /migrations) before test data is inserted
+# and registers them in _sqlx_migrations (the same way as 'mvv_account_soa migrate up' does),
+# so application sees test DB as already migrated.
+
+psql -v ON_ERROR_STOP=1 --username "$POSTGRES_USER" --dbname "$POSTGRES_DB" <<-EOSQL
+    create table if not exists _sqlx_migrations (
+        version        BIGINT      primary key,
+        description    TEXT        not null,
+        installed_on   TIMESTAMPTZ not null default now(),
+        success        BOOLEAN     not null,
+        checksum       BYTEA       not null,
+        execution_time BIGINT      not null
+    );
+EOSQL
+
+for migration in /migrations/*.sql; do
+    file_name=$(basename "$migration" .sql)
+    version=$((10#${file_name%%_*}))
+    description=${file_name#*_}
+    description=${description//_/ }
+    checksum=$(sha384sum "$migration" | cut -d ' ' -f 1)
+
+    echo "Applying migration $version ($description)"
+    psql -v ON_ERROR_STOP=1 --single-transaction --username "$POSTGRES_USER" --dbname "$POSTGRES_DB" \
+        -f "$migration" \
+        -c "insert into _sqlx_migrations (version, description, success, checksum, execution_time) \
+                values ($version, '$description', true, decode('$checksum', 'hex'), 0)"
+done
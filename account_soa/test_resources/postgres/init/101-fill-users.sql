@@ -11,6 +11,7 @@ insert into USERS (ID, NAME, PASSWORD, PSW_HASH) values (24, 'vovan-read-write',
 insert into USERS (ID, NAME, PASSWORD, PSW_HASH) values (25, 'vovan-user', 'qwerty', '$argon2d$v=16$m=19456,t=2,p=1$1jd9ljbST1E/CsGOiBOsOg$4NUBW5Rf7Cm/E/+YtoQw2Vg3270pO5EWufqo3qmS7oE');
 insert into USERS (ID, NAME, PASSWORD, PSW_HASH) values (26, 'vovan-super-user', 'qwerty', '$argon2d$v=16$m=19456,t=2,p=1$EUXO4jWOfnouAFF+CipWgQ$TleOX6tIVbCkynIGptjzRTcFVSbS2K3tZcmPckwJVt8');
 insert into USERS (ID, NAME, PASSWORD, PSW_HASH) values (27, 'vovan-admin', 'qwerty', '$argon2d$v=16$m=19456,t=2,p=1$66zW697+kjcdOfLDOs6GGA$76kr0gFz1xQ6o2bpTqwoetOn0RoJ7QrSxRoMwLkA0xg');
+insert into USERS (ID, NAME, PASSWORD, PSW_HASH) values (28, 'vovan-read-super-user', 'qwerty', '$argon2d$v=16$m=19456,t=2,p=1$1jd9ljbST1E/CsGOiBOsOg$4NUBW5Rf7Cm/E/+YtoQw2Vg3270pO5EWufqo3qmS7oE');
 
 
 insert into USER_ROLES (USER_ID, READ_ROLE)  values (22, 'y');
@@ -19,3 +20,5 @@ insert into USER_ROLES (USER_ID, READ_ROLE, WRITE_ROLE) values (24, 'y', 'y');
 --insert into USER_ROLES (USER_ID, USER_ROLE) values (25, 'y');
 insert into USER_ROLES (USER_ID, SUPER_USER_ROLE) values (26, 'y');
 insert into USER_ROLES (USER_ID, ADMIN_ROLE) values (27, 'y');
+-- Maker and checker at once (used to check that creator of pending transfer cannot approve it).
+insert into USER_ROLES (USER_ID, READ_ROLE, SUPER_USER_ROLE) values (28, 'y', 'y');
@@ -14,3 +14,7 @@ insert into CLIENTS (CLIENT_ID, EMAIL, ACTIVE, PHONE, PHONE_TYPE, BUSINESS_USER,
 values ('00000000-0000-0000-0000-000000000003', 'puh@ukr.net', 'y', '+380671234569', 'B', 'y', 'y', 'Puh', 'Bodya', DATE '1995-09-01');
 insert into CLIENTS_CREDS (CLIENT_ID, PSW, PSW_HASH)
 values ('00000000-0000-0000-0000-000000000003', 'qwerty', '$argon2d$v=16$m=19456,t=2,p=1$EUXO4jWOfnouAFF+CipWgQ$TleOX6tIVbCkynIGptjzRTcFVSbS2K3tZcmPckwJVt8');
+
+-- Bank itself (owner of internal bank accounts, for example, fee accounts). It cannot log in.
+insert into CLIENTS (CLIENT_ID, EMAIL, ACTIVE, PHONE, PHONE_TYPE, BUSINESS_USER, FIRST_NAME, LAST_NAME, BIRTHDAY)
+values ('00000000-0000-0000-0000-000000000900', 'bank@mvv-bank.com', 'n', '+380440000000', 'W', 'y', 'MVV', 'Bank', DATE '1991-08-24');
@@ -15,3 +15,40 @@ values ('00000000-0000-0000-0000-000000000103', 'UA565117374274826517545533479',
 insert into ACCOUNTS (ID, IBAN, CLIENT_ID, NAME, CREATED_AT, AMOUNT, CUR, UPDATED_AT)
 values ('00000000-0000-0000-0000-000000000104', 'UA496826153843944716538382928', '00000000-0000-0000-0000-000000000001',
  'UAH account 2', TIMESTAMP '2021-11-13 10:00:00', 2000.0, 'UAH', TIMESTAMP '2021-11-15 15:00:00');
+
+insert into ACCOUNTS (ID, IBAN, CLIENT_ID, NAME, CREATED_AT, AMOUNT, CUR, UPDATED_AT)
+values ('00000000-0000-0000-0000-000000000201', 'UA853052990000000000000002011', '00000000-0000-0000-0000-000000000002',
+ 'USD account 1', TIMESTAMP '2022-03-01 09:00:00', 100.0, 'USD', TIMESTAMP '2022-03-01 09:00:00');
+
+-- Bank fee accounts
+insert into ACCOUNTS (ID, IBAN, CLIENT_ID, NAME, CREATED_AT, AMOUNT, CUR, UPDATED_AT)
+values ('00000000-0000-0000-0000-000000000901', 'UA393052999000000000000000001', '00000000-0000-0000-0000-000000000900',
+ 'USD fee account', TIMESTAMP '2021-01-01 00:00:00', 0.0, 'USD', TIMESTAMP '2021-01-01 00:00:00');
+
+insert into ACCOUNTS (ID, IBAN, CLIENT_ID, NAME, CREATED_AT, AMOUNT, CUR, UPDATED_AT)
+values ('00000000-0000-0000-0000-000000000902', 'UA123052999000000000000000002', '00000000-0000-0000-0000-000000000900',
+ 'EUR fee account', TIMESTAMP '2021-01-01 00:00:00', 0.0, 'EUR', TIMESTAMP '2021-01-01 00:00:00');
+
+insert into ACCOUNTS (ID, IBAN, CLIENT_ID, NAME, CREATED_AT, AMOUNT, CUR, UPDATED_AT)
+values ('00000000-0000-0000-0000-000000000903', 'UA823052999000000000000000003', '00000000-0000-0000-0000-000000000900',
+ 'UAH fee account', TIMESTAMP '2021-01-01 00:00:00', 0.0, 'UAH', TIMESTAMP '2021-01-01 00:00:00');
+
+-- Bank interest expense accounts (credited interest is paid from them)
+insert into ACCOUNTS (ID, IBAN, CLIENT_ID, NAME, CREATED_AT, AMOUNT, CUR, OVERDRAFT_LIMIT, UPDATED_AT)
+values ('00000000-0000-0000-0000-000000000911', 'UA603052999000000000000000011', '00000000-0000-0000-0000-000000000900',
+ 'USD interest account', TIMESTAMP '2021-01-01 00:00:00', 0.0, 'USD', 1000000000.0, TIMESTAMP '2021-01-01 00:00:00');
+
+insert into ACCOUNTS (ID, IBAN, CLIENT_ID, NAME, CREATED_AT, AMOUNT, CUR, OVERDRAFT_LIMIT, UPDATED_AT)
+values ('00000000-0000-0000-0000-000000000912', 'UA333052999000000000000000012', '00000000-0000-0000-0000-000000000900',
+ 'EUR interest account', TIMESTAMP '2021-01-01 00:00:00', 0.0, 'EUR', 1000000000.0, TIMESTAMP '2021-01-01 00:00:00');
+
+insert into ACCOUNTS (ID, IBAN, CLIENT_ID, NAME, CREATED_AT, AMOUNT, CUR, OVERDRAFT_LIMIT, UPDATED_AT)
+values ('00000000-0000-0000-0000-000000000913', 'UA063052999000000000000000013', '00000000-0000-0000-0000-000000000900',
+ 'UAH interest account', TIMESTAMP '2021-01-01 00:00:00', 0.0, 'UAH', 1000000000.0, TIMESTAMP '2021-01-01 00:00:00');
+
+
+-- Savings accounts
+update ACCOUNTS set INTEREST_RATE = 3.5, DAY_COUNT_CONVENTION = 'ACT_365'
+    where ID = '00000000-0000-0000-0000-000000000104';
+update ACCOUNTS set INTEREST_RATE = 2.0, DAY_COUNT_CONVENTION = 'THIRTY_360'
+    where ID = '00000000-0000-0000-0000-000000000102';
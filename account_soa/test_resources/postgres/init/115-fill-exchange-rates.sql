@@ -0,0 +1,18 @@
+
+insert into EXCHANGE_RATES (FROM_CUR, TO_CUR, RATE, UPDATED_AT)
+values ('USD', 'UAH', 41.25, TIMESTAMP '2024-09-01 10:00:00');
+
+insert into EXCHANGE_RATES (FROM_CUR, TO_CUR, RATE, UPDATED_AT)
+values ('UAH', 'USD', 0.0242, TIMESTAMP '2024-09-01 10:00:00');
+
+insert into EXCHANGE_RATES (FROM_CUR, TO_CUR, RATE, UPDATED_AT)
+values ('EUR', 'UAH', 45.80, TIMESTAMP '2024-09-01 10:00:00');
+
+insert into EXCHANGE_RATES (FROM_CUR, TO_CUR, RATE, UPDATED_AT)
+values ('UAH', 'EUR', 0.0218, TIMESTAMP '2024-09-01 10:00:00');
+
+insert into EXCHANGE_RATES (FROM_CUR, TO_CUR, RATE, UPDATED_AT)
+values ('EUR', 'USD', 1.1050, TIMESTAMP '2024-09-01 10:00:00');
+
+insert into EXCHANGE_RATES (FROM_CUR, TO_CUR, RATE, UPDATED_AT)
+values ('USD', 'EUR', 0.9050, TIMESTAMP '2024-09-01 10:00:00');
@@ -0,0 +1,20 @@
+
+insert into TRANSFER_LIMITS (CLIENT_TIER, CUR, MAX_TRANSFER_AMOUNT, MAX_DAILY_AMOUNT)
+values ('STANDARD', 'USD', 1000, 5000);
+insert into TRANSFER_LIMITS (CLIENT_TIER, CUR, MAX_TRANSFER_AMOUNT, MAX_DAILY_AMOUNT)
+values ('STANDARD', 'EUR', 1000, 5000);
+insert into TRANSFER_LIMITS (CLIENT_TIER, CUR, MAX_TRANSFER_AMOUNT, MAX_DAILY_AMOUNT)
+values ('STANDARD', 'UAH', 40000, 200000);
+
+insert into TRANSFER_LIMITS (CLIENT_TIER, CUR, MAX_TRANSFER_AMOUNT, MAX_DAILY_AMOUNT)
+values ('BUSINESS', 'USD', 20000, 100000);
+insert into TRANSFER_LIMITS (CLIENT_TIER, CUR, MAX_TRANSFER_AMOUNT, MAX_DAILY_AMOUNT)
+values ('BUSINESS', 'EUR', 20000, 100000);
+insert into TRANSFER_LIMITS (CLIENT_TIER, CUR, MAX_TRANSFER_AMOUNT, MAX_DAILY_AMOUNT)
+values ('BUSINESS', 'UAH', 800000, 4000000);
+
+-- Only daily limit.
+insert into TRANSFER_LIMITS (CLIENT_TIER, CUR, MAX_DAILY_AMOUNT)
+values ('SUPER_BUSINESS', 'USD', 1000000);
+insert into TRANSFER_LIMITS (CLIENT_TIER, CUR, MAX_DAILY_AMOUNT)
+values ('SUPER_BUSINESS', 'EUR', 1000000);
@@ -0,0 +1,30 @@
+
+insert into BANK_FEE_ACCOUNTS (CUR, ACCOUNT_ID) values ('USD', '00000000-0000-0000-0000-000000000901');
+insert into BANK_FEE_ACCOUNTS (CUR, ACCOUNT_ID) values ('EUR', '00000000-0000-0000-0000-000000000902');
+insert into BANK_FEE_ACCOUNTS (CUR, ACCOUNT_ID) values ('UAH', '00000000-0000-0000-0000-000000000903');
+
+-- Transfers between own accounts of the same currency are free (there are no such rules).
+
+-- USD
+insert into FEE_RULES (CUR, INTER_CLIENT, CROSS_CURRENCY, FLAT_FEE, FEE_PERCENT, MIN_FEE, MAX_FEE)
+values ('USD', 'y', 'n', 0.5, 0.5, 1, 50);
+insert into FEE_RULES (CUR, INTER_CLIENT, CROSS_CURRENCY, FLAT_FEE, FEE_PERCENT, MIN_FEE, MAX_FEE)
+values ('USD', 'n', 'y', 0, 1, 1, 100);
+insert into FEE_RULES (CUR, INTER_CLIENT, CROSS_CURRENCY, FLAT_FEE, FEE_PERCENT, MIN_FEE, MAX_FEE)
+values ('USD', 'y', 'y', 0.5, 1.5, 2, 150);
+
+-- EUR
+insert into FEE_RULES (CUR, INTER_CLIENT, CROSS_CURRENCY, FLAT_FEE, FEE_PERCENT, MIN_FEE, MAX_FEE)
+values ('EUR', 'y', 'n', 0.5, 0.5, 1, 50);
+insert into FEE_RULES (CUR, INTER_CLIENT, CROSS_CURRENCY, FLAT_FEE, FEE_PERCENT, MIN_FEE, MAX_FEE)
+values ('EUR', 'n', 'y', 0, 1, 1, 100);
+insert into FEE_RULES (CUR, INTER_CLIENT, CROSS_CURRENCY, FLAT_FEE, FEE_PERCENT, MIN_FEE, MAX_FEE)
+values ('EUR', 'y', 'y', 0.5, 1.5, 2, 150);
+
+-- UAH (only flat fee for inter-client transfers)
+insert into FEE_RULES (CUR, INTER_CLIENT, CROSS_CURRENCY, FLAT_FEE)
+values ('UAH', 'y', 'n', 5);
+insert into FEE_RULES (CUR, INTER_CLIENT, CROSS_CURRENCY, FLAT_FEE, FEE_PERCENT, MIN_FEE)
+values ('UAH', 'n', 'y', 0, 1, 20);
+insert into FEE_RULES (CUR, INTER_CLIENT, CROSS_CURRENCY, FLAT_FEE, FEE_PERCENT, MIN_FEE)
+values ('UAH', 'y', 'y', 5, 1.5, 40);
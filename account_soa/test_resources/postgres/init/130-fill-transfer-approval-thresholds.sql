@@ -0,0 +1,4 @@
+
+insert into TRANSFER_APPROVAL_THRESHOLDS (CUR, THRESHOLD) values ('USD', 10000);
+insert into TRANSFER_APPROVAL_THRESHOLDS (CUR, THRESHOLD) values ('EUR', 10000);
+insert into TRANSFER_APPROVAL_THRESHOLDS (CUR, THRESHOLD) values ('UAH', 400000);
@@ -0,0 +1,4 @@
+
+insert into BANK_INTEREST_ACCOUNTS (CUR, ACCOUNT_ID) values ('USD', '00000000-0000-0000-0000-000000000911');
+insert into BANK_INTEREST_ACCOUNTS (CUR, ACCOUNT_ID) values ('EUR', '00000000-0000-0000-0000-000000000912');
+insert into BANK_INTEREST_ACCOUNTS (CUR, ACCOUNT_ID) values ('UAH', '00000000-0000-0000-0000-000000000913');
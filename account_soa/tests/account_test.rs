@@ -2,7 +2,7 @@ use chrono::{ FixedOffset, Utc };
 use iban::Iban;
 use mvv_account_soa::entity::{
     prelude::AccountId,
-    account::{ self, Account },
+    account::{ self, Account, AccountStatus },
     ClientId,
 };
 use mvv_common_bank_entities::amount::Amount;
@@ -58,6 +58,9 @@ fn account_01() -> Account {
         iban: Iban::from_str("UA90 3515 3300 0002 6006 0359 0071 2").test_unwrap(),
         name: "account 1".try_into().test_unwrap(),
         amount: Amount::from_str("123.44 USD").test_unwrap(),
+        overdraft_limit: Amount::from_str("0 USD").test_unwrap(),
+        held_amount: Amount::from_str("0 USD").test_unwrap(),
+        status: AccountStatus::Active,
         created_at: datetime_from_str("2022-05-31 10:29:30 +02:00"),
         updated_at: datetime_from_str("2024-05-31 22:29:57 +02:00"),
     });
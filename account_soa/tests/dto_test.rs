@@ -6,7 +6,12 @@ use mvv_common::{
     test::{ TestDisplayStringOps, TestDebugStringOps, TestOptionUnwrap, TestResultUnwrap },
 };
 use mvv_common_bank_entities::currency::InnerCurStr;
-use mvv_account_soa::rest::dto::{ Account, Amount };
+use mvv_account_soa::rest::dto::{
+    Account, AccountStatus, AccountTransaction, AccountTransactions, Amount,
+    StandingOrder, StandingOrderPeriod, TransferBatchResponse, TransferLegResult, TransferLegStatus,
+    TransferQuote, TransferOutcome, TransferOutcomeStatus, PendingTransfer, PendingTransferStatus,
+    WebhookDelivery, WebhookDeliveryAttempt, WebhookDeliveryStatus, WebhookEventType,
+};
 //--------------------------------------------------------------------------------------------------
 
 
@@ -85,6 +90,19 @@ fn account_display_and_debug_fmt_test() {
             value: BigDecimal::from_str("123.0456").test_unwrap(),
             currency: InnerCurStr::const_make("USD"),
         },
+        ledger: Amount {
+            value: BigDecimal::from_str("123.0456").test_unwrap(),
+            currency: InnerCurStr::const_make("USD"),
+        },
+        available: Amount {
+            value: BigDecimal::from_str("203.0456").test_unwrap(),
+            currency: InnerCurStr::const_make("USD"),
+        },
+        overdraft_limit: Amount {
+            value: BigDecimal::from_str("100").test_unwrap(),
+            currency: InnerCurStr::const_make("USD"),
+        },
+        status: AccountStatus::Active,
         created_at: chrono::DateTime::<FixedOffset>::from_str("2024-05-30 22:29:57 +02:00")
             .test_unwrap().to_utc(),
         updated_at: chrono::DateTime::<FixedOffset>::from_str("2024-05-31 22:29:57 +02:00")
@@ -103,6 +121,10 @@ fn account_display_and_debug_fmt_test() {
              client_id: 7911a64a-7aef-4ade-ace0-0299849b28a6, \
              name: Account 2, \
              amount: Amount { value: 123.0456 (BigDecimal(sign=Plus, scale=4, digits=[1230456])), currency: USD }, \
+             ledger: Amount { value: 123.0456 (BigDecimal(sign=Plus, scale=4, digits=[1230456])), currency: USD }, \
+             available: Amount { value: 203.0456 (BigDecimal(sign=Plus, scale=4, digits=[2030456])), currency: USD }, \
+             overdraft_limit: Amount { value: 100 (BigDecimal(sign=Plus, scale=0, digits=[100])), currency: USD }, \
+             status: Active, \
              created_at: 2024-05-30T20:29:57Z, updated_at: 2024-05-31T20:29:57Z }",
         );
 }
@@ -119,6 +141,19 @@ fn account_to_json() {
             value: BigDecimal::from_str("123.0456").test_unwrap(),
             currency: InnerCurStr::const_make("USD"),
         },
+        ledger: Amount {
+            value: BigDecimal::from_str("123.0456").test_unwrap(),
+            currency: InnerCurStr::const_make("USD"),
+        },
+        available: Amount {
+            value: BigDecimal::from_str("203.0456").test_unwrap(),
+            currency: InnerCurStr::const_make("USD"),
+        },
+        overdraft_limit: Amount {
+            value: BigDecimal::from_str("100").test_unwrap(),
+            currency: InnerCurStr::const_make("USD"),
+        },
+        status: AccountStatus::Active,
         created_at: chrono::DateTime::<FixedOffset>::from_str("2024-05-30 22:29:57 +02:00")
             .test_unwrap().to_utc(),
         updated_at: chrono::DateTime::<FixedOffset>::from_str("2024-05-31 22:29:57 +02:00")
@@ -132,6 +167,10 @@ fn account_to_json() {
                "clientId":"7911a64a-7aef-4ade-ace0-0299849b28a6",
                "name":"Account 3",
                "amount":{"value":123.0456,"currency":"USD"},
+               "ledger":{"value":123.0456,"currency":"USD"},
+               "available":{"value":203.0456,"currency":"USD"},
+               "overdraftLimit":{"value":100,"currency":"USD"},
+               "status":"ACTIVE",
                "createdAt":"2024-05-30T20:29:57Z",
                "updatedAt":"2024-05-31T20:29:57Z"}
                "#}, "\n", "")
@@ -147,6 +186,10 @@ fn account_to_json() {
             "name":"Account 3",
             "amount": { "value": 123.0456, "currency":"USD" },
             // "amount": "123.0456 USD",
+            "ledger": { "value": 123.0456, "currency":"USD" },
+            "available": { "value": 203.0456, "currency":"USD" },
+            "overdraftLimit": { "value": 100, "currency":"USD" },
+            "status": "ACTIVE",
             "createdAt": "2024-05-30T20:29:57Z",
             "updatedAt": "2024-05-31T20:29:57Z",
             }
@@ -166,6 +209,10 @@ fn account_from_json() {
             "name":"Account 4",
             "amount": { "value": 123.0456, "currency":"USD" },
             // "amount": "123.0456 USD",
+            "ledger": { "value": 123.0456, "currency":"USD" },
+            "available": { "value": 203.0456, "currency":"USD" },
+            "overdraftLimit": { "value": 100, "currency":"USD" },
+            "status": "ACTIVE",
             "createdAt": "2024-05-30T20:29:57Z",
             "updatedAt": "2024-05-31T20:29:57Z",
             }
@@ -192,6 +239,19 @@ fn account_from_json() {
             value: BigDecimal::from_str("123.0456").test_unwrap(),
             currency: InnerCurStr::const_make("USD"),
         },
+        ledger: Amount {
+            value: BigDecimal::from_str("123.0456").test_unwrap(),
+            currency: InnerCurStr::const_make("USD"),
+        },
+        available: Amount {
+            value: BigDecimal::from_str("203.0456").test_unwrap(),
+            currency: InnerCurStr::const_make("USD"),
+        },
+        overdraft_limit: Amount {
+            value: BigDecimal::from_str("100").test_unwrap(),
+            currency: InnerCurStr::const_make("USD"),
+        },
+        status: AccountStatus::Active,
         created_at: chrono::DateTime::<FixedOffset>::from_str("2024-05-30 22:29:57 +02:00")
             .test_unwrap().to_utc(),
         updated_at: chrono::DateTime::<FixedOffset>::from_str("2024-05-31 22:29:57 +02:00")
@@ -202,6 +262,267 @@ fn account_from_json() {
 }
 
 
+#[test]
+fn account_transactions_to_json() {
+    let transactions = AccountTransactions {
+        transactions: vec!(AccountTransaction {
+            id: 1001,
+            transfer_id: uuid_from_str("6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55"),
+            account_id: uuid_from_str("00000000-0000-0000-0000-000000000101"),
+            counterparty_account_id: Some(uuid_from_str("00000000-0000-0000-0000-000000000102")),
+            amount: Amount {
+                value: BigDecimal::from_str("-12.5").test_unwrap(),
+                currency: InnerCurStr::const_make("USD"),
+            },
+            balance_after: Amount {
+                value: BigDecimal::from_str("137.5").test_unwrap(),
+                currency: InnerCurStr::const_make("USD"),
+            },
+            exchange_rate: None,
+            exchange_rate_at: None,
+            reversed_transfer_id: None,
+            fee_for_transfer_id: None,
+            created_at: chrono::DateTime::<FixedOffset>::from_str("2024-05-30 22:29:57 +02:00")
+                .test_unwrap().to_utc(),
+        }),
+        next_cursor: Some("1001".to_test_string()),
+    };
+
+    let transactions_json = serde_json::to_string(&transactions).test_unwrap();
+
+    assert_json_diff::assert_json_eq!(
+        serde_json::Value::from_str(transactions_json.as_str()).test_unwrap(),
+        serde_json::json!(
+            {
+            "transactions": [{
+                "id": 1001,
+                "transferId": "6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55",
+                "accountId": "00000000-0000-0000-0000-000000000101",
+                "counterpartyAccountId": "00000000-0000-0000-0000-000000000102",
+                "amount": { "value": -12.5, "currency":"USD" },
+                "balanceAfter": { "value": 137.5, "currency":"USD" },
+                "createdAt": "2024-05-30T20:29:57Z",
+            }],
+            "nextCursor": "1001",
+            }
+        )
+    );
+
+    let from_json: AccountTransactions = serde_json::from_str(&transactions_json).test_unwrap();
+    assert_eq!(from_json, transactions);
+}
+
+
+#[test]
+fn standing_order_to_json() {
+    let standing_order = StandingOrder {
+        id: uuid_from_str("0c9d3a7e-2f4b-4e8a-b1c6-5d7e9f0a2b4c"),
+        from_account_id: uuid_from_str("00000000-0000-0000-0000-000000000101"),
+        to_iban: "UA853052990000000000000002011".to_test_string(),
+        beneficiary_name: Some("Tigra Bohdan".to_test_string()),
+        amount: Amount {
+            value: BigDecimal::from_str("250.75").test_unwrap(),
+            currency: InnerCurStr::const_make("USD"),
+        },
+        period: StandingOrderPeriod::Monthly,
+        start_at: chrono::DateTime::<FixedOffset>::from_str("2024-05-01 10:00:00 +02:00")
+            .test_unwrap().to_utc(),
+        next_execution_at: chrono::DateTime::<FixedOffset>::from_str("2024-06-01 10:00:00 +02:00")
+            .test_unwrap().to_utc(),
+        failed_attempts: 0,
+        retry_at: None,
+        last_executed_at: Some(chrono::DateTime::<FixedOffset>::from_str("2024-05-01 10:00:05 +02:00")
+            .test_unwrap().to_utc()),
+        last_transfer_id: Some(uuid_from_str("6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55")),
+        last_error: None,
+        created_at: chrono::DateTime::<FixedOffset>::from_str("2024-04-30 22:29:57 +02:00")
+            .test_unwrap().to_utc(),
+        updated_at: chrono::DateTime::<FixedOffset>::from_str("2024-05-01 10:00:05 +02:00")
+            .test_unwrap().to_utc(),
+    };
+
+    let standing_order_json = serde_json::to_string(&standing_order).test_unwrap();
+
+    assert_json_diff::assert_json_eq!(
+        serde_json::Value::from_str(standing_order_json.as_str()).test_unwrap(),
+        serde_json::json!(
+            {
+            "id": "0c9d3a7e-2f4b-4e8a-b1c6-5d7e9f0a2b4c",
+            "fromAccountId": "00000000-0000-0000-0000-000000000101",
+            "toIban": "UA853052990000000000000002011",
+            "beneficiaryName": "Tigra Bohdan",
+            "amount": { "value": 250.75, "currency":"USD" },
+            "period": "MONTHLY",
+            "startAt": "2024-05-01T08:00:00Z",
+            "nextExecutionAt": "2024-06-01T08:00:00Z",
+            "failedAttempts": 0,
+            "lastExecutedAt": "2024-05-01T08:00:05Z",
+            "lastTransferId": "6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55",
+            "createdAt": "2024-04-30T20:29:57Z",
+            "updatedAt": "2024-05-01T08:00:05Z",
+            }
+        )
+    );
+
+    let from_json: StandingOrder = serde_json::from_str(&standing_order_json).test_unwrap();
+    assert_eq!(from_json, standing_order);
+}
+
+
+#[test]
+fn transfer_batch_response_to_json() {
+    let batch_response = TransferBatchResponse {
+        committed: false,
+        legs: vec!(
+            TransferLegResult {
+                index: 0,
+                status: TransferLegStatus::Ok,
+                transfer_id: None,
+                error: None,
+            },
+            TransferLegResult {
+                index: 1,
+                status: TransferLegStatus::Failed,
+                transfer_id: None,
+                error: Some("NotEnoughBalance (available funds: 10 USD)".to_test_string()),
+            },
+        ),
+    };
+
+    let batch_response_json = serde_json::to_string(&batch_response).test_unwrap();
+
+    assert_json_diff::assert_json_eq!(
+        serde_json::Value::from_str(batch_response_json.as_str()).test_unwrap(),
+        serde_json::json!(
+            {
+            "committed": false,
+            "legs": [
+                { "index": 0, "status": "OK" },
+                { "index": 1, "status": "FAILED", "error": "NotEnoughBalance (available funds: 10 USD)" },
+            ],
+            }
+        )
+    );
+
+    let from_json: TransferBatchResponse = serde_json::from_str(&batch_response_json).test_unwrap();
+    assert_eq!(from_json, batch_response);
+}
+
+
+#[test]
+fn transfer_quote_to_json() {
+    let usd = |value: &str| Amount {
+        value: BigDecimal::from_str(value).test_unwrap(),
+        currency: InnerCurStr::const_make("USD"),
+    };
+
+    let quote = TransferQuote {
+        amount: usd("250.75"),
+        fee: usd("1.75"),
+        total_debit_amount: usd("252.5"),
+        credit_amount: Amount {
+            value: BigDecimal::from_str("10343.4375").test_unwrap(),
+            currency: InnerCurStr::const_make("UAH"),
+        },
+        exchange_rate: Some(BigDecimal::from_str("41.25").test_unwrap()),
+    };
+
+    let quote_json = serde_json::to_string(&quote).test_unwrap();
+
+    assert_json_diff::assert_json_eq!(
+        serde_json::Value::from_str(quote_json.as_str()).test_unwrap(),
+        serde_json::json!(
+            {
+            "amount": { "value": 250.75, "currency": "USD" },
+            "fee": { "value": 1.75, "currency": "USD" },
+            "totalDebitAmount": { "value": 252.5, "currency": "USD" },
+            "creditAmount": { "value": 10343.4375, "currency": "UAH" },
+            "exchangeRate": 41.25,
+            }
+        )
+    );
+
+    let from_json: TransferQuote = serde_json::from_str(&quote_json).test_unwrap();
+    assert_eq!(from_json, quote);
+}
+
+
+#[test]
+fn pending_transfer_to_json() {
+    let pending_transfer = PendingTransfer {
+        id: uuid_from_str("3f1c2b7d-9a4e-4c8b-8e21-7b5d0c6a9f13"),
+        from_account_id: uuid_from_str("00000000-0000-0000-0000-000000000101"),
+        to_iban: "UA853052990000000000000002011".to_owned(),
+        beneficiary_name: Some("Tigra Bohdan".to_owned()),
+        amount: Amount {
+            value: BigDecimal::from_str("15000.5").test_unwrap(),
+            currency: InnerCurStr::const_make("USD"),
+        },
+        status: PendingTransferStatus::Approved,
+        created_by: "vovan-write".to_owned(),
+        decided_by: Some("vovan-super-user".to_owned()),
+        decided_at: Some(chrono::DateTime::<FixedOffset>::from_str("2024-05-30 23:29:57 +02:00")
+            .test_unwrap().to_utc()),
+        transfer_id: Some(uuid_from_str("6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55")),
+        reject_reason: None,
+        created_at: chrono::DateTime::<FixedOffset>::from_str("2024-05-30 22:29:57 +02:00")
+            .test_unwrap().to_utc(),
+        updated_at: chrono::DateTime::<FixedOffset>::from_str("2024-05-30 23:29:57 +02:00")
+            .test_unwrap().to_utc(),
+    };
+
+    let pending_transfer_json = serde_json::to_string(&pending_transfer).test_unwrap();
+
+    assert_json_diff::assert_json_eq!(
+        serde_json::Value::from_str(pending_transfer_json.as_str()).test_unwrap(),
+        serde_json::json!(
+            {
+            "id": "3f1c2b7d-9a4e-4c8b-8e21-7b5d0c6a9f13",
+            "fromAccountId": "00000000-0000-0000-0000-000000000101",
+            "toIban": "UA853052990000000000000002011",
+            "beneficiaryName": "Tigra Bohdan",
+            "amount": { "value": 15000.5, "currency": "USD" },
+            "status": "APPROVED",
+            "createdBy": "vovan-write",
+            "decidedBy": "vovan-super-user",
+            "decidedAt": "2024-05-30T21:29:57Z",
+            "transferId": "6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55",
+            "createdAt": "2024-05-30T20:29:57Z",
+            "updatedAt": "2024-05-30T21:29:57Z",
+            }
+        )
+    );
+
+    let from_json: PendingTransfer = serde_json::from_str(&pending_transfer_json).test_unwrap();
+    assert_eq!(from_json, pending_transfer);
+}
+
+
+#[test]
+fn transfer_outcome_to_json() {
+    let outcome = TransferOutcome {
+        status: TransferOutcomeStatus::Completed,
+        transfer_id: Some(uuid_from_str("6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55")),
+        pending_transfer: None,
+    };
+
+    let outcome_json = serde_json::to_string(&outcome).test_unwrap();
+
+    assert_json_diff::assert_json_eq!(
+        serde_json::Value::from_str(outcome_json.as_str()).test_unwrap(),
+        serde_json::json!(
+            {
+            "status": "COMPLETED",
+            "transferId": "6a3b4f1e-8c1d-4e0a-9d6b-2f4c0b1e7a55",
+            }
+        )
+    );
+
+    let from_json: TransferOutcome = serde_json::from_str(&outcome_json).test_unwrap();
+    assert_eq!(from_json, outcome);
+}
+
+
 /*
 #[test]
 fn account_from_json_with_alt_amount_format() {
@@ -266,6 +587,10 @@ fn validate_account_by_validify_test() {
             "name":"Account 4",
             "amount": { "value": 123.0456, "currency":"us2" },// "ДОЛ" },
             // "amount": "123.0456 USD",
+            "ledger": { "value": 123.0456, "currency":"USD" },
+            "available": { "value": 203.0456, "currency":"USD" },
+            "overdraftLimit": { "value": 100, "currency":"USD" },
+            "status": "ACTIVE",
             "createdAt": "2024-05-30T20:29:57Z",
             "updatedAt": "2024-05-31T20:29:57Z",
             }
@@ -286,6 +611,61 @@ fn validate_account_by_validify_test() {
 }
 
 
+
+#[test]
+fn webhook_delivery_to_json() {
+    let delivery = WebhookDelivery {
+        id: uuid_from_str("5d4c3b2a-1f0e-4d9c-8b7a-6f5e4d3c2b1a"),
+        event_id: uuid_from_str("7e6d5c4b-3a29-4817-9f6e-5d4c3b2a1908"),
+        event_type: WebhookEventType::TransferIncoming,
+        status: WebhookDeliveryStatus::Pending,
+        attempts: 1,
+        next_attempt_at: Some(chrono::DateTime::<FixedOffset>::from_str("2024-05-30 22:30:27 +02:00")
+            .test_unwrap().to_utc()),
+        last_error: Some("Response status 503 Service Unavailable.".to_owned()),
+        delivered_at: None,
+        created_at: chrono::DateTime::<FixedOffset>::from_str("2024-05-30 22:29:57 +02:00")
+            .test_unwrap().to_utc(),
+        attempt_history: vec![WebhookDeliveryAttempt {
+            attempt_no: 1,
+            attempted_at: chrono::DateTime::<FixedOffset>::from_str("2024-05-30 22:29:57 +02:00")
+                .test_unwrap().to_utc(),
+            response_status: Some(503),
+            error: Some("Response status 503 Service Unavailable.".to_owned()),
+            duration_ms: 120,
+        }],
+    };
+
+    let delivery_json = serde_json::to_string(&delivery).test_unwrap();
+
+    assert_json_diff::assert_json_eq!(
+        serde_json::Value::from_str(delivery_json.as_str()).test_unwrap(),
+        serde_json::json!(
+            {
+            "id": "5d4c3b2a-1f0e-4d9c-8b7a-6f5e4d3c2b1a",
+            "eventId": "7e6d5c4b-3a29-4817-9f6e-5d4c3b2a1908",
+            "eventType": "TRANSFER_INCOMING",
+            "status": "PENDING",
+            "attempts": 1,
+            "nextAttemptAt": "2024-05-30T20:30:27Z",
+            "lastError": "Response status 503 Service Unavailable.",
+            "createdAt": "2024-05-30T20:29:57Z",
+            "attemptHistory": [{
+                "attemptNo": 1,
+                "attemptedAt": "2024-05-30T20:29:57Z",
+                "responseStatus": 503,
+                "error": "Response status 503 Service Unavailable.",
+                "durationMs": 120,
+            }],
+            }
+        )
+    );
+
+    let from_json: WebhookDelivery = serde_json::from_str(&delivery_json).test_unwrap();
+    assert_eq!(from_json, delivery);
+}
+
+
 /*
 #[test]
 fn main_test()  {
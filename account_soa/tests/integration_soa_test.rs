@@ -4,6 +4,7 @@ use std::time::Duration;
 use anyhow::anyhow;
 use assert_json_diff::{assert_json_eq};
 use assertables::{assert_ge, assert_ge_as_result};
+use bigdecimal::BigDecimal;
 use log::{debug, info};
 use reqwest::{Certificate, Response};
 use rustainers::compose::{
@@ -166,6 +167,13 @@ async fn integration_test_run_account_soa_docker_compose() {
     let port: u16 = port.test_unwrap().into();
 
     test_get_all_client_accounts(port).await;
+    test_concurrent_transfers_conserve_money(port).await;
+    test_not_positive_transfer_to_iban_is_rejected(port).await;
+    test_transfer_fee(port).await;
+    test_batch_transfer_is_atomic(port).await;
+    test_standing_order_execution(port).await;
+    test_pending_transfer_approval(port).await;
+    test_webhook_signature(port).await;
 
     // let pause_timeout = Duration::from_secs(5);
     // info!("### Pause for {}s...", pause_timeout.as_secs());
@@ -187,16 +195,7 @@ async fn test_get_all_client_accounts(account_soa_port: u16) {
     let base_url = format!("https://localhost:{account_soa_port}");
     let url = format!("{base_url}/api/client/00000000-0000-0000-0000-000000000001/account/all");
 
-    let build_target_dir = current_project_target_dir().test_unwrap();
-    let cert_path = build_target_dir.join("generated-test-resources/ssl/ca.crt.pem");
-
-    let pem: String = std::fs::read_to_string(&cert_path)
-        .map_err(|err| anyhow!("Error of reading from [{cert_path:?}] ({err:?})")).test_unwrap();
-
-    let client = reqwest::Client::builder()
-        // .danger_accept_invalid_certs(true)
-        .add_root_certificate(Certificate::from_pem(pem.as_bytes()).test_unwrap())
-        .build().test_unwrap();
+    let client = create_https_client();
 
     let resp: Response = client.get(url)
         .basic_auth("vovan-read", Some("qwerty"))
@@ -230,6 +229,19 @@ async fn test_get_all_client_accounts(account_soa_port: u16) {
                 "iban":  "UA71 3736 5721 7292 6969 8418 3239 3",
                 "id": "00000000-0000-0000-0000-000000000101",
                 "name":  "USD account 1",
+                "ledger": {
+                  "currency": "USD",
+                  "value": 150
+                },
+                "available": {
+                  "currency": "USD",
+                  "value": 150
+                },
+                "overdraftLimit": {
+                  "currency": "USD",
+                  "value": 0
+                },
+                "status": "ACTIVE",
                 "updatedAt": "2021-11-10T15:14:15Z",
             }
         )
@@ -257,3 +269,429 @@ async fn test_get_all_client_accounts(account_soa_port: u16) {
     */
 }
 
+
+fn create_https_client() -> reqwest::Client {
+    let build_target_dir = current_project_target_dir().test_unwrap();
+    let cert_path = build_target_dir.join("generated-test-resources/ssl/ca.crt.pem");
+
+    let pem: String = std::fs::read_to_string(&cert_path)
+        .map_err(|err| anyhow!("Error of reading from [{cert_path:?}] ({err:?})")).test_unwrap();
+
+    reqwest::Client::builder()
+        // .danger_accept_invalid_certs(true)
+        .add_root_certificate(Certificate::from_pem(pem.as_bytes()).test_unwrap())
+        .build().test_unwrap()
+}
+
+
+async fn get_account_amount(client: &reqwest::Client, base_url: &str, account_id: &str) -> BigDecimal {
+    get_client_account_amount(client, base_url, "00000000-0000-0000-0000-000000000001", account_id).await
+}
+
+
+async fn get_client_account_amount(client: &reqwest::Client, base_url: &str, client_id: &str, account_id: &str) -> BigDecimal {
+    let url = format!("{base_url}/api/client/{client_id}/account/{account_id}");
+
+    let resp: Response = client.get(url)
+        .basic_auth("vovan-read", Some("qwerty"))
+        .send()
+        .await
+        .test_unwrap();
+    assert_eq!(resp.status().as_u16(), 200);
+
+    let account: serde_json::Value = resp.json().await.test_unwrap();
+    let amount = account.get("amount").and_then(|amount| amount.get("value")).test_unwrap();
+
+    use core::str::FromStr;
+    BigDecimal::from_str(&amount.to_string()).test_unwrap()
+}
+
+
+/// Many concurrent transfers in both directions between the same accounts
+/// (it used to cause deadlocks and lost updates).
+/// Sum of accounts balances should not be changed.
+async fn test_concurrent_transfers_conserve_money(account_soa_port: u16) {
+
+    const ACCOUNT_1: &str = "00000000-0000-0000-0000-000000000101";
+    const ACCOUNT_2: &str = "00000000-0000-0000-0000-000000000102";
+    const TRANSFER_COUNT: usize = 100;
+
+    let base_url = format!("https://localhost:{account_soa_port}");
+    let client = create_https_client();
+
+    let amount_before = get_account_amount(&client, &base_url, ACCOUNT_1).await
+        + get_account_amount(&client, &base_url, ACCOUNT_2).await;
+
+    let mut transfers = tokio::task::JoinSet::new();
+    for i in 0..TRANSFER_COUNT {
+        let (from_account, to_account) = if i % 2 == 0 { (ACCOUNT_1, ACCOUNT_2) } else { (ACCOUNT_2, ACCOUNT_1) };
+        let client = client.clone();
+        let url = format!("{base_url}/api/client/00000000-0000-0000-0000-000000000001/transfer");
+
+        transfers.spawn(async move {
+            let resp: Response = client.post(url)
+                .basic_auth("vovan-read", Some("qwerty"))
+                .json(&json!({
+                    "from_account": from_account,
+                    "to_account": to_account,
+                    "amount": 1.5 + (i % 7) as f64,
+                    "currency": "USD",
+                }))
+                .send()
+                .await
+                .test_unwrap();
+            resp.status().as_u16()
+        });
+    }
+
+    let mut succeeded_count = 0;
+    while let Some(status) = transfers.join_next().await {
+        let status = status.test_unwrap();
+        // 400 is possible in case of NotEnoughBalance.
+        assert!(status == 200 || status == 201 || status == 400, "Unexpected transfer status {status}.");
+        if status != 400 { succeeded_count += 1 }
+    }
+    info!("### {succeeded_count} of {TRANSFER_COUNT} concurrent transfers succeeded");
+    assert_ge!(succeeded_count, TRANSFER_COUNT / 2);
+
+    let amount_after = get_account_amount(&client, &base_url, ACCOUNT_1).await
+        + get_account_amount(&client, &base_url, ACCOUNT_2).await;
+
+    assert_eq!(amount_after, amount_before);
+}
+
+
+
+const CLIENT_1_PATH: &str = "/api/client/00000000-0000-0000-0000-000000000001";
+const USD_ACCOUNT_1: &str = "00000000-0000-0000-0000-000000000101";
+// UAH accounts are not used by concurrent transfers test, so their balances are known.
+const UAH_ACCOUNT_1: &str = "00000000-0000-0000-0000-000000000103";
+const UAH_ACCOUNT_2: &str = "00000000-0000-0000-0000-000000000104";
+
+
+/// Returns status and body (body is Null if it is not JSON).
+async fn send_json(request: reqwest::RequestBuilder, user: &str) -> (u16, serde_json::Value) {
+    let resp: Response = request
+        .basic_auth(user, Some("qwerty"))
+        .send()
+        .await
+        .test_unwrap();
+
+    let status = resp.status().as_u16();
+    let body = resp.text().await.test_unwrap();
+    debug!("### response {status}: {body}");
+    (status, serde_json::from_str(&body).unwrap_or(serde_json::Value::Null))
+}
+
+fn uah_transfer_request(from_account: &str, to_account: &str, amount: u32) -> serde_json::Value {
+    json!({
+        "from_account": from_account,
+        "to_account": to_account,
+        "amount": amount,
+        "currency": "UAH",
+    })
+}
+
+
+/// Batch with failed legs is rejected as a whole (status of every leg is returned),
+/// batch without failed legs is committed as a whole.
+async fn test_batch_transfer_is_atomic(account_soa_port: u16) {
+
+    let base_url = format!("https://localhost:{account_soa_port}");
+    let client = create_https_client();
+    let url = format!("{base_url}{CLIENT_1_PATH}/transfers/batch");
+
+    let amount_1_before = get_account_amount(&client, &base_url, UAH_ACCOUNT_1).await;
+    let amount_2_before = get_account_amount(&client, &base_url, UAH_ACCOUNT_2).await;
+
+    let (status, batch_res) = send_json(client.post(&url).json(&json!({ "legs": [
+        uah_transfer_request(UAH_ACCOUNT_1, UAH_ACCOUNT_2, 100),
+        // Not enough balance
+        uah_transfer_request(UAH_ACCOUNT_2, UAH_ACCOUNT_1, 30000),
+        // Above approval threshold
+        uah_transfer_request(UAH_ACCOUNT_1, UAH_ACCOUNT_2, 500000),
+    ]})), "vovan-read").await;
+
+    assert_eq!(status, 422);
+    assert_eq!(batch_res.get("committed").test_unwrap(), false);
+    let legs = batch_res.get("legs").and_then(|legs| legs.as_array()).test_unwrap();
+    assert_eq!(legs.len(), 3);
+    assert_json_eq!(legs[0].clone(), json!({ "index": 0, "status": "OK" }));
+    for leg in &legs[1..] {
+        assert_eq!(leg.get("status").test_unwrap(), "FAILED");
+        assert!(leg.get("error").is_some(), "Failed leg should have error.");
+    }
+
+    assert_eq!(get_account_amount(&client, &base_url, UAH_ACCOUNT_1).await, amount_1_before);
+    assert_eq!(get_account_amount(&client, &base_url, UAH_ACCOUNT_2).await, amount_2_before);
+
+    let (status, batch_res) = send_json(client.post(&url).json(&json!({ "legs": [
+        uah_transfer_request(UAH_ACCOUNT_1, UAH_ACCOUNT_2, 100),
+        uah_transfer_request(UAH_ACCOUNT_2, UAH_ACCOUNT_1, 40),
+    ]})), "vovan-read").await;
+
+    assert_eq!(status, 201);
+    assert_eq!(batch_res.get("committed").test_unwrap(), true);
+    for leg in batch_res.get("legs").and_then(|legs| legs.as_array()).test_unwrap() {
+        assert_eq!(leg.get("status").test_unwrap(), "OK");
+        assert!(leg.get("transferId").is_some(), "Leg of committed batch should have transfer ID.");
+    }
+
+    assert_eq!(get_account_amount(&client, &base_url, UAH_ACCOUNT_1).await, amount_1_before - BigDecimal::from(60));
+    assert_eq!(get_account_amount(&client, &base_url, UAH_ACCOUNT_2).await, amount_2_before + BigDecimal::from(60));
+}
+
+
+/// Transfer of negative amount to account of another client would take money from beneficiary.
+async fn test_not_positive_transfer_to_iban_is_rejected(account_soa_port: u16) {
+    const CLIENT_2: &str = "00000000-0000-0000-0000-000000000002";
+    const CLIENT_2_USD_ACCOUNT: &str = "00000000-0000-0000-0000-000000000201";
+    const CLIENT_2_USD_IBAN: &str = "UA853052990000000000000002011";
+
+    let base_url = format!("https://localhost:{account_soa_port}");
+    let client = create_https_client();
+
+    let amount_1_before = get_account_amount(&client, &base_url, USD_ACCOUNT_1).await;
+    let amount_2_before = get_client_account_amount(&client, &base_url, CLIENT_2, CLIENT_2_USD_ACCOUNT).await;
+
+    for amount in ["-10", "0"] {
+        let transfer_request: serde_json::Value = serde_json::from_str(&format!(
+            r#"{{ "from_account": "{USD_ACCOUNT_1}", "to_account": "{CLIENT_2_USD_IBAN}",
+                 "beneficiary_name": "Tigra Bohdan", "amount": {amount}, "currency": "USD" }}"#
+        )).test_unwrap();
+
+        let (status, _) = send_json(
+            client.post(format!("{base_url}{CLIENT_1_PATH}/transfer")).json(&transfer_request),
+            "vovan-read").await;
+        assert_eq!(status, 400, "Transfer of amount {amount} should be rejected.");
+    }
+
+    assert_eq!(get_account_amount(&client, &base_url, USD_ACCOUNT_1).await, amount_1_before);
+    assert_eq!(get_client_account_amount(&client, &base_url, CLIENT_2, CLIENT_2_USD_ACCOUNT).await, amount_2_before);
+}
+
+
+/// Standing order is executed by scheduler at its start time.
+async fn test_standing_order_execution(account_soa_port: u16) {
+
+    let base_url = format!("https://localhost:{account_soa_port}");
+    let client = create_https_client();
+
+    let amount_1_before = get_account_amount(&client, &base_url, UAH_ACCOUNT_1).await;
+    let amount_2_before = get_account_amount(&client, &base_url, UAH_ACCOUNT_2).await;
+
+    let start_at = chrono::Utc::now() + chrono::Duration::seconds(3);
+
+    // Order in currency other than 'from' account one is rejected at once (not by every execution).
+    let (status, _) = send_json(
+        client.post(format!("{base_url}{CLIENT_1_PATH}/standing-order")).json(&json!({
+            "from_account": UAH_ACCOUNT_2,
+            "to_account": UAH_ACCOUNT_1,
+            "amount": { "value": 10, "currency": "USD" },
+            "period": "DAILY",
+            "start_at": start_at,
+        })),
+        "vovan-write").await;
+    assert_eq!(status, 400);
+
+    let (status, order) = send_json(
+        client.post(format!("{base_url}{CLIENT_1_PATH}/standing-order")).json(&json!({
+            "from_account": UAH_ACCOUNT_2,
+            "to_account": UAH_ACCOUNT_1,
+            "amount": { "value": 10, "currency": "UAH" },
+            "period": "DAILY",
+            "start_at": start_at,
+        })),
+        "vovan-write").await;
+    assert_eq!(status, 201);
+    let order_id = order.get("id").and_then(|id| id.as_str()).test_unwrap().to_owned();
+    let order_url = format!("{base_url}{CLIENT_1_PATH}/standing-order/{order_id}");
+
+    let mut executed_order = None;
+    for _ in 0..30 {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let (status, order) = send_json(client.get(&order_url), "vovan-read").await;
+        assert_eq!(status, 200);
+        if order.get("lastTransferId").is_some() {
+            executed_order = Some(order);
+            break;
+        }
+    }
+    let executed_order = executed_order.test_unwrap();
+    assert_eq!(executed_order.get("failedAttempts").test_unwrap(), 0);
+
+    assert_eq!(get_account_amount(&client, &base_url, UAH_ACCOUNT_1).await, amount_1_before + BigDecimal::from(10));
+    assert_eq!(get_account_amount(&client, &base_url, UAH_ACCOUNT_2).await, amount_2_before - BigDecimal::from(10));
+
+    let (status, _) = send_json(client.delete(&order_url), "vovan-write").await;
+    assert_ge!(status, 200);
+    assert!(status < 300, "Unexpected status {status} of standing order deletion.");
+}
+
+
+/// Fee of cross-currency transfer between own accounts (1%, but not less than 20 UAH)
+/// is charged in addition to amount, and it is the same as in quote.
+async fn test_transfer_fee(account_soa_port: u16) {
+    use core::str::FromStr;
+
+    fn bd_from_json(value: &serde_json::Value) -> BigDecimal {
+        BigDecimal::from_str(value.to_string().trim_matches('"')).test_unwrap()
+    }
+
+    let base_url = format!("https://localhost:{account_soa_port}");
+    let client = create_https_client();
+
+    let uah_before = get_account_amount(&client, &base_url, UAH_ACCOUNT_1).await;
+    let usd_before = get_account_amount(&client, &base_url, USD_ACCOUNT_1).await;
+    let transfer_request = uah_transfer_request(UAH_ACCOUNT_1, USD_ACCOUNT_1, 100);
+
+    // Quote is not returned for transfer which would be rejected (amount not in 'from' account currency).
+    let mut wrong_currency_request = transfer_request.clone();
+    wrong_currency_request["currency"] = json!("USD");
+    let (status, _) = send_json(
+        client.post(format!("{base_url}{CLIENT_1_PATH}/transfer/quote")).json(&wrong_currency_request),
+        "vovan-read").await;
+    assert_eq!(status, 400);
+
+    let (status, quote) = send_json(
+        client.post(format!("{base_url}{CLIENT_1_PATH}/transfer/quote")).json(&transfer_request),
+        "vovan-read").await;
+    assert_eq!(status, 200);
+    let quote_amount = |name: &str| {
+        let amount = quote.get(name).test_unwrap();
+        (bd_from_json(amount.get("value").test_unwrap()), amount.get("currency").and_then(|c| c.as_str()).test_unwrap().to_owned())
+    };
+    assert_eq!(quote_amount("fee"), (BigDecimal::from(20), "UAH".to_owned()));
+    assert_eq!(quote_amount("totalDebitAmount"), (BigDecimal::from(120), "UAH".to_owned()));
+    assert_eq!(quote_amount("creditAmount"), (BigDecimal::from_str("2.42").test_unwrap(), "USD".to_owned()));
+
+    let (status, outcome) = send_json(
+        client.post(format!("{base_url}{CLIENT_1_PATH}/transfer")).json(&transfer_request),
+        "vovan-read").await;
+    assert_eq!(status, 201);
+    assert_eq!(outcome.get("status").test_unwrap(), "COMPLETED");
+
+    let (total_debit_amount, _) = quote_amount("totalDebitAmount");
+    let (credit_amount, _) = quote_amount("creditAmount");
+    assert_eq!(get_account_amount(&client, &base_url, UAH_ACCOUNT_1).await, uah_before - total_debit_amount);
+    assert_eq!(get_account_amount(&client, &base_url, USD_ACCOUNT_1).await, usd_before + credit_amount);
+}
+
+
+/// Transfer above approval threshold waits for approval by another user.
+async fn test_pending_transfer_approval(account_soa_port: u16) {
+
+    let base_url = format!("https://localhost:{account_soa_port}");
+    let client = create_https_client();
+
+    let amount_1_before = get_account_amount(&client, &base_url, UAH_ACCOUNT_1).await;
+
+    let (status, outcome) = send_json(
+        client.post(format!("{base_url}{CLIENT_1_PATH}/transfer"))
+            .json(&uah_transfer_request(UAH_ACCOUNT_1, UAH_ACCOUNT_2, 500000)),
+        "vovan-read-super-user").await;
+    assert_eq!(status, 202);
+    assert_eq!(outcome.get("status").test_unwrap(), "PENDING_APPROVAL");
+    assert!(outcome.get("transferId").is_none());
+    assert_eq!(outcome.pointer("/pendingTransfer/status").test_unwrap(), "PENDING");
+    let pending_transfer_id = outcome.pointer("/pendingTransfer/id").and_then(|id| id.as_str()).test_unwrap();
+
+    let pending_transfer_url = format!("{base_url}{CLIENT_1_PATH}/pending-transfer/{pending_transfer_id}");
+
+    // Creator cannot approve own transfer.
+    let (status, _) = send_json(client.post(format!("{pending_transfer_url}/approval")),
+        "vovan-read-super-user").await;
+    assert_eq!(status, 403);
+
+    let (status, pending_transfer) = send_json(
+        client.post(format!("{pending_transfer_url}/rejection")).json(&json!({ "reason": "Too large amount" })),
+        "vovan-super-user").await;
+    assert_eq!(status, 200);
+    assert_eq!(pending_transfer.get("status").test_unwrap(), "REJECTED");
+    assert_eq!(pending_transfer.get("decidedBy").test_unwrap(), "vovan-super-user");
+
+    // Already decided.
+    let (status, _) = send_json(client.post(format!("{pending_transfer_url}/approval")),
+        "vovan-super-user").await;
+    assert_eq!(status, 409);
+
+    // Hold above approval threshold could not be captured.
+    let (status, _) = send_json(
+        client.post(format!("{base_url}{CLIENT_1_PATH}/account/{UAH_ACCOUNT_1}/hold")).json(&json!({
+            "amount": { "value": 500000, "currency": "UAH" },
+            "ttl_seconds": 60,
+        })),
+        "vovan-write").await;
+    assert_eq!(status, 422);
+
+    assert_eq!(get_account_amount(&client, &base_url, UAH_ACCOUNT_1).await, amount_1_before);
+}
+
+
+/// Webhook is delivered with 'v1=' + hex(HMAC-SHA256(secret, '{timestamp}.{body}')) signature.
+async fn test_webhook_signature(account_soa_port: u16) {
+    use axum::http::{ HeaderMap, StatusCode };
+    use hmac::{ Hmac, Mac };
+
+    const SECRET: &str = "integration-test-webhook-secret";
+
+    // Receiver is run on docker host (container reaches it by 'host.docker.internal').
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<(HeaderMap, String)>();
+    let listener = tokio::net::TcpListener::bind("0.0.0.0:0").await.test_unwrap();
+    let receiver_port = listener.local_addr().test_unwrap().port();
+    let receiver_app = axum::Router::new()
+        .route("/webhook", axum::routing::post(move |headers: HeaderMap, body: String| async move {
+            let _ = sender.send((headers, body));
+            StatusCode::OK
+        }));
+    let _receiver_task = tokio::spawn(async move { axum::serve(listener, receiver_app).await });
+
+    let base_url = format!("https://localhost:{account_soa_port}");
+    let client = create_https_client();
+
+    let (status, subscription) = send_json(
+        client.post(format!("{base_url}{CLIENT_1_PATH}/webhook")).json(&json!({
+            "url": format!("http://host.docker.internal:{receiver_port}/webhook"),
+            "secret": SECRET,
+            "event_types": ["TRANSFER_OUTGOING"],
+        })),
+        "vovan-write").await;
+    assert_eq!(status, 201);
+    assert!(subscription.get("secret").is_none(), "Secret should never be returned.");
+    let subscription_id = subscription.get("id").and_then(|id| id.as_str()).test_unwrap().to_owned();
+
+    let (status, outcome) = send_json(
+        client.post(format!("{base_url}{CLIENT_1_PATH}/transfer"))
+            .json(&uah_transfer_request(UAH_ACCOUNT_1, UAH_ACCOUNT_2, 1)),
+        "vovan-read").await;
+    assert_eq!(status, 201);
+    let transfer_id = outcome.get("transferId").test_unwrap().clone();
+
+    let (headers, body) = tokio::time::timeout(Duration::from_secs(30), async {
+        loop {
+            let (headers, body) = receiver.recv().await.test_unwrap();
+            let payload: serde_json::Value = serde_json::from_str(&body).test_unwrap();
+            if payload.get("transferId") == Some(&transfer_id) {
+                break (headers, body);
+            }
+        }
+    }).await.test_unwrap();
+
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok()).test_unwrap().to_owned();
+    assert_eq!(header("X-Webhook-Event-Type"), "TRANSFER_OUTGOING");
+
+    let timestamp = header("X-Webhook-Timestamp");
+    let timestamp_secs: i64 = timestamp.parse().test_unwrap();
+    assert!((chrono::Utc::now().timestamp() - timestamp_secs).abs() < 60, "Webhook timestamp is too old.");
+
+    let mut mac = <Hmac<sha2::Sha256> as Mac>::new_from_slice(SECRET.as_bytes()).test_unwrap();
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+    let expected_signature = format!("v1={}", hex::encode(mac.finalize().into_bytes()));
+    assert_eq!(header("X-Webhook-Signature"), expected_signature);
+
+    let (status, _) = send_json(
+        client.delete(format!("{base_url}{CLIENT_1_PATH}/webhook/{subscription_id}")),
+        "vovan-write").await;
+    assert_ge!(status, 200);
+    assert!(status < 300, "Unexpected status {status} of webhook subscription deletion.");
+}
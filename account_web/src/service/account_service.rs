@@ -20,8 +20,8 @@ use crate::rest_dependencies::account_soa_client::{
 pub trait AccountService {
     async fn get_client_accounts(&self, client_id: &str) -> Result<Vec<Account>, RestCallError>;
     // async fn get_client_account(&self, client_id: &str, account_id: &str) -> anyhow::Result<Account>;
-    // async fn transfer_amount(&self, client_id: &str, from_account_id: String, to_account_id: String, amount: Amount)
-    //     -> anyhow::Result<()>;
+    // async fn transfer_amount(&self, client_id: &str, from_account_id: String, to_account_id: String, amount: Amount,
+    //                          idempotency_key: Option<&str>) -> anyhow::Result<TransferAmountResponse>;
 }
 
 
@@ -45,14 +45,17 @@ impl AccountService for AccountServiceImpl {
     //     Ok(r.into_inner())
     // }
     //
-    // async fn transfer_amount(&self, client_id: &str, from_account_id: String, to_account_id: String, amount: Amount) -> anyhow::Result<()> {
-    //     self.client.transfer_amount(client_id, &TransferAmountRequest {
+    // async fn transfer_amount(&self, client_id: &str, from_account_id: String, to_account_id: String, amount: Amount,
+    //                          idempotency_key: Option<&str>) -> anyhow::Result<TransferAmountResponse> {
+    //     // Use the same idempotency key for retries of the same transfer.
+    //     let r = self.client.transfer_amount(client_id, idempotency_key, &TransferAmountRequest {
     //         from_account: from_account_id,
     //         to_account: to_account_id,
     //         amount: amount.value,
     //         currency: amount.currency,
     //     }).await
-    //     .map_err(improve_prog_err)
+    //     .map_err(improve_prog_err) ?;
+    //     Ok(r.into_inner())
     // }
 }
 
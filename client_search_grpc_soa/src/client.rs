@@ -1,4 +1,5 @@
 use std::io::Write;
+use anyhow::anyhow;
 use chrono::Datelike;
 use mvv_common::{
     generate_diesel2_pg_from_sql_from_str, generate_diesel2_pg_to_sql_from_as_str,
@@ -29,6 +30,7 @@ pub struct ClientInfo {
     pub active: bool,
     pub business_user: bool,
     pub super_business_user: bool,
+    pub version: i64,
 }
 
 impl TryFrom<ClientInfo> for GrpcClient {
@@ -66,12 +68,82 @@ impl ClientInfo {
             }),
             active: self.active,
             client_type: client_type as i32,
+            version: self.version,
         })
     }
 }
 
 
-#[derive(Debug)]
+/// Changed client fields (only 'Some' fields are updated).
+#[derive(Debug, Default, PartialEq, diesel::AsChangeset)]
+#[diesel(table_name = crate::schema::CLIENTS)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ClientChange {
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub phone_type: Option<PhoneType>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub active: Option<bool>,
+}
+
+const NAME_MAX_LEN: usize = 100;
+
+impl ClientChange {
+    /// Takes from 'client' only fields specified by field mask paths.
+    pub fn try_from_grpc(client: &GrpcClient, update_paths: &[String]) -> anyhow::Result<Self> {
+        if update_paths.is_empty() {
+            anyhow::bail!("Update mask is empty.");
+        }
+
+        let mut change = ClientChange::default();
+        for path in update_paths {
+            match path.as_str() {
+                "email" => {
+                    let Some(Email::EmailValue(ref email)) = client.email
+                        else { anyhow::bail!("Email is not specified.") };
+                    let email = email.trim();
+                    if email.is_empty() || !email.contains('@') {
+                        anyhow::bail!("Invalid email [{email}].");
+                    }
+                    change.email = Some(email.to_lowercase());
+                }
+                "phones" => {
+                    let [phone] = client.phones.as_slice()
+                        else { anyhow::bail!("Exactly one phone is expected (but {} are specified).", client.phones.len()) };
+                    let number = phone.number.as_deref().map(str::trim).unwrap_or_default();
+                    if number.is_empty() {
+                        anyhow::bail!("Phone number is not specified.");
+                    }
+                    let phone_type = GrpcPhoneType::try_from(phone.r#type)
+                        .map_err(|_| anyhow!("Unexpected phone type [{}].", phone.r#type))
+                        .and_then(PhoneType::try_from_grpc) ?;
+                    change.phone = Some(number.to_owned());
+                    change.phone_type = Some(phone_type);
+                }
+                "first_name" => change.first_name = Some(validate_name("First name", &client.first_name) ?),
+                "last_name" => change.last_name = Some(validate_name("Last name", &client.last_name) ?),
+                "active" => change.active = Some(client.active),
+                other => anyhow::bail!("Field [{other}] cannot be updated."),
+            }
+        }
+        Ok(change)
+    }
+}
+
+fn validate_name(label: &str, name: &str) -> anyhow::Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        anyhow::bail!("{label} is not specified.");
+    }
+    if name.chars().count() > NAME_MAX_LEN {
+        anyhow::bail!("{label} is too long (max {NAME_MAX_LEN} chars).");
+    }
+    Ok(name.to_owned())
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 #[derive(strum_macros::Display)]
 #[derive(diesel::AsExpression, diesel::FromSqlRow)]
 #[diesel(sql_type = diesel::sql_types::VarChar)]
@@ -113,6 +185,15 @@ impl PhoneType {
             PhoneType::Business => Ok(GrpcPhoneType::Business),
         }
     }
+    pub fn try_from_grpc(phone_type: GrpcPhoneType) -> anyhow::Result<Self> {
+        match phone_type {
+            GrpcPhoneType::Mobile => Ok(PhoneType::Mobile),
+            GrpcPhoneType::Home => Ok(PhoneType::Home),
+            GrpcPhoneType::Work => Ok(PhoneType::Work),
+            GrpcPhoneType::Business => Ok(PhoneType::Business),
+            GrpcPhoneType::Unspecified => Err(anyhow!("Phone type is not specified.")),
+        }
+    }
 }
 impl TryFrom<PhoneType> for GrpcPhoneType {
     type Error = anyhow::Error;
@@ -189,7 +270,71 @@ impl diesel::deserialize::FromSql<diesel::sql_types::VarChar, diesel::pg::Pg> fo
 
 #[cfg(test)]
 mod tests {
+    use mvv_auth::util::test_unwrap::TestResultUnwrap;
+    use crate::grpc::mvv::client::search::api::v1::{
+        {Client as GrpcClient, PhoneNumber},
+        client::Email,
+        phone_number::PhoneType as GrpcPhoneType,
+    };
+    use super::{ ClientChange, PhoneType };
+
     #[test]
     fn verify_compilation_success() {
     }
+
+    fn paths(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|path| path.to_string()).collect()
+    }
+
+    fn grpc_client() -> GrpcClient {
+        GrpcClient {
+            email: Some(Email::EmailValue(" Cheburan@Ukr.Net ".to_owned())),
+            phones: vec!(PhoneNumber { number: Some("+380671234567".to_owned()), r#type: GrpcPhoneType::Work as i32 }),
+            first_name: " Cheburan ".to_owned(),
+            last_name: "Cheburashka".to_owned(),
+            active: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn client_change_contains_only_masked_fields() {
+        let change = ClientChange::try_from_grpc(&grpc_client(), &paths(&["email", "first_name"])).test_unwrap();
+        assert_eq!(change, ClientChange {
+            email: Some("cheburan@ukr.net".to_owned()),
+            first_name: Some("Cheburan".to_owned()),
+            ..Default::default()
+        });
+
+        let change = ClientChange::try_from_grpc(&grpc_client(), &paths(&["phones", "last_name", "active"])).test_unwrap();
+        assert_eq!(change, ClientChange {
+            phone: Some("+380671234567".to_owned()),
+            phone_type: Some(PhoneType::Work),
+            last_name: Some("Cheburashka".to_owned()),
+            active: Some(true),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn client_change_with_bad_mask_or_values() {
+        assert!(ClientChange::try_from_grpc(&grpc_client(), &[]).is_err());
+        assert!(ClientChange::try_from_grpc(&grpc_client(), &paths(&["birthday"])).is_err());
+        assert!(ClientChange::try_from_grpc(&grpc_client(), &paths(&["id"])).is_err());
+
+        let client = GrpcClient { email: None, ..grpc_client() };
+        assert!(ClientChange::try_from_grpc(&client, &paths(&["email"])).is_err());
+
+        let client = GrpcClient { first_name: "  ".to_owned(), ..grpc_client() };
+        assert!(ClientChange::try_from_grpc(&client, &paths(&["first_name"])).is_err());
+
+        let client = GrpcClient { phones: vec!(), ..grpc_client() };
+        assert!(ClientChange::try_from_grpc(&client, &paths(&["phones"])).is_err());
+
+        let client = GrpcClient {
+            phones: vec!(PhoneNumber { number: Some("+380671234567".to_owned()), r#type: GrpcPhoneType::Unspecified as i32 }),
+            ..grpc_client()
+        };
+        assert!(ClientChange::try_from_grpc(&client, &paths(&["phones"])).is_err());
+    }
 }
\ No newline at end of file
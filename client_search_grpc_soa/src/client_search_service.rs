@@ -4,6 +4,7 @@ use anyhow::anyhow;
 use chrono::Datelike;
 use diesel::{Connection, PgConnection};
 use implicit_clone::ImplicitClone;
+use tokio_stream::Stream;
 use tonic::{Request, Response, Status};
 use mvv_auth::permission::PermissionSet;
 use mvv_common::{
@@ -12,11 +13,14 @@ use mvv_common::{
 };
 use crate::{
     auth::{Role, RolePermissionsSet},
-    client::ClientInfo,
+    client::{ClientChange, ClientInfo},
     dependencies::{Dependencies},
+    search_page::{ClientOrder, ClientOrderField, PageCursor, SearchPage, SortDirection, MAX_PAGE_SIZE},
+    stream_static_wrapper::StreamStaticWrapper,
 };
 use crate::grpc::mvv::client::search::api::v1::{
     {Client, ClientSearchRequest, ClientSearchResponse, GetClientByIdRequest, GetClientByIdResponse},
+    {UpdateClientRequest, UpdateClientResponse},
     client_search_service_server::ClientSearchService as ClientSearchServiceTrait,
 };
 //--------------------------------------------------------------------------------------------------
@@ -50,6 +54,7 @@ impl ClientSearchService {
         let read_write_permissions = RolePermissionsSet::from_permissions([Role::Read, Role::Write]);
         HashMap::from([
             ("/mvv.client.search.api.v1.ClientSearchService/Search".into(), read_permissions.implicit_clone()),
+            ("/mvv.client.search.api.v1.ClientSearchService/SearchStream".into(), read_permissions.implicit_clone()),
             ("/mvv.client.search.api.v1.ClientSearchService/GetClientById".into(), read_permissions.implicit_clone()),
             ("/mvv.client.search.api.v1.ClientSearchService/UpdateClient".into(), read_write_permissions.implicit_clone()),
         ])
@@ -64,7 +69,8 @@ impl ClientSearchService {
 
 
 impl ClientSearchService {
-    async fn do_search(&self, request: Request<ClientSearchRequest>) -> anyhow::Result<Vec<Client>> {
+    async fn do_search(&self, request: Request<ClientSearchRequest>, page: SearchPage)
+        -> anyhow::Result<ClientSearchResponse> {
 
         // RustlsAcceptorFuture::
 
@@ -83,58 +89,60 @@ impl ClientSearchService {
         let request = request.get_ref();
 
         use diesel::prelude::*;
-        // use diesel_async::;
-        // use crate::schema::*;
-        use crate::schema::CLIENTS::dsl::*;
 
-        let mut query = CLIENTS
-            .select(ClientInfo::as_select())
-            .into_boxed();
+        let total_size: i64 = filtered_clients_query(request) ?
+            .count()
+            .get_result(&mut con) ?;
 
-        if let Some(ref email_value) = request.user_email {
-            query = query.filter(email.eq(email_value.to_lowercase()))
-        };
+        // +1 to know whether there is next page
+        let mut results = load_clients(&mut con, request, &page, page.page_size + 1) ?;
 
-        if let Some(ref first_name_value) = request.first_name {
-            // let first_name_value_lc = first_name_value.to_lowercase();
-            query = query.filter(first_name.ilike(first_name_value.as_str()))
-        };
+        let next_page_token =
+            if results.len() as i64 > page.page_size {
+                results.truncate(page.page_size as usize);
+                results.last().map(|last| PageCursor::after(page.order, last).to_page_token())
+            } else {
+                None
+            };
 
-        if let Some(ref last_name_value) = request.last_name {
-            query = query.filter(last_name.ilike(last_name_value.as_str()))
-        };
+        let clients: Vec<Client> = results.into_iter()
+            .map(|client|client.try_into())
+            .collect::<Result<Vec<Client>, _>>() ?;
 
-        if let Some(age) = request.age {
-            let now: chrono::NaiveDate = chrono::Local::now().naive_local().date();
-            let birthday_from: chrono::NaiveDate =
-                if now.month() == 2 && now.day() == 29 {
-                    let birthday_from = now.with_year(now.year() - age);
-                    match birthday_from {
-                        None =>
-                            // second attempt, see NaiveDate doc
-                            now.with_day(28)
-                                .ok_or_else(|| anyhow!("Internal error of processing 'age' (setting day).")) ?
-                                .with_year(now.year() - age)
-                                .ok_or_else(|| anyhow!("Internal error of processing 'age' (setting year).")) ?,
-                        Some(birthday_from) => birthday_from,
-                    }
-                } else {
-                    now.with_year(now.year() - age)
-                        .ok_or_else(||anyhow!("Internal error of processing 'age' (setting year).")) ?
+        Ok(ClientSearchResponse { success: true, message: None, clients, next_page_token, total_size })
+    }
+
+    /// Clients are loaded by batches (keyset pagination), and next batch is loaded only
+    /// after previous one is sent (tonic polls stream only when transport is ready),
+    /// so only one batch is kept in memory and DB connection is not held between batches.
+    fn do_search_stream(&self, request: ClientSearchRequest, order: ClientOrder)
+        -> impl Stream<Item = Result<Client, Status>> + Send + 'static {
+
+        let dependencies = Arc::clone(&self.dependencies);
+
+        async_stream::try_stream! {
+            let mut page = SearchPage { page_size: SEARCH_STREAM_BATCH_SIZE, order, cursor: None };
+            loop {
+                let batch = {
+                    let mut con = dependencies.diesel_db_pool.get()
+                        .to_tonic_internal_err("Client search stream error") ?;
+                    load_clients(&mut con, &request, &page, page.page_size)
+                        .to_tonic_internal_err("Client search stream error") ?
                 };
 
-            query = query.filter(birthday.ge(birthday_from));
-        }
+                let is_last_batch = (batch.len() as i64) < page.page_size;
+                let next_cursor = batch.last().map(|last| PageCursor::after(page.order, last));
 
-        let results: Vec<ClientInfo> = query
-            .limit(5)
-            .load(&mut con) ?;
+                for client in batch {
+                    yield client.try_into_grpc().to_tonic_internal_err("Client search stream error") ?;
+                }
 
-        let clients: Vec<Client> = results.into_iter()
-            .map(|client|client.try_into())
-            .collect::<Result<Vec<Client>, _>>() ?;
-
-        Ok(clients)
+                match next_cursor {
+                    Some(next_cursor) if !is_last_batch => page.cursor = Some(next_cursor),
+                    _ => break,
+                }
+            }
+        }
     }
 
     async fn do_get_client_by_id(&self, client_id_value: &str) -> anyhow::Result<Option<Client>> {
@@ -157,26 +165,228 @@ impl ClientSearchService {
 
         Ok(client)
     }
+
+    /// Client is updated only if its version is still 'expected_version' (optimistic locking).
+    async fn do_update_client(&self, request: &UpdateClientRequest) -> Result<Client, Status> {
+
+        use core::str::FromStr;
+        use diesel::prelude::*;
+        use crate::schema::CLIENTS::dsl::*;
+
+        let client_id_uuid = uuid::Uuid::from_str(request.client_id.as_str())
+            .map_err(|_| Status::invalid_argument(format!("Invalid client ID [{}].", request.client_id))) ?;
+        let client = request.client.as_ref()
+            .ok_or_else(|| Status::invalid_argument("Client is not specified.")) ?;
+        let update_paths = request.update_mask.as_ref()
+            .map(|mask| mask.paths.as_slice())
+            .unwrap_or_default();
+        let change = ClientChange::try_from_grpc(client, update_paths)
+            .map_err(|err| Status::invalid_argument(err.to_string())) ?;
+
+        let mut con = self.dependencies.diesel_db_pool.get()
+            .to_tonic_internal_err("update_client error") ?;
+
+        let updated = diesel::update(CLIENTS)
+            .filter(client_id.eq(client_id_uuid).and(version.eq(request.expected_version)))
+            .set((&change, version.eq(version + 1)))
+            .returning(ClientInfo::as_returning())
+            .get_result(&mut con)
+            .optional();
+        let updated = map_update_client_db_err(updated) ?;
+
+        match updated {
+            Some(updated) => updated.try_into_grpc().to_tonic_internal_err("update_client error"),
+            None => {
+                let current_version: Option<i64> = CLIENTS
+                    .select(version)
+                    .filter(client_id.eq(client_id_uuid))
+                    .first(&mut con)
+                    .optional()
+                    .to_tonic_internal_err("update_client error") ?;
+
+                match current_version {
+                    None => Err(Status::not_found(format!("Client [{client_id_uuid}] is not found."))),
+                    Some(current_version) => Err(Status::aborted(format!(
+                        "Client [{client_id_uuid}] was already changed (expected version {}, current version {current_version}).",
+                        request.expected_version))),
+                }
+            }
+        }
+    }
+}
+
+
+fn map_update_client_db_err<T>(result: Result<T, diesel::result::Error>) -> Result<T, Status> {
+    use diesel::result::{ DatabaseErrorKind, Error as DieselError };
+
+    match result {
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, ref info)) => {
+            let field = match info.constraint_name() {
+                Some("clients_email_key") => "Email",
+                Some("clients_phone_key") => "Phone",
+                _ => "Email or phone",
+            };
+            Err(Status::already_exists(format!("{field} is already used by another client.")))
+        }
+        Err(DieselError::DatabaseError(DatabaseErrorKind::CheckViolation, ref info)) =>
+            Err(Status::invalid_argument(format!("Invalid client data ({}).", info.message()))),
+        other => other.to_tonic_internal_err("update_client error"),
+    }
 }
 
 
+/// Count of clients loaded from DB at once by SearchStream.
+const SEARCH_STREAM_BATCH_SIZE: i64 = MAX_PAGE_SIZE;
+
+
+fn load_clients(con: &mut PgConnection, request: &ClientSearchRequest, page: &SearchPage, limit: i64)
+    -> anyhow::Result<Vec<ClientInfo>> {
+
+    use diesel::prelude::*;
+
+    let clients = ordered_clients_query(filtered_clients_query(request) ?, page) ?
+        .select(ClientInfo::as_select())
+        .limit(limit)
+        .load(con) ?;
+    Ok(clients)
+}
+
+
+type ClientsQuery<'a> = crate::schema::CLIENTS::BoxedQuery<'a, diesel::pg::Pg>;
+
+fn filtered_clients_query(request: &ClientSearchRequest) -> anyhow::Result<ClientsQuery<'_>> {
+
+    use diesel::prelude::*;
+    use crate::schema::CLIENTS::dsl::*;
+
+    let mut query = CLIENTS.into_boxed();
+
+    if let Some(ref email_value) = request.user_email {
+        query = query.filter(email.eq(email_value.to_lowercase()))
+    };
+
+    if let Some(ref first_name_value) = request.first_name {
+        // let first_name_value_lc = first_name_value.to_lowercase();
+        query = query.filter(first_name.ilike(first_name_value.as_str()))
+    };
+
+    if let Some(ref last_name_value) = request.last_name {
+        query = query.filter(last_name.ilike(last_name_value.as_str()))
+    };
+
+    if let Some(age) = request.age {
+        let now: chrono::NaiveDate = chrono::Local::now().naive_local().date();
+        let birthday_from: chrono::NaiveDate =
+            if now.month() == 2 && now.day() == 29 {
+                let birthday_from = now.with_year(now.year() - age);
+                match birthday_from {
+                    None =>
+                        // second attempt, see NaiveDate doc
+                        now.with_day(28)
+                            .ok_or_else(|| anyhow!("Internal error of processing 'age' (setting day).")) ?
+                            .with_year(now.year() - age)
+                            .ok_or_else(|| anyhow!("Internal error of processing 'age' (setting year).")) ?,
+                    Some(birthday_from) => birthday_from,
+                }
+            } else {
+                now.with_year(now.year() - age)
+                    .ok_or_else(||anyhow!("Internal error of processing 'age' (setting year).")) ?
+            };
+
+        query = query.filter(birthday.ge(birthday_from));
+    }
+
+    Ok(query)
+}
+
+
+/// Keyset pagination: clients are ordered by requested field and client_id,
+/// and the next page starts just after the cursor (last client of previous page).
+fn ordered_clients_query<'a>(mut query: ClientsQuery<'a>, page: &SearchPage) -> anyhow::Result<ClientsQuery<'a>> {
+
+    use diesel::prelude::*;
+    use crate::schema::CLIENTS::dsl::*;
+
+    macro_rules! keyset_order {
+        ($column:expr, $cursor:ident => $cursor_value:expr) => {
+            match page.order.direction {
+                SortDirection::Asc => {
+                    if let Some($cursor) = page.cursor.as_ref() {
+                        query = query.filter($column.gt($cursor_value)
+                            .or($column.eq($cursor_value).and(client_id.gt($cursor.client_id))));
+                    }
+                    query.order(($column.asc(), client_id.asc()))
+                }
+                SortDirection::Desc => {
+                    if let Some($cursor) = page.cursor.as_ref() {
+                        query = query.filter($column.lt($cursor_value)
+                            .or($column.eq($cursor_value).and(client_id.lt($cursor.client_id))));
+                    }
+                    query.order(($column.desc(), client_id.desc()))
+                }
+            }
+        };
+    }
+
+    let query = match page.order.field {
+        ClientOrderField::ClientId => match page.order.direction {
+            SortDirection::Asc => {
+                if let Some(ref cursor) = page.cursor {
+                    query = query.filter(client_id.gt(cursor.client_id));
+                }
+                query.order(client_id.asc())
+            }
+            SortDirection::Desc => {
+                if let Some(ref cursor) = page.cursor {
+                    query = query.filter(client_id.lt(cursor.client_id));
+                }
+                query.order(client_id.desc())
+            }
+        },
+        ClientOrderField::Email => keyset_order!(email, cursor => cursor.value.clone()),
+        ClientOrderField::FirstName => keyset_order!(first_name, cursor => cursor.value.clone()),
+        ClientOrderField::LastName => keyset_order!(last_name, cursor => cursor.value.clone()),
+        ClientOrderField::Birthday => keyset_order!(birthday, cursor => cursor.birthday_value() ?),
+    };
+    Ok(query)
+}
+
+
+
 #[tonic::async_trait]
 impl ClientSearchServiceTrait for ClientSearchService {
 
     async fn search(&self, request: Request<ClientSearchRequest>) -> Result<Response<ClientSearchResponse>, Status> {
-        self.do_search(request).await
-            .map(|clients| Response::new(
-                ClientSearchResponse { success: true, message: None, clients }))
+        let search_request = request.get_ref();
+        let page = SearchPage::try_from_request(
+                search_request.page_size,
+                search_request.page_token.as_deref(),
+                search_request.order_by.as_deref())
+            .map_err(|err| Status::invalid_argument(err.to_string())) ?;
+
+        self.do_search(request, page).await
+            .map(Response::new)
             .to_tonic_internal_err("Client search error")
     }
 
+    type SearchStreamStream = StreamStaticWrapper<Result<Client, Status>>;
+
+    async fn search_stream(&self, request: Request<ClientSearchRequest>) -> Result<Response<Self::SearchStreamStream>, Status> {
+        let order = ClientOrder::parse(request.get_ref().order_by.as_deref().unwrap_or_default())
+            .map_err(|err| Status::invalid_argument(err.to_string())) ?;
+
+        let clients = self.do_search_stream(request.into_inner(), order);
+        Ok(Response::new(StreamStaticWrapper::new(clients)))
+    }
+
     async fn get_client_by_id(&self, request: Request<GetClientByIdRequest>) -> Result<Response<GetClientByIdResponse>, Status> {
         self.do_get_client_by_id(request.get_ref().client_id.as_str()).await
             .map(|client| Response::new(GetClientByIdResponse { client }))
             .to_tonic_internal_err("get_client_by_id error")
     }
 
-    async fn update_client(&self, _request: Request<ClientSearchRequest>) -> Result<Response<ClientSearchResponse>, Status> {
-        todo!()
+    async fn update_client(&self, request: Request<UpdateClientRequest>) -> Result<Response<UpdateClientResponse>, Status> {
+        self.do_update_client(request.get_ref()).await
+            .map(|client| Response::new(UpdateClientResponse { client: Some(client) }))
     }
 }
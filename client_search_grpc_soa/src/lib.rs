@@ -3,6 +3,7 @@ mod client_search_service;
 mod client;
 mod dependencies;
 mod schema;
+mod search_page;
 pub mod app;
 pub mod cfg;
 pub mod grpc;
@@ -1,3 +1,5 @@
+// It should be in sync with CLIENTS table of account_soa migrations
+// (account_soa/migrations/012_create_clients_tables.sql, 027_add_clients_version.sql), see test below.
 
 
 diesel::table! {
@@ -18,5 +20,69 @@ diesel::table! {
         active -> Bool,
         business_user -> Bool,
         super_business_user -> Bool,
+        version -> Int8,
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use diesel::Column;
+    use super::CLIENTS;
+
+    /// Column names of 'create table {table_name} (...)' statement.
+    fn migration_table_columns(migration_sql: &str, table_name: &str) -> Vec<String> {
+        let create_table = format!("create table {table_name} (");
+        let table_start = migration_sql.find(&create_table)
+            .unwrap_or_else(|| panic!("Table {table_name} is not found in migration"));
+
+        migration_sql[table_start + create_table.len()..]
+            .lines()
+            .map(str::trim)
+            .take_while(|line| !line.starts_with(");"))
+            .filter(|line| !line.is_empty() && !line.starts_with("--") && !line.starts_with("constraint "))
+            .filter_map(|line| line.split_whitespace().next())
+            .map(|column| column.to_lowercase())
+            .collect()
+    }
+
+    /// Column names of 'alter table {table_name} add column ...' statements.
+    fn migration_added_columns(migration_sql: &str, table_name: &str) -> Vec<String> {
+        let add_column = format!("alter table {table_name} add column ");
+        migration_sql
+            .lines()
+            .map(str::trim)
+            .filter_map(|line| line.strip_prefix(add_column.as_str()))
+            .filter_map(|line| line.split_whitespace().next())
+            .map(|column| column.to_lowercase())
+            .collect()
+    }
+
+    #[test]
+    fn clients_table_is_in_sync_with_migrations() {
+        let migration_sql = include_str!("../../account_soa/migrations/012_create_clients_tables.sql");
+        let version_migration_sql = include_str!("../../account_soa/migrations/027_add_clients_version.sql");
+
+        let mut migration_columns = migration_table_columns(migration_sql, "CLIENTS");
+        migration_columns.extend(migration_added_columns(version_migration_sql, "CLIENTS"));
+        migration_columns.sort();
+
+        let mut schema_columns = vec![
+            CLIENTS::client_id::NAME,
+            CLIENTS::email::NAME,
+            CLIENTS::first_name::NAME,
+            CLIENTS::last_name::NAME,
+            CLIENTS::phone::NAME,
+            CLIENTS::phone_type::NAME,
+            CLIENTS::birthday::NAME,
+            CLIENTS::active::NAME,
+            CLIENTS::business_user::NAME,
+            CLIENTS::super_business_user::NAME,
+            CLIENTS::version::NAME,
+        ];
+        schema_columns.sort();
+
+        assert_eq!(migration_columns, schema_columns);
     }
 }
@@ -0,0 +1,305 @@
+use core::fmt::Write;
+use core::str::FromStr;
+use anyhow::anyhow;
+use crate::client::ClientInfo;
+//--------------------------------------------------------------------------------------------------
+
+
+
+/// It is used if request does not have page_size (the same count as search returned before paging).
+pub const DEFAULT_PAGE_SIZE: i64 = 5;
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+const PAGE_TOKEN_VERSION: &str = "v1";
+const BIRTHDAY_FORMAT: &str = "%Y-%m-%d";
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientOrderField {
+    ClientId,
+    Email,
+    FirstName,
+    LastName,
+    Birthday,
+}
+impl ClientOrderField {
+    fn as_field_name(&self) -> &'static str {
+        match self {
+            ClientOrderField::ClientId => "client_id",
+            ClientOrderField::Email => "email",
+            ClientOrderField::FirstName => "first_name",
+            ClientOrderField::LastName => "last_name",
+            ClientOrderField::Birthday => "birthday",
+        }
+    }
+    fn from_field_name(field_name: &str) -> anyhow::Result<Self> {
+        match field_name {
+            "client_id" => Ok(ClientOrderField::ClientId),
+            "email" => Ok(ClientOrderField::Email),
+            "first_name" => Ok(ClientOrderField::FirstName),
+            "last_name" => Ok(ClientOrderField::LastName),
+            "birthday" => Ok(ClientOrderField::Birthday),
+            other => Err(anyhow!("Clients cannot be ordered by [{other}].")),
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+
+/// Clients with equal field value are additionally ordered by client_id
+/// (in the same direction) to have deterministic pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientOrder {
+    pub field: ClientOrderField,
+    pub direction: SortDirection,
+}
+
+impl Default for ClientOrder {
+    fn default() -> Self {
+        ClientOrder { field: ClientOrderField::ClientId, direction: SortDirection::Asc }
+    }
+}
+
+impl ClientOrder {
+    /// Format: "{field}" or "{field} asc|desc", empty string means default order.
+    pub fn parse(order_by: &str) -> anyhow::Result<Self> {
+        let mut parts = order_by.split_whitespace();
+
+        let Some(field) = parts.next()
+            else { return Ok(ClientOrder::default()) };
+        let field = ClientOrderField::from_field_name(field) ?;
+
+        let direction = match parts.next() {
+            None => SortDirection::Asc,
+            Some(direction) if direction.eq_ignore_ascii_case("asc") => SortDirection::Asc,
+            Some(direction) if direction.eq_ignore_ascii_case("desc") => SortDirection::Desc,
+            Some(other) => anyhow::bail!("Unexpected sort direction [{other}]."),
+        };
+
+        if parts.next().is_some() {
+            anyhow::bail!("Clients can be ordered only by one field (order_by: [{order_by}]).");
+        }
+        Ok(ClientOrder { field, direction })
+    }
+
+    fn as_token_str(&self) -> String {
+        let direction = match self.direction {
+            SortDirection::Asc => "asc",
+            SortDirection::Desc => "desc",
+        };
+        format!("{} {direction}", self.field.as_field_name())
+    }
+}
+
+
+/// Keyset cursor, it points to the last client of previous page
+/// (next page starts just after it).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageCursor {
+    pub order: ClientOrder,
+    pub client_id: uuid::Uuid,
+    /// Value of ordered field of the last client (empty for client_id order).
+    pub value: String,
+}
+
+impl PageCursor {
+    pub fn after(order: ClientOrder, client: &ClientInfo) -> Self {
+        let value = match order.field {
+            ClientOrderField::ClientId => String::new(),
+            ClientOrderField::Email => client.email.clone(),
+            ClientOrderField::FirstName => client.first_name.clone(),
+            ClientOrderField::LastName => client.last_name.clone(),
+            ClientOrderField::Birthday => client.birthday.format(BIRTHDAY_FORMAT).to_string(),
+        };
+        PageCursor { order, client_id: client.client_id, value }
+    }
+
+    pub fn birthday_value(&self) -> anyhow::Result<chrono::NaiveDate> {
+        chrono::NaiveDate::parse_from_str(self.value.as_str(), BIRTHDAY_FORMAT)
+            .map_err(|_| anyhow!("Invalid page token (birthday [{}]).", self.value))
+    }
+
+    /// Token is opaque for clients (its format can be changed at any time).
+    pub fn to_page_token(&self) -> String {
+        let token = format!("{PAGE_TOKEN_VERSION}\n{}\n{}\n{}", self.order.as_token_str(), self.client_id, self.value);
+        to_hex(token.as_bytes())
+    }
+
+    pub fn from_page_token(page_token: &str) -> anyhow::Result<Self> {
+        let invalid_token = || anyhow!("Invalid page token.");
+
+        let token = from_hex(page_token).ok_or_else(invalid_token) ?;
+        let token = String::from_utf8(token).map_err(|_| invalid_token()) ?;
+
+        let mut parts = token.splitn(4, '\n');
+        let (Some(PAGE_TOKEN_VERSION), Some(order), Some(client_id), Some(value)) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+            else { return Err(invalid_token()) };
+
+        let cursor = PageCursor {
+            order: ClientOrder::parse(order).map_err(|_| invalid_token()) ?,
+            client_id: uuid::Uuid::from_str(client_id).map_err(|_| invalid_token()) ?,
+            value: value.to_owned(),
+        };
+        if cursor.order.field == ClientOrderField::Birthday {
+            cursor.birthday_value() ?;
+        }
+        Ok(cursor)
+    }
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchPage {
+    pub page_size: i64,
+    pub order: ClientOrder,
+    /// None for the first page.
+    pub cursor: Option<PageCursor>,
+}
+
+impl SearchPage {
+    /// Absent values (old clients do not send them) mean the first page of default size and order.
+    pub fn try_from_request(page_size: Option<i32>, page_token: Option<&str>, order_by: Option<&str>)
+        -> anyhow::Result<Self> {
+
+        let page_size = match page_size {
+            None | Some(0) => DEFAULT_PAGE_SIZE,
+            Some(page_size) if page_size < 0 => anyhow::bail!("Negative page size [{page_size}]."),
+            Some(page_size) => i64::from(page_size).min(MAX_PAGE_SIZE),
+        };
+
+        let order = order_by
+            .map(ClientOrder::parse)
+            .transpose() ?
+            .unwrap_or_default();
+
+        let cursor = page_token
+            .filter(|page_token| !page_token.is_empty())
+            .map(PageCursor::from_page_token)
+            .transpose() ?;
+
+        if let Some(ref cursor) = cursor {
+            if cursor.order != order {
+                anyhow::bail!("Page token was issued for another order ({}).", cursor.order.as_token_str());
+            }
+        }
+
+        Ok(SearchPage { page_size, order, cursor })
+    }
+}
+
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        let _ = write!(hex, "{b:02x}");
+    }
+    hex
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use mvv_auth::util::test_unwrap::{ TestOptionUnwrap, TestResultUnwrap };
+    use crate::client::{ ClientInfo, PhoneType };
+    use super::{
+        ClientOrder, ClientOrderField, PageCursor, SearchPage, SortDirection,
+        DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE,
+    };
+
+    fn client_info() -> ClientInfo {
+        ClientInfo {
+            client_id: uuid::Uuid::from_u128(1),
+            email: "cheburan@ukr.net".to_owned(),
+            first_name: "Cheburan".to_owned(),
+            last_name: "Cheburashka".to_owned(),
+            phone: "+380671234567".to_owned(),
+            phone_type: PhoneType::Mobile,
+            birthday: chrono::NaiveDate::from_ymd_opt(2000, 2, 29).test_unwrap(),
+            active: true,
+            business_user: false,
+            super_business_user: false,
+            version: 0,
+        }
+    }
+
+    #[test]
+    fn parse_order() {
+        assert_eq!(ClientOrder::parse("").test_unwrap(), ClientOrder::default());
+        assert_eq!(
+            ClientOrder::parse(" last_name  DESC ").test_unwrap(),
+            ClientOrder { field: ClientOrderField::LastName, direction: SortDirection::Desc },
+        );
+        assert_eq!(
+            ClientOrder::parse("birthday").test_unwrap(),
+            ClientOrder { field: ClientOrderField::Birthday, direction: SortDirection::Asc },
+        );
+        assert!(ClientOrder::parse("phone").is_err());
+        assert!(ClientOrder::parse("email up").is_err());
+        assert!(ClientOrder::parse("email asc, last_name").is_err());
+    }
+
+    #[test]
+    fn page_token_round_trip() {
+        for order_by in ["client_id", "email desc", "first_name", "last_name desc", "birthday"] {
+            let order = ClientOrder::parse(order_by).test_unwrap();
+            let cursor = PageCursor::after(order, &client_info());
+            assert_eq!(PageCursor::from_page_token(&cursor.to_page_token()).test_unwrap(), cursor);
+        }
+
+        let cursor = PageCursor::after(ClientOrder::parse("birthday").test_unwrap(), &client_info());
+        assert_eq!(cursor.value, "2000-02-29");
+        assert_eq!(cursor.birthday_value().test_unwrap(), client_info().birthday);
+    }
+
+    #[test]
+    fn invalid_page_token() {
+        assert!(PageCursor::from_page_token("not-a-token").is_err());
+        assert!(PageCursor::from_page_token("abc").is_err());
+        // valid hex, but not a token
+        assert!(PageCursor::from_page_token("6162").is_err());
+    }
+
+    #[test]
+    fn search_page_of_old_client_request() {
+        let page = SearchPage::try_from_request(None, None, None).test_unwrap();
+        assert_eq!(page, SearchPage { page_size: DEFAULT_PAGE_SIZE, order: ClientOrder::default(), cursor: None });
+
+        let page = SearchPage::try_from_request(Some(0), Some(""), Some("")).test_unwrap();
+        assert_eq!(page, SearchPage { page_size: DEFAULT_PAGE_SIZE, order: ClientOrder::default(), cursor: None });
+    }
+
+    #[test]
+    fn search_page_size_and_token() {
+        let page = SearchPage::try_from_request(Some(1000), None, None).test_unwrap();
+        assert_eq!(page.page_size, MAX_PAGE_SIZE);
+        assert!(SearchPage::try_from_request(Some(-1), None, None).is_err());
+
+        let order = ClientOrder::parse("last_name desc").test_unwrap();
+        let token = PageCursor::after(order, &client_info()).to_page_token();
+
+        let page = SearchPage::try_from_request(Some(10), Some(&token), Some("last_name desc")).test_unwrap();
+        assert_eq!(page.page_size, 10);
+        assert_eq!(page.cursor.test_unwrap().client_id, client_info().client_id);
+
+        // token of another order
+        assert!(SearchPage::try_from_request(Some(10), Some(&token), Some("last_name")).is_err());
+    }
+}
@@ -11,22 +11,48 @@ use pin_project_lite::pin_project;
 
 
 pin_project! {
-    pub struct FooStream<St>
-    where
-        St: Stream,
-    {
+    /// Wraps any (unnameable, like async_stream) 'static stream into named type,
+    /// which can be used as stream type of tonic server-streaming method.
+    pub struct StreamStaticWrapper<T> {
         #[pin]
-        inner: St,
+        inner: Pin<Box<dyn Stream<Item = T> + Send + 'static>>,
     }
 }
-impl<St: Stream> Stream for FooStream<St>
-where
-    St: Stream,
-{
-    type Item = St::Item;
+
+impl<T> StreamStaticWrapper<T> {
+    pub fn new<St: Stream<Item = T> + Send + 'static>(inner: St) -> Self {
+        StreamStaticWrapper { inner: Box::pin(inner) }
+    }
+}
+
+impl<T> Stream for StreamStaticWrapper<T> {
+    type Item = T;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let this = self.project();
         this.inner.poll_next(cx)
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use tokio_stream::StreamExt;
+    use super::StreamStaticWrapper;
+
+    #[tokio::test]
+    async fn wrapped_stream_items() {
+        let stream = StreamStaticWrapper::new(async_stream::stream! {
+            for i in 0..3 {
+                yield i;
+            }
+        });
+        let items: Vec<i32> = stream.collect().await;
+        assert_eq!(items, vec![0, 1, 2]);
+    }
 }
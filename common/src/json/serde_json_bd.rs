@@ -266,6 +266,26 @@ pub mod bd_with {
     }
 }
 
+/// The same as 'bd_with' but for Option<BigDecimal>.
+pub mod bd_opt_with {
+    use bigdecimal::BigDecimal;
+    use super::{ BDRefSerdeWrapper, BDSerdeWrapper };
+
+    #[inline]
+    pub fn serialize<'se,S>(bd: &Option<BigDecimal>, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+        match bd {
+            None => serializer.serialize_none(),
+            Some(ref bd) => serializer.serialize_some(&BDRefSerdeWrapper(bd)),
+        }
+    }
+
+    #[inline]
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<BigDecimal>, D::Error> where D: serde::Deserializer<'de> {
+        let bd: Option<BDSerdeWrapper> = serde::Deserialize::deserialize(deserializer) ?;
+        Ok(bd.map(|bd| bd.0))
+    }
+}
+
 
 /*
 pub fn array_init<const LEN: usize>() -> [u8; LEN] {
@@ -107,7 +107,6 @@ pub impl <
 fn validate_path_params(open_api_path: &str, open_api_path_item: &PathItem) {
     use crate::map::MapOpsExt;
 
-    // TODO: add support of '?' and query params
     let url_path_params: HashSet<_> = open_api_path.split('/')
         .filter(|s|s.starts_with('{') && s.ends_with('}'))
         .map(|s|{
@@ -121,9 +120,11 @@ fn validate_path_params(open_api_path: &str, open_api_path_item: &PathItem) {
         .1;
 
     // let annotated_path_params = path_item.parameters.as_ref()
+    // Query/header params are not the part of URL path and they are not compared.
     let annotated_path_params = op.parameters.as_ref()
         .map(|parameters|parameters
             .iter()
+            .filter(|p|p.parameter_in == ParameterIn::Path)
             .map(|p|p.name.as_str())
             .collect::<HashSet<_>>())
         .unwrap_or(HashSet::new());
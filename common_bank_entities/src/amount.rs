@@ -204,6 +204,12 @@ impl Amount {
     pub fn into_parts(self) -> AmountParts {
         AmountParts { value: self.value, currency: self.currency }
     }
+
+    /// Rounds value to minor units of currency (for example, to cents for USD).
+    pub fn round_to_minor_units(&self, rounding_mode: bigdecimal::RoundingMode) -> Amount {
+        let scale = i64::from(self.currency.minor_units());
+        Amount { value: self.value.with_scale_round(scale, rounding_mode), currency: self.currency }
+    }
 }
 
 // Just short alias (similar to kotlin style)
@@ -74,6 +74,18 @@ impl Currency {
         validate_currency(inner.as_str()) ?;
         Ok(Currency(inner))
     }
+
+    /// Count of fraction digits of minor unit (ISO 4217), for example, 2 for USD (cents), 0 for JPY.
+    /// 2 is used for currencies which are not listed there.
+    pub fn minor_units(&self) -> u32 {
+        match self.as_str() {
+            "BIF" | "CLP" | "DJF" | "GNF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG"
+            | "RWF" | "UGX" | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+            "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+            "CLF" | "UYW" => 4,
+            _ => 2,
+        }
+    }
 }
 
 impl DisplayValueExample for Currency {
@@ -53,6 +53,23 @@ fn amount_base_test() {
 }
 
 
+#[test]
+fn amount_round_to_minor_units() {
+    use bigdecimal::RoundingMode;
+
+    let amount = Amount::new(bd("10.0549999"), USD);
+    assert_eq!(amount.round_to_minor_units(RoundingMode::HalfUp), Amount::new(bd("10.05"), USD));
+    assert_eq!(amount.round_to_minor_units(RoundingMode::Up), Amount::new(bd("10.06"), USD));
+    assert_eq!(amount.round_to_minor_units(RoundingMode::Down), Amount::new(bd("10.05"), USD));
+
+    let amount = Amount::new(bd("1234.5"), make_currency!("JPY"));
+    assert_eq!(amount.round_to_minor_units(RoundingMode::HalfUp).to_test_string(), "1235 JPY");
+
+    let amount = Amount::new(bd("1.23456"), make_currency!("KWD"));
+    assert_eq!(amount.round_to_minor_units(RoundingMode::Down).to_test_string(), "1.234 KWD");
+}
+
+
 #[test]
 fn amount_should_be_immutable() {
     let amount = Amount::with_str_amount_unchecked("10.050", EUR);
@@ -128,6 +128,14 @@ fn currency_new_test() {
     assert_eq!(uah.code_as_ascii_bytes(), ['U' as u8, 'A' as u8, 'H' as u8]);
 }
 
+#[test]
+fn currency_minor_units() {
+    assert_eq!(Currency::new("USD".to_test_string()).test_unwrap().minor_units(), 2);
+    assert_eq!(Currency::new("UAH".to_test_string()).test_unwrap().minor_units(), 2);
+    assert_eq!(Currency::new("JPY".to_test_string()).test_unwrap().minor_units(), 0);
+    assert_eq!(Currency::new("KWD".to_test_string()).test_unwrap().minor_units(), 3);
+}
+
 #[test]
 #[should_panic(expected = "called `Result::unwrap()` on an `Err` value: CurrencyFormatError")]
 fn currency_with_wrong() {